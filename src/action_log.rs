@@ -0,0 +1,29 @@
+use std::collections::VecDeque;
+
+const MAX_ACTION_LOG_LEN: usize = 50;
+
+// the last actions the plugin took, shown in the debug panel to help with bug reports
+#[derive(Debug, Default)]
+pub struct ActionLog {
+    entries: VecDeque<(usize, String)>, // sequence number, description - oldest first
+    next_sequence_number: usize,
+}
+
+impl ActionLog {
+    pub fn record(&mut self, description: String) {
+        self.next_sequence_number += 1;
+        self.entries.push_back((self.next_sequence_number, description));
+        if self.entries.len() > MAX_ACTION_LOG_LEN {
+            self.entries.pop_front();
+        }
+    }
+    pub fn last_entries(&self, count: usize) -> Vec<String> {
+        // newest first
+        self.entries
+            .iter()
+            .rev()
+            .take(count)
+            .map(|(sequence_number, description)| format!("#{} {}", sequence_number, description))
+            .collect()
+    }
+}
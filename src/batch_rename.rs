@@ -0,0 +1,56 @@
+use crate::strings::{Language, Message};
+
+// context key (holding the original session name) used when renaming a session that is not the
+// current one through the cli, since the rename_session api only renames the current session
+pub const BATCH_RENAME_REQUEST: &str = "batch_rename_request";
+pub const NAME_PLACEHOLDER: &str = "$name";
+
+pub fn apply_rename_pattern(pattern: &str, session_name: &str) -> String {
+    pattern.replace(NAME_PLACEHOLDER, session_name)
+}
+
+// the outcome of renaming all the sessions matching the filter, some of the renames only finish
+// when their command returns
+#[derive(Debug, Default)]
+pub struct BatchRename {
+    pending_renames: usize,
+    renamed: Vec<String>,
+    failed: Vec<(String, String)>, // session name, reason
+}
+
+impl BatchRename {
+    pub fn add_pending(&mut self) {
+        self.pending_renames += 1;
+    }
+    pub fn record_success(&mut self, session_name: &str, new_name: &str) {
+        self.renamed
+            .push(format!("{} -> {}", session_name, new_name));
+    }
+    pub fn record_failure(&mut self, session_name: &str, reason: &str) {
+        self.failed
+            .push((session_name.to_owned(), reason.to_owned()));
+    }
+    pub fn finish_pending(&mut self) {
+        self.pending_renames = self.pending_renames.saturating_sub(1);
+    }
+    pub fn is_done(&self) -> bool {
+        self.pending_renames == 0
+    }
+    pub fn report(&self, language: Language) -> Result<String, String> {
+        let renamed_count = self.renamed.len().to_string();
+        let total_count = (self.renamed.len() + self.failed.len()).to_string();
+        if self.failed.is_empty() {
+            Ok(language.format(Message::BatchRenamed, &[&renamed_count, &total_count]))
+        } else {
+            let failed: Vec<String> = self
+                .failed
+                .iter()
+                .map(|(session_name, reason)| format!("{} ({})", session_name, reason))
+                .collect();
+            Err(language.format(
+                Message::BatchRenameFailed,
+                &[&renamed_count, &total_count, &failed.join(", ")],
+            ))
+        }
+    }
+}
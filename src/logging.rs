@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// what goes to stderr, which zellij writes to its log file - only errors unless the log_level
+// config (or debug = true) asks for more
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Info,  // the host actions we take, eg. attaching or killing a session
+    Debug, // also the events we receive and the keys we handle
+}
+
+impl LogLevel {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "error" => Some(LogLevel::Error),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            _ => None,
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+}
+
+// global rather than part of the plugin state so that every module can log, it's only set once
+// the configuration is loaded
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Error as u8);
+
+pub fn set_log_level(log_level: LogLevel) {
+    LOG_LEVEL.store(log_level as u8, Ordering::Relaxed);
+}
+
+pub fn is_enabled(log_level: LogLevel) -> bool {
+    log_level as u8 <= LOG_LEVEL.load(Ordering::Relaxed)
+}
+
+// eg. log!(LogLevel::Error, "Failed to save session order: {}", e), the message is only
+// formatted if its level is logged
+macro_rules! log {
+    ($log_level:expr, $($arg:tt)*) => {
+        if $crate::logging::is_enabled($log_level) {
+            eprintln!("zj-sesh [{}] {}", $log_level.name(), format!($($arg)*));
+        }
+    };
+}
+pub(crate) use log;
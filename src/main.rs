@@ -1,25 +1,55 @@
+mod action_log;
+mod batch_rename;
+mod logging;
 mod new_session_info;
+mod recently_killed;
 mod resurrectable_sessions;
+mod search_history;
+mod session_history;
 mod session_list;
+mod session_order;
+mod session_tags;
+mod strings;
+mod tab_picker;
 mod ui;
 use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::PathBuf;
 use uuid::Uuid;
 use zellij_tile::prelude::*;
 
-use new_session_info::NewSessionInfo;
+use action_log::ActionLog;
+use batch_rename::{apply_rename_pattern, BatchRename, BATCH_RENAME_REQUEST, NAME_PLACEHOLDER};
+use logging::{log, set_log_level, LogLevel};
+use new_session_info::{
+    generate_session_name, is_blank_session_name, strip_pasted_session_name, LayoutSort,
+    NewSessionInfo, SessionNameScheme, NEW_SESSION_INITIAL_COMMAND, NEW_SESSION_WITH_ENV_REQUEST,
+};
 use ui::{
     components::{
-        render_controls_line, render_error, render_new_session_block, render_prompt,
-        render_renaming_session_screen, render_screen_toggle, Colors,
+        parse_color, prompt_width, render_action_log, render_clear_search_hint,
+        render_command_line, render_compact_matches, render_confirmation, render_controls_line,
+        render_error, render_flash_line, render_frame, render_layout_selection_list,
+        render_new_session_block, render_notice, render_permissions_denied, render_prompt,
+        render_renaming_session_screen, render_result_position, render_screen_toggle,
+        render_scrollbar, render_session_details, result_position_width, set_home_folder, Colors,
+        ControlsConfig, ControlsVerbosity, CurrentSessionStyle, ScreenControls, ScreenLabels,
+        SESSION_DETAILS_ROWS,
     },
-    welcome_screen::{render_banner, render_welcome_boundaries},
+    welcome_screen::{cheatsheet_sections, render_banner, render_welcome_boundaries},
     SessionUiInfo,
 };
 
+use recently_killed::{KilledSession, RecentlyKilled};
 use resurrectable_sessions::ResurrectableSessions;
-use session_list::SessionList;
+use search_history::SearchHistory;
+use session_history::SessionHistory;
+use session_list::{ClientFilter, ExpansionLevel, SearchScope, SessionList, SessionSort};
+use session_tags::{is_tag_character, parse_tag_filters};
+use strings::{Language, Message};
+use tab_picker::TabPicker;
 
-#[derive(Clone, Debug, Copy, Default)]
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
 enum ActiveScreen {
     New,
     #[default]
@@ -27,45 +57,827 @@ enum ActiveScreen {
     Resurrect,
 }
 
+impl ActiveScreen {
+    fn from_config_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "new" => Some(ActiveScreen::New),
+            "attach" => Some(ActiveScreen::Attach),
+            "resurrect" => Some(ActiveScreen::Resurrect),
+            _ => None,
+        }
+    }
+    fn config_name(&self) -> &'static str {
+        match self {
+            ActiveScreen::New => "new",
+            ActiveScreen::Attach => "attach",
+            ActiveScreen::Resurrect => "resurrect",
+        }
+    }
+}
+
+// what to focus in the session we attach to
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+enum AttachFocus {
+    Selected,     // the selected tab or pane, if any
+    Tab,          // the selected tab, even if one of its panes is selected
+    SessionFocus, // whatever the session last focused
+}
+
+impl AttachFocus {
+    fn from_preserve_focus(preserve_focus: bool) -> Self {
+        if preserve_focus {
+            AttachFocus::SessionFocus
+        } else {
+            AttachFocus::Selected
+        }
+    }
+}
+
+// what Enter does on the current session, which we can't attach to again
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+enum CurrentSessionEnter {
+    #[default]
+    Focus, // the selected tab or pane, or back to where we were
+    Hide, // only hide the plugin
+    Noop, // say that we're already attached and stay open
+}
+
+impl CurrentSessionEnter {
+    fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "focus" => Some(CurrentSessionEnter::Focus),
+            "hide" => Some(CurrentSessionEnter::Hide),
+            "noop" => Some(CurrentSessionEnter::Noop),
+            _ => None,
+        }
+    }
+}
+
+// what Esc does on the Attach screen: it backs out of renaming, then clears the search and only
+// hides once there is nothing left to clear (the welcome screen is never hidden)
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+enum AttachEsc {
+    StopRenaming,
+    ClearSearch,
+    Hide,
+    Nothing,
+}
+
+impl AttachEsc {
+    fn decide(is_renaming: bool, search_term: &str, is_welcome_screen: bool) -> Self {
+        if is_renaming {
+            AttachEsc::StopRenaming
+        } else if !search_term.is_empty() {
+            AttachEsc::ClearSearch
+        } else if !is_welcome_screen {
+            AttachEsc::Hide
+        } else {
+            AttachEsc::Nothing
+        }
+    }
+}
+
+// what was typed into a screen's search, kept per screen so that switching to another screen and
+// back finds it as it was left
+#[derive(Debug, Default)]
+struct ScreenSearch {
+    term: String,
+    cursor: usize, // in characters of the term, not bytes
+}
+
+impl ScreenSearch {
+    fn char_count(&self) -> usize {
+        self.term.chars().count()
+    }
+    fn byte_index(&self, char_index: usize) -> usize {
+        // so that multibyte characters (eg. emoji or CJK) are never split, positions past the
+        // end are the end
+        self.term
+            .char_indices()
+            .nth(char_index)
+            .map(|(i, _)| i)
+            .unwrap_or(self.term.len())
+    }
+    fn move_cursor_to_end(&mut self) {
+        self.cursor = self.char_count();
+    }
+    // the edits work in characters and only index the term through byte_index, each returns
+    // whether the term changed
+    fn insert(&mut self, text: &str) -> bool {
+        let cursor_byte_index = self.byte_index(self.cursor);
+        self.term.insert_str(cursor_byte_index, text);
+        self.cursor = self.cursor.min(self.char_count()) + text.chars().count();
+        !text.is_empty()
+    }
+    fn delete_char_backward(&mut self) -> bool {
+        if self.cursor == 0 || self.cursor > self.char_count() {
+            return false;
+        }
+        self.cursor -= 1;
+        let cursor_byte_index = self.byte_index(self.cursor);
+        self.term.remove(cursor_byte_index);
+        true
+    }
+    fn delete_char_forward(&mut self) -> bool {
+        if self.cursor >= self.char_count() {
+            return false;
+        }
+        let cursor_byte_index = self.byte_index(self.cursor);
+        self.term.remove(cursor_byte_index);
+        true
+    }
+    fn kill_to_end(&mut self) -> bool {
+        let cursor_byte_index = self.byte_index(self.cursor);
+        let changed = cursor_byte_index < self.term.len();
+        self.term.truncate(cursor_byte_index);
+        changed
+    }
+    fn delete_word_backward(&mut self) -> bool {
+        let chars: Vec<char> = self.term.chars().collect();
+        let cursor = self.cursor.min(chars.len());
+        let mut word_start = cursor;
+        while word_start > 0 && chars[word_start - 1].is_whitespace() {
+            word_start -= 1;
+        }
+        while word_start > 0 && !chars[word_start - 1].is_whitespace() {
+            word_start -= 1;
+        }
+        let drain_start = self.byte_index(word_start);
+        let drain_end = self.byte_index(cursor);
+        self.term.drain(drain_start..drain_end);
+        self.cursor = word_start;
+        word_start < cursor
+    }
+    fn delete_word_forward(&mut self) -> bool {
+        let chars: Vec<char> = self.term.chars().collect();
+        let cursor = self.cursor.min(chars.len());
+        let mut word_end = cursor;
+        while word_end < chars.len() && chars[word_end].is_whitespace() {
+            word_end += 1;
+        }
+        while word_end < chars.len() && !chars[word_end].is_whitespace() {
+            word_end += 1;
+        }
+        let drain_start = self.byte_index(cursor);
+        let drain_end = self.byte_index(word_end);
+        self.term.drain(drain_start..drain_end);
+        cursor < word_end
+    }
+    fn clear(&mut self) {
+        self.term.clear();
+        self.cursor = 0;
+    }
+}
+
+// a y/n question shown instead of the session list, the action runs once it's answered with y
+#[derive(Clone, Debug)]
+enum PendingConfirmation {
+    KillAllSessions(Vec<String>), // all the other sessions, the ones the warning counts
+    KillSessions(Vec<String>),    // the sessions of the selection range
+    KillSession(String),
+    ForceKillSession(String),
+    DisconnectOthers,
+    AutoAttach(String), // the first time auto_attach_single would attach, to this session
+    DiscardNewSession,  // Esc on the New screen with something filled in
+    ResurrectWithLayout(String, LayoutInfo), // deletes the stored layout of the session
+    OverwriteLayoutFile(String, String), // the file name and the layout to write to it
+}
+
+impl PendingConfirmation {
+    fn title(&self, language: Language) -> (String, Vec<Range<usize>>) {
+        match self {
+            PendingConfirmation::KillAllSessions(session_names)
+            | PendingConfirmation::KillSessions(session_names) => language.format_with_ranges(
+                Message::KillSessionsWarning,
+                &[&session_names.len().to_string()],
+            ),
+            PendingConfirmation::KillSession(session_name) => {
+                language.format_with_ranges(Message::KillSessionWarning, &[session_name])
+            }
+            PendingConfirmation::ForceKillSession(session_name) => {
+                language.format_with_ranges(Message::ForceKillSessionWarning, &[session_name])
+            }
+            PendingConfirmation::DisconnectOthers => language.format_with_ranges(
+                Message::DisconnectOthersWarning,
+                &[language.text(Message::Disconnect)],
+            ),
+            PendingConfirmation::AutoAttach(session_name) => {
+                language.format_with_ranges(Message::AutoAttachWarning, &[session_name])
+            }
+            PendingConfirmation::DiscardNewSession => {
+                language.format_with_ranges(Message::DiscardNewSessionWarning, &[])
+            }
+            PendingConfirmation::ResurrectWithLayout(session_name, _) => {
+                language.format_with_ranges(Message::ResurrectWithLayoutWarning, &[session_name])
+            }
+            PendingConfirmation::OverwriteLayoutFile(file_name, _) => {
+                language.format_with_ranges(Message::OverwriteLayoutFileWarning, &[file_name])
+            }
+        }
+    }
+}
+
+// an action whose outcome only shows in a later session update, a spinner is shown in the footer
+// until then (or until PENDING_OPERATION_TIMEOUT_SECS) - attaching is not one of them, the
+// session we switch to never becomes the current session of this instance
+#[derive(Clone, Debug)]
+enum PendingOperation {
+    Creating(String),
+    Killing(Vec<String>),
+    Renaming(String), // the current session, to this name
+}
+
+const SPINNER_INTERVAL_SECS: f64 = 0.5; // well above the flash timer, so the two are told apart
+const SPINNER_FRAMES: [char; 4] = ['◐', '◓', '◑', '◒'];
+const PENDING_OPERATION_TIMEOUT_SECS: f64 = 10.0;
+
+impl PendingOperation {
+    fn is_done(&self, session_infos: &[SessionInfo]) -> bool {
+        let is_listed = |session_name: &str| session_infos.iter().any(|s| s.name == session_name);
+        match self {
+            PendingOperation::Creating(session_name) => is_listed(session_name),
+            PendingOperation::Killing(session_names) => !session_names.iter().any(|s| is_listed(s)),
+            PendingOperation::Renaming(new_name) => session_infos
+                .iter()
+                .any(|s| s.is_current_session && &s.name == new_name),
+        }
+    }
+    fn description(&self, language: Language) -> String {
+        match self {
+            PendingOperation::Creating(session_name) => {
+                language.format(Message::CreatingSession, &[session_name])
+            }
+            PendingOperation::Killing(session_names) => language.format(
+                Message::KillingSessions,
+                &[&session_names.len().to_string()],
+            ),
+            PendingOperation::Renaming(new_name) => {
+                language.format(Message::RenamingSession, &[new_name])
+            }
+        }
+    }
+    fn completion(&self, language: Language) -> String {
+        match self {
+            PendingOperation::Creating(session_name) => {
+                language.format(Message::CreatedSession, &[session_name])
+            }
+            PendingOperation::Killing(session_names) => {
+                language.format(Message::KilledSessions, &[&session_names.len().to_string()])
+            }
+            PendingOperation::Renaming(new_name) => {
+                language.format(Message::RenamedSession, &[new_name])
+            }
+        }
+    }
+}
+
+// shared between all instances of this plugin, so this is the screen last used in any session
+const ACTIVE_SCREEN_FILE: &str = "/cache/active_screen";
+
+fn load_active_screen() -> Option<ActiveScreen> {
+    std::fs::read_to_string(ACTIVE_SCREEN_FILE)
+        .ok()
+        .and_then(|name| ActiveScreen::from_config_name(&name))
+}
+
+fn save_active_screen(active_screen: ActiveScreen) {
+    // best effort, we fall back to the default screen if this is not available
+    let _ = std::fs::write(ACTIVE_SCREEN_FILE, active_screen.config_name());
+}
+
+// exists once auto_attach_single was confirmed, so that it is only asked about the first time
+const AUTO_ATTACH_CONFIRMED_FILE: &str = "/cache/auto_attach_confirmed";
+
 #[derive(Default)]
 struct State {
     session_name: Option<String>,
     sessions: SessionList,
     resurrectable_sessions: ResurrectableSessions,
-    search_term: String,
-    search_cursor: usize, // Cursor position in search term
+    recently_killed: RecentlyKilled,
+    resurrect_layout_picker: Option<String>, // the session to resurrect with the picked layout
+    attach_layout_picker: Option<String>,    // the session to attach to with the picked layout
+    tab_picker: Option<TabPicker>,
+    attach_search: ScreenSearch, // the New and Resurrect screens keep theirs themselves
+    initial_search_pending: bool, // the configured initial_search waits for the first sessions
     new_session_info: NewSessionInfo,
     renaming_session_name: Option<String>,
+    batch_rename_pattern: Option<String>, // eg. prod-$name, applied to all filtered sessions
+    batch_rename: Option<BatchRename>,
+    tagging_session: Option<(String, String)>, // session name, the tag to add or remove
+    command_line: Option<String>, // what was typed after : on the Attach screen, eg. kill prod
+    pending_count: Option<usize>, // typed with Ctrl+Alt+digits, moves the selection that many rows
     error: Option<String>,
+    notice: Option<String>,
+    current_layout_signature: Option<(usize, usize)>, // tab count, pane count
     active_screen: ActiveScreen,
+    enabled_screens: Vec<ActiveScreen>,
     colors: Colors,
     is_welcome_screen: bool,
-    show_kill_all_sessions_warning: bool,
+    pending_confirmation: Option<PendingConfirmation>,
+    pending_operation: Option<PendingOperation>,
+    pending_operation_secs: f64, // how long we have been waiting for it
+    notify: bool,                // send a desktop notification once it's done
+    listed_new_session_folder: Option<PathBuf>, // the folder whose files the layouts were fit to
+    previous_session: Option<String>, // the live session attached to before this one
+    spinner_timer_running: bool,
+    confirm_single_kill: bool,
+    auto_attach_single: bool, // attach as soon as the search narrows the list to one session
+    confirm_disconnect_others: bool,
     request_ids: Vec<String>,
     is_web_client: bool,
+    is_compact: bool,
+    show_frame: bool,
+    show_session_details: bool, // a footer with the details of the selected session
+    session_history: SessionHistory,
+    search_history: SearchHistory,
+    feedback_flash: bool,
+    flash_until_render: usize, // renders left with the controls line inverted
+    auto_hide_secs: u64,       // hide after this long without a key, 0 disables it
+    auto_hide_timers: usize,   // started and not yet elapsed, only the last one hides us
+    force_redraw: bool,
+    remote_url: Option<String>,
+    remote_sessions: bool, // list the sessions piped in from the remote_url host to web clients
+    remote_session_names: Vec<String>, // as last piped in
+    post_create_command: Option<String>, // run with sh -c after creating a session
+    post_attach_command: Option<String>, // run with sh -c after attaching to a session
+    copy_command: Option<String>, // reads the text to copy from stdin, eg. wl-copy
+    default_new_session_cwd: Option<PathBuf>, // the New screen's folder unless one is picked
+    unexpanded_default_new_session_cwd: Option<String>, // until we got the variables it uses
+    event_plugin: Option<String>, // url or alias of the plugin we pipe attach/kill events to
+    run_commands: bool,    // the features that shell out, each needs the RunCommands permission
+    web_sharing: bool,     // toggling web sharing needs the StartWebServer permission
+    home_folder_requested: bool,
+    permissions_denied: bool,
+    is_debug: bool,
+    preserve_focus_on_attach: bool, // what Enter does, Alt+Enter does the opposite
+    current_session_enter: CurrentSessionEnter,
+    session_name_scheme: SessionNameScheme,
+    hide_current_session: bool, // selecting it otherwise returns to its focused pane
+    controls: ControlsConfig,
+    screen_labels: ScreenLabels,
+    welcome_cheatsheet: bool, // the top actions of each screen, next to the welcome screen's menu
+    language: Language,
+    trim_session_names: bool, // drop leading and trailing whitespace from new and renamed names
+    carry_search_to_new: bool, // the Attach search becomes the New screen's session name
+    confirm_discard_new: bool, // before Esc hides the New screen with something filled in
+    show_action_log: bool,
+    action_log: ActionLog,
 }
 
 register_plugin!(State);
 
+// context key used to chain the host commands that resurrect a session under a new name
+const RESURRECT_RENAME_STEP: &str = "resurrect_rename_step";
+// context key (holding the session name) of the configured post_create_command, which gets the
+// name of the new session in this env variable
+const POST_CREATE_COMMAND_REQUEST: &str = "post_create_command_request";
+const POST_CREATE_SESSION_NAME_ENV_VARIABLE: &str = "ZJ_SESH_SESSION_NAME";
+// context key (holding the session name) of the configured post_attach_command, which gets the
+// name of the session we attached to as $SESSION_NAME (and like post_create_command as
+// $ZJ_SESH_SESSION_NAME) - an env variable rather than pasted into the command, which the shell
+// would run
+const POST_ATTACH_COMMAND_REQUEST: &str = "post_attach_command_request";
+const POST_ATTACH_SESSION_NAME_ENV_VARIABLE: &str = "SESSION_NAME";
+// context key (holding the session name) of the command opening the initial command of a new
+// session in a new pane, we switch to the session once it returns
+const INITIAL_COMMAND_REQUEST: &str = "initial_command_request";
+// context key (holding the copied text) of the command copying a session's attach command, there
+// is no clipboard api for plugins so we pipe it to a clipboard command on the host
+const COPY_ATTACH_COMMAND_REQUEST: &str = "copy_attach_command_request";
+const COPY_TEXT_ENV_VARIABLE: &str = "ZJ_SESH_COPY_TEXT";
+const DEFAULT_COPY_COMMAND: &str = "if command -v pbcopy >/dev/null; then pbcopy; \
+    elif command -v wl-copy >/dev/null; then wl-copy; \
+    elif command -v xclip >/dev/null; then xclip -selection clipboard; \
+    else echo 'no clipboard command found, set copy_command' >&2; exit 1; fi";
+// context key of the desktop notification sent when an operation is done (with notify = true),
+// there is no notification api for plugins so we try the host's notification commands - if it has
+// none this does nothing
+const NOTIFY_REQUEST: &str = "notify_request";
+const NOTIFY_TEXT_ENV_VARIABLE: &str = "ZJ_SESH_NOTIFY_TEXT";
+const NOTIFY_COMMAND: &str = "if command -v notify-send >/dev/null; then \
+    notify-send zj-sesh \"$ZJ_SESH_NOTIFY_TEXT\"; \
+    elif command -v osascript >/dev/null; then osascript -e 'on run argv' \
+    -e 'display notification (item 1 of argv) with title \"zj-sesh\"' -e 'end run' \
+    \"$ZJ_SESH_NOTIFY_TEXT\"; fi";
+// context key (holding the session name) used when dumping a session layout to find its cwd
+const SESSION_CWD_REQUEST: &str = "session_cwd_request";
+// context key used when asking the shell for the home folder, which paths are shown relative to
+const HOME_FOLDER_REQUEST: &str = "home_folder_request";
+// context key (holding the unexpanded cwd) used when asking for the environment variables that
+// the ~ and $NAME of the default_new_session_cwd config expand to, the plugin does not see the
+// host's environment
+const DEFAULT_CWD_REQUEST: &str = "default_cwd_request";
+// prints the value of each variable given as an argument followed by a nul, nothing is evaluated
+const PRINT_ENV_VARIABLES_COMMAND: &str = "for name do printenv \"$name\"; printf '\\0'; done";
+// context key (holding the folder) used when listing the new session folder to tell its kind of
+// project, which brings the layouts fitting it first - the plugin can only read its own cwd
+const FOLDER_FILES_REQUEST: &str = "folder_files_request";
+// context key used when asking git for the repo root of the new session folder (or the plugin's
+// cwd), which then becomes the new session folder
+const GIT_ROOT_REQUEST: &str = "git_root_request";
+
+fn shell_quote(text: &str) -> String {
+    if !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.:@".contains(c))
+    {
+        text.to_owned()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+fn cwd_variable_names(cwd: &str) -> Vec<String> {
+    // the environment variables that expand_cwd needs, a leading ~ is $HOME
+    let mut variable_names = vec![];
+    if cwd == "~" || cwd.starts_with("~/") {
+        variable_names.push("HOME".to_owned());
+    }
+    let mut rest = cwd;
+    while let Some(dollar_index) = rest.find('$') {
+        let (variable_name, after) = parse_variable_name(&rest[dollar_index + 1..]);
+        if let Some(variable_name) = variable_name {
+            if !variable_names.iter().any(|v| v == variable_name) {
+                variable_names.push(variable_name.to_owned());
+            }
+        }
+        rest = after;
+    }
+    variable_names
+}
+
+fn expand_cwd(cwd: &str, variables: &BTreeMap<String, String>) -> String {
+    // like the shell expands a leading ~ and $NAME or ${NAME}, unset variables are empty - a $
+    // that does not start a variable name is kept as is
+    let value_of = |name: &str| variables.get(name).cloned().unwrap_or_default();
+    let mut expanded = String::new();
+    let mut rest = cwd;
+    if cwd == "~" || cwd.starts_with("~/") {
+        expanded.push_str(&value_of("HOME"));
+        rest = &cwd[1..];
+    }
+    while let Some(dollar_index) = rest.find('$') {
+        expanded.push_str(&rest[..dollar_index]);
+        let (variable_name, after) = parse_variable_name(&rest[dollar_index + 1..]);
+        match variable_name {
+            Some(variable_name) => expanded.push_str(&value_of(variable_name)),
+            None => expanded.push('$'),
+        }
+        rest = after;
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn parse_variable_name(text: &str) -> (Option<&str>, &str) {
+    // the NAME or {NAME} at the start of text (right after a $) and what comes after it
+    let is_name = |name: &str| {
+        name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    if let Some(braced) = text.strip_prefix('{') {
+        if let Some(closing_index) = braced.find('}') {
+            let name = &braced[..closing_index];
+            if is_name(name) {
+                return (Some(name), &braced[closing_index + 1..]);
+            }
+        }
+        return (None, text);
+    }
+    let name_len = text
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .unwrap_or(text.len());
+    let name = &text[..name_len];
+    if is_name(name) {
+        (Some(name), &text[name_len..])
+    } else {
+        (None, text)
+    }
+}
+
+fn cwd_from_layout(layout: &str) -> Option<String> {
+    // the session's cwd is a `cwd "/path"` node right inside the `layout { }` block, tabs and
+    // panes nested deeper can have their own
+    let mut depth = 0;
+    for line in layout.lines() {
+        let line = line.trim();
+        if depth == 1 {
+            if let Some(cwd) = line
+                .strip_prefix("cwd \"")
+                .and_then(|rest| rest.strip_suffix('"'))
+            {
+                return Some(cwd.to_owned());
+            }
+        }
+        depth += line.matches('{').count();
+        depth = depth.saturating_sub(line.matches('}').count());
+    }
+    None
+}
+
 impl ZellijPlugin for State {
     fn load(&mut self, configuration: BTreeMap<String, String>) {
         self.is_welcome_screen = configuration
             .get("welcome_screen")
             .map(|v| v == "true")
             .unwrap_or(false);
-        if self.is_welcome_screen {
+        self.enabled_screens = configuration
+            .get("screens")
+            .map(|v| {
+                let mut screens = vec![];
+                for screen in v.split(',').filter_map(ActiveScreen::from_config_name) {
+                    if !screens.contains(&screen) {
+                        screens.push(screen);
+                    }
+                }
+                screens
+            })
+            .unwrap_or_default();
+        if let Some(first_screen) = self.enabled_screens.first() {
+            self.active_screen = *first_screen;
+        } else {
+            self.enabled_screens = vec![
+                ActiveScreen::New,
+                ActiveScreen::Attach,
+                ActiveScreen::Resurrect,
+            ];
+        }
+        if let Some(saved_active_screen) =
+            load_active_screen().filter(|s| self.enabled_screens.contains(s))
+        {
+            self.active_screen = saved_active_screen;
+        }
+        if self.is_welcome_screen && self.enabled_screens.contains(&ActiveScreen::New) {
             self.active_screen = ActiveScreen::New;
         }
-        request_permission(&[
+        if let Some(search_scope) = configuration
+            .get("search_scope")
+            .and_then(|v| SearchScope::from_config(v))
+        {
+            self.sessions.search_scope = search_scope;
+        }
+        self.post_create_command = configuration
+            .get("post_create_command")
+            .filter(|c| !c.trim().is_empty())
+            .cloned();
+        self.post_attach_command = configuration
+            .get("post_attach_command")
+            .filter(|c| !c.trim().is_empty())
+            .cloned();
+        self.copy_command = configuration
+            .get("copy_command")
+            .filter(|c| !c.trim().is_empty())
+            .cloned();
+        self.notify = configuration
+            .get("notify")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.event_plugin = configuration
+            .get("event_plugin")
+            .filter(|p| !p.trim().is_empty())
+            .cloned();
+        self.confirm_single_kill = configuration
+            .get("confirm_single_kill")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.show_session_details = configuration
+            .get("session_details")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.show_frame = configuration
+            .get("frame")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        if let Some(client_filter) = configuration
+            .get("client_filter")
+            .and_then(|v| ClientFilter::from_config(v))
+        {
+            self.sessions.client_filter = client_filter;
+            // applied like the initial search, once the first sessions arrive
+            self.initial_search_pending = client_filter != ClientFilter::All;
+        }
+        if let Some(sort) = configuration
+            .get("sort")
+            .and_then(|v| SessionSort::from_config(v))
+        {
+            self.sessions.sort = sort;
+        }
+        if let Some(initial_search) = configuration
+            .get("initial_search")
+            .filter(|v| !v.is_empty())
+        {
+            self.attach_search.term = initial_search.to_owned();
+            self.attach_search.move_cursor_to_end();
+            self.initial_search_pending = true;
+        }
+        self.hide_current_session = configuration
+            .get("show_current_session")
+            .map(|v| v == "false")
+            .unwrap_or(false);
+        self.auto_attach_single = configuration
+            .get("auto_attach_single")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        if let Some(default_new_session_cwd) = configuration
+            .get("default_new_session_cwd")
+            .filter(|v| !v.is_empty())
+        {
+            if default_new_session_cwd.starts_with('~') || default_new_session_cwd.contains('$') {
+                // expanded once we get to run commands, see expand_default_new_session_cwd
+                self.unexpanded_default_new_session_cwd = Some(default_new_session_cwd.to_owned());
+            } else {
+                self.default_new_session_cwd = Some(PathBuf::from(default_new_session_cwd));
+                self.apply_default_new_session_cwd();
+            }
+        }
+        self.welcome_cheatsheet = configuration
+            .get("welcome_cheatsheet")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.resurrectable_sessions.deleting_disabled = configuration
+            .get("allow_delete_resurrectable")
+            .map(|v| v == "false")
+            .unwrap_or(false);
+        if let Some(verbosity) = configuration
+            .get("controls")
+            .and_then(|v| ControlsVerbosity::from_config(v))
+        {
+            self.controls.verbosity = verbosity;
+        }
+        self.controls.hints = configuration.get("controls_hints").map(|v| {
+            v.split(',')
+                .map(|hint| hint.trim().to_owned())
+                .filter(|hint| !hint.is_empty())
+                .collect()
+        });
+        if let Some(language) = configuration
+            .get("lang")
+            .and_then(|v| Language::from_config(v))
+        {
+            self.language = language;
+            self.resurrectable_sessions.language = language;
+            self.new_session_info.language = language;
+        }
+        let screen_label = |key: &str| {
+            configuration
+                .get(key)
+                .filter(|l| !l.trim().is_empty())
+                .cloned()
+        };
+        self.screen_labels = ScreenLabels {
+            new: screen_label("label_new"),
+            attach: screen_label("label_attach"),
+            resurrect: screen_label("label_resurrect"),
+        };
+        self.sessions.max_results = configuration
+            .get("max_results")
+            .and_then(|v| v.parse().ok());
+        self.sessions.show_relative_line_numbers = configuration
+            .get("relative_line_numbers")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.sessions.show_activity = configuration
+            .get("show_activity")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        if let Some(current_session_style) = configuration
+            .get("current_session_style")
+            .and_then(|v| CurrentSessionStyle::from_config(v))
+        {
+            self.colors = self
+                .colors
+                .with_current_session_style(current_session_style);
+        }
+        self.colors = self.colors.with_match_colors(
+            configuration.get("match_fg").and_then(|c| parse_color(c)),
+            configuration.get("match_bg").and_then(|c| parse_color(c)),
+        );
+        self.confirm_disconnect_others = configuration
+            .get("confirm_disconnect")
+            .map(|v| v != "false")
+            .unwrap_or(true);
+        self.is_compact = configuration
+            .get("compact")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.remote_url = configuration
+            .get("remote_url")
+            .map(|v| v.trim_end_matches('/').to_owned());
+        self.remote_sessions = self.remote_url.is_some()
+            && configuration
+                .get("remote_sessions")
+                .map(|v| v == "true")
+                .unwrap_or(false);
+        self.sessions.remote_host = self.remote_url.as_ref().and_then(|remote_url| {
+            let without_scheme = remote_url.split("://").last().unwrap_or(remote_url);
+            without_scheme.split('/').next().map(|host| host.to_owned())
+        });
+        if let Some(layout_sort) = configuration
+            .get("layout_sort")
+            .and_then(|v| LayoutSort::from_config(v))
+        {
+            self.new_session_info.set_layout_sort(layout_sort);
+        }
+        if let Some(session_name_scheme) = configuration
+            .get("session_name_scheme")
+            .and_then(|v| SessionNameScheme::from_config(v))
+        {
+            self.session_name_scheme = session_name_scheme;
+        }
+        self.preserve_focus_on_attach = configuration
+            .get("enter_preserves_focus")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        if let Some(current_session_enter) = configuration
+            .get("current_session_enter")
+            .and_then(|v| CurrentSessionEnter::from_config(v))
+        {
+            self.current_session_enter = current_session_enter;
+        }
+        self.trim_session_names = configuration
+            .get("trim_session_names")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.carry_search_to_new = configuration
+            .get("carry_search_to_new")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.confirm_discard_new = configuration
+            .get("confirm_discard_new")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.is_debug = configuration
+            .get("debug")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        let log_level = configuration
+            .get("log_level")
+            .and_then(|v| LogLevel::from_config(v))
+            .unwrap_or(if self.is_debug {
+                LogLevel::Debug
+            } else {
+                LogLevel::Error
+            });
+        set_log_level(log_level);
+        self.feedback_flash = configuration
+            .get("feedback_flash")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        self.auto_hide_secs = configuration
+            .get("auto_hide_secs")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        if self.is_compact {
+            // the compact mode only has room for the attach search
+            self.enabled_screens = vec![ActiveScreen::Attach];
+            self.active_screen = ActiveScreen::Attach;
+        }
+        // configuring a command implies that commands may be run
+        self.run_commands = configuration
+            .get("run_commands")
+            .map(|v| v == "true")
+            .unwrap_or(false)
+            || self.post_create_command.is_some()
+            || self.post_attach_command.is_some()
+            || self.copy_command.is_some()
+            || self.unexpanded_default_new_session_cwd.is_some()
+            || self.notify;
+        self.web_sharing = configuration
+            .get("web_sharing")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        // only what listing and managing sessions needs, the rest is asked for when the feature
+        // that needs it is configured
+        let mut permissions = vec![
             PermissionType::ReadApplicationState,
             PermissionType::ChangeApplicationState,
-        ]);
+        ];
+        if self.run_commands {
+            permissions.push(PermissionType::RunCommands);
+        }
+        if self.remote_sessions {
+            // the remote sessions are piped in from the cli
+            permissions.push(PermissionType::ReadCliPipes);
+        }
+        if self.web_sharing {
+            permissions.push(PermissionType::StartWebServer);
+        }
+        if self.event_plugin.is_some() {
+            permissions.push(PermissionType::MessageAndLaunchOtherPlugins);
+        }
+        request_permission(&permissions);
         subscribe(&[
             EventType::ModeUpdate,
             EventType::SessionUpdate,
             EventType::Key,
             EventType::RunCommandResult,
+            EventType::CustomMessage,
+            EventType::Timer,
+            EventType::Visible,
+            EventType::PastedText,
         ]);
+        self.restart_auto_hide_timer();
     }
 
     fn pipe(&mut self, pipe_message: PipeMessage) -> bool {
@@ -77,42 +889,144 @@ impl ZellijPlugin for State {
                     Some(request_id_position) => {
                         self.request_ids.remove(request_id_position);
                         let new_session_folder = std::path::PathBuf::from(payload);
+                        self.new_session_info
+                            .prefill_name_from_folder(&new_session_folder);
+                        self.new_session_info.last_picked_folder = Some(new_session_folder.clone());
                         self.new_session_info.new_session_folder = Some(new_session_folder);
+                        self.list_new_session_folder();
                     }
                     None => {
-                        eprintln!("request id not found");
+                        log!(
+                            LogLevel::Error,
+                            "file picker result for unknown request id {}",
+                            request_id
+                        );
                     }
                 }
             }
             true
+        } else if pipe_message.name == "attach_mru" {
+            self.attach_to_most_recent_session(&pipe_message.source);
+            false
+        } else if pipe_message.name == "remote_sessions" {
+            // eg. zellij pipe --name remote_sessions -- "$(ssh my-host zellij list-sessions -s)"
+            if !self.remote_sessions {
+                return false;
+            }
+            self.remote_session_names = pipe_message
+                .payload
+                .unwrap_or_default()
+                .lines()
+                .map(|l| l.trim().to_owned())
+                .filter(|l| !l.is_empty())
+                .collect();
+            self.update_remote_sessions();
+            true
         } else {
             false
         }
     }
     fn update(&mut self, event: Event) -> bool {
         let mut should_render = false;
+        // only the kind of event, some carry all sessions
+        log!(
+            LogLevel::Debug,
+            "event {}",
+            format!("{:?}", event)
+                .split(['(', ' '])
+                .next()
+                .unwrap_or_default()
+        );
         match event {
             Event::ModeUpdate(mode_info) => {
-                self.colors = Colors::new(mode_info.style.colors);
-                self.is_web_client = mode_info.is_web_client.unwrap_or(false);
+                self.colors = Colors::new(mode_info.style.colors)
+                    .with_match_colors(self.colors.match_fg, self.colors.match_bg)
+                    .with_current_session_style(self.colors.current_session_style);
+                let is_web_client = mode_info.is_web_client.unwrap_or(false);
+                if is_web_client != self.is_web_client {
+                    self.is_web_client = is_web_client;
+                    self.update_remote_sessions();
+                }
                 should_render = true;
             }
             Event::Key(key) => {
+                self.restart_auto_hide_timer();
                 should_render = self.handle_key(key);
             }
-            Event::PermissionRequestResult(_result) => {
+            Event::PastedText(text) => {
+                should_render = self.handle_paste(text);
+            }
+            Event::Visible(true) => {
+                self.restart_auto_hide_timer();
+            }
+            Event::RunCommandResult(exit_code, stdout, stderr, context) => {
+                self.handle_run_command_result(exit_code, stdout, stderr, context);
+                should_render = true;
+            }
+            Event::PermissionRequestResult(result) => {
+                self.permissions_denied = result == PermissionStatus::Denied;
+                should_render = true;
+            }
+            Event::Timer(elapsed) => {
+                if self.is_auto_hide_timer(elapsed) {
+                    self.auto_hide_timers = self.auto_hide_timers.saturating_sub(1);
+                    if self.auto_hide_timers == 0 {
+                        hide_self();
+                    }
+                }
+                // ends a feedback flash
+                should_render = self.flash_until_render > 0;
+                if self.spinner_timer_running
+                    && !self.is_auto_hide_timer(elapsed)
+                    && elapsed >= SPINNER_INTERVAL_SECS
+                {
+                    self.spinner_timer_running = false;
+                    if self.pending_operation.is_some() {
+                        self.pending_operation_secs += elapsed;
+                        if self.pending_operation_secs >= PENDING_OPERATION_TIMEOUT_SECS {
+                            self.pending_operation = None;
+                        } else {
+                            self.start_spinner_timer();
+                        }
+                        should_render = true;
+                    }
+                }
+            }
+            Event::CustomMessage(message, payload) if message == "session_layout" => {
+                self.new_session_info.update_current_session_layout(payload);
                 should_render = true;
             }
             Event::SessionUpdate(session_infos, resurrectable_session_list) => {
+                if self
+                    .pending_operation
+                    .as_ref()
+                    .map(|p| p.is_done(&session_infos))
+                    .unwrap_or(false)
+                {
+                    if let Some(pending_operation) = self.pending_operation.take() {
+                        self.send_notification(&pending_operation.completion(self.language));
+                    }
+                }
                 for session_info in &session_infos {
                     if session_info.is_current_session {
                         self.new_session_info
                             .update_layout_list(session_info.available_layouts.clone());
                     }
                 }
+                self.sessions.set_resurrectable_session_names(
+                    resurrectable_session_list
+                        .iter()
+                        .map(|(name, _)| name.clone())
+                        .collect(),
+                );
                 self.resurrectable_sessions
                     .update(resurrectable_session_list);
                 self.update_session_infos(session_infos);
+                if self.initial_search_pending {
+                    self.initial_search_pending = false;
+                    self.sessions
+                        .update_search_term(&self.attach_search.term, &self.colors);
+                }
                 should_render = true;
             }
             _ => (),
@@ -121,6 +1035,19 @@ impl ZellijPlugin for State {
     }
 
     fn render(&mut self, rows: usize, cols: usize) {
+        if self.force_redraw {
+            // clear any stale glyphs before reprinting everything
+            print!("\u{1b}[H\u{1b}[2J");
+            self.force_redraw = false;
+        }
+        if self.permissions_denied {
+            render_permissions_denied(self.language, !self.is_welcome_screen, rows, cols);
+            return;
+        }
+        if self.is_compact {
+            self.render_compact(rows, cols);
+            return;
+        }
         let (x, y, width, height) = self.main_menu_size(rows, cols);
 
         let background = self.colors.palette.text_unselected.background;
@@ -130,6 +1057,8 @@ impl ZellijPlugin for State {
         }
         render_screen_toggle(
             self.active_screen,
+            &self.enabled_screens,
+            &self.screen_labels,
             x,
             y,
             width.saturating_sub(2),
@@ -138,51 +1067,292 @@ impl ZellijPlugin for State {
 
         match self.active_screen {
             ActiveScreen::New => {
-                render_new_session_block(
-                    &self.new_session_info,
-                    self.colors,
-                    height.saturating_sub(2),
-                    width,
-                    x,
-                    y + 2,
-                );
+                if let Some(pending_confirmation) = &self.pending_confirmation {
+                    let (title, highlighted_ranges) = pending_confirmation.title(self.language);
+                    render_confirmation(
+                        &title,
+                        highlighted_ranges,
+                        self.language.text(Message::AreYouSure),
+                        height,
+                        width,
+                        x,
+                        y,
+                    );
+                } else {
+                    render_new_session_block(
+                        self.language,
+                        &self.new_session_info,
+                        self.colors,
+                        height.saturating_sub(2),
+                        width,
+                        x,
+                        y + 2,
+                    );
+                }
             }
             ActiveScreen::Attach => {
-                if let Some(new_session_name) = &self.renaming_session_name {
-                    render_renaming_session_screen(&new_session_name, height, width, x, y + 2);
-                } else if self.show_kill_all_sessions_warning {
-                    self.render_kill_all_sessions_warning(height, width, x, y);
+                if let Some(batch_rename_pattern) = &self.batch_rename_pattern {
+                    render_renaming_session_screen(
+                        self.language,
+                        &self.language.format(
+                            Message::RenameMatchingSessions,
+                            &[
+                                &self.sessions.filtered_session_names().len().to_string(),
+                                NAME_PLACEHOLDER,
+                            ],
+                        ),
+                        batch_rename_pattern,
+                        height,
+                        width,
+                        x,
+                        y + 2,
+                    );
+                } else if let Some(command_line) = &self.command_line {
+                    render_command_line(command_line, width, x, y + 2);
+                } else if let Some(tab_picker) = &self.tab_picker {
+                    tab_picker.render(self.language, height, width, x, y);
+                } else if let Some(session_name) = &self.attach_layout_picker {
+                    let (title, session_name_ranges) = self
+                        .language
+                        .format_with_ranges(Message::AttachWithLayoutTitle, &[session_name]);
+                    print_text_with_coordinates(
+                        session_name_ranges
+                            .into_iter()
+                            .fold(Text::new(&title), |text, range| text.color_range(0, range)),
+                        x,
+                        y + 2,
+                        None,
+                        None,
+                    );
+                    render_layout_selection_list(
+                        self.language,
+                        &self.new_session_info,
+                        self.language.text(Message::LayoutPrompt),
+                        height.saturating_sub(8),
+                        width,
+                        x,
+                        y + 2,
+                    );
+                } else if let Some((session_name, tag)) = &self.tagging_session {
+                    render_renaming_session_screen(
+                        self.language,
+                        &self
+                            .language
+                            .format(Message::TagSessionTitle, &[session_name]),
+                        tag,
+                        height,
+                        width,
+                        x,
+                        y + 2,
+                    );
+                } else if let Some(new_session_name) = &self.renaming_session_name {
+                    render_renaming_session_screen(
+                        self.language,
+                        self.language.text(Message::NewNameForCurrentSession),
+                        &new_session_name,
+                        height,
+                        width,
+                        x,
+                        y + 2,
+                    );
+                } else if let Some(pending_confirmation) = &self.pending_confirmation {
+                    let (title, highlighted_ranges) = pending_confirmation.title(self.language);
+                    render_confirmation(
+                        &title,
+                        highlighted_ranges,
+                        self.language.text(Message::AreYouSure),
+                        height,
+                        width,
+                        x,
+                        y,
+                    );
                 } else {
+                    let (result_position, result_count) = self.sessions.result_position();
+                    let result_position_width =
+                        result_position_width(result_position, result_count);
                     render_prompt(
-                        &self.search_term,
-                        self.search_cursor,
-                        self.sessions.is_expanded(),
+                        &self.attach_search.term,
+                        self.attach_search.cursor,
+                        &self.sessions.search_prompt(self.language),
+                        self.colors,
+                        x,
+                        y + 2,
+                        width.saturating_sub(result_position_width + 2),
+                    );
+                    render_result_position(
+                        result_position,
+                        result_count,
+                        self.colors,
+                        x,
+                        y + 2,
+                        width.saturating_sub(1),
+                    );
+                    render_clear_search_hint(
+                        self.language,
+                        &self.attach_search.term,
+                        &self.sessions.search_prompt(self.language),
                         self.colors,
                         x,
                         y + 2,
+                        width,
                     );
                     let room_for_list = height.saturating_sub(6); // search line and controls;
+                    let room_for_list = if self.show_session_details {
+                        room_for_list.saturating_sub(SESSION_DETAILS_ROWS)
+                    } else {
+                        room_for_list
+                    };
                     self.sessions.update_rows(room_for_list);
-                    let list =
+                    self.sessions
+                        .update_columns(width.saturating_sub(7), self.colors);
+                    let mut list =
                         self.sessions
                             .render(room_for_list, width.saturating_sub(7), self.colors); // 7 for various ui
+                    if self.sessions.show_expanded_content {
+                        // the deepest selected line, search results select all their lines
+                        if let Some(selected_line) = list.iter_mut().rev().find(|l| l.is_selected())
+                        {
+                            selected_line
+                                .set_attach_target(&self.attach_target(), width.saturating_sub(7));
+                        }
+                    }
                     for (i, line) in list.iter().enumerate() {
                         print!("\u{1b}[{};{}H{}", y + i + 5, x, line.render());
                     }
+                    if let Some((first_visible_line, visible_line_count, total_line_count)) =
+                        self.sessions.scroll_position(room_for_list)
+                    {
+                        // in the last of the 7 columns reserved next to the list
+                        render_scrollbar(
+                            first_visible_line,
+                            visible_line_count,
+                            total_line_count,
+                            self.colors,
+                            x + width.saturating_sub(1),
+                            y + 5,
+                            room_for_list,
+                        );
+                    }
+                    if self.show_session_details {
+                        // right below the list, 0 based unlike the list lines
+                        render_session_details(
+                            self.sessions.get_selected_session_ui_info(),
+                            self.colors,
+                            x,
+                            y + room_for_list + 4,
+                            width.saturating_sub(1),
+                        );
+                    }
                 }
             }
             ActiveScreen::Resurrect => {
-                self.resurrectable_sessions.render(height, width, x, y);
-            }
+                if let Some(pending_confirmation) = &self.pending_confirmation {
+                    let (title, highlighted_ranges) = pending_confirmation.title(self.language);
+                    render_confirmation(
+                        &title,
+                        highlighted_ranges,
+                        self.language.text(Message::AreYouSure),
+                        height,
+                        width,
+                        x,
+                        y,
+                    );
+                } else if let Some(session_name) = &self.resurrect_layout_picker {
+                    let (title, session_name_ranges) = self.language.format_with_ranges(
+                        Message::ResurrectWithAnotherLayoutTitle,
+                        &[session_name],
+                    );
+                    print_text_with_coordinates(
+                        session_name_ranges
+                            .into_iter()
+                            .fold(Text::new(&title), |text, range| text.color_range(0, range)),
+                        x,
+                        y + 2,
+                        None,
+                        None,
+                    );
+                    render_layout_selection_list(
+                        self.language,
+                        &self.new_session_info,
+                        self.language.text(Message::LayoutPrompt),
+                        height.saturating_sub(8),
+                        width,
+                        x,
+                        y + 2,
+                    );
+                } else if self.recently_killed.is_visible {
+                    self.recently_killed
+                        .render(self.language, height, width, x, y);
+                } else {
+                    self.resurrectable_sessions.render(height, width, x, y);
+                }
+            }
         }
+        // errors and notices are placed 0 based, unlike the controls line, so they need to keep
+        // off the bottom of the frame
+        let footer_rows = if self.show_frame && !self.is_welcome_screen {
+            height.saturating_sub(1)
+        } else {
+            height
+        };
         if let Some(error) = &self.error {
-            render_error(&error, height, width, x, y);
+            render_error(self.language, &error, footer_rows, width, x, y);
+        } else if let Some(pending_operation) = &self.pending_operation {
+            let spinner_tick = (self.pending_operation_secs / SPINNER_INTERVAL_SECS) as usize;
+            let spinner_frame = SPINNER_FRAMES[spinner_tick % SPINNER_FRAMES.len()];
+            let description = pending_operation.description(self.language);
+            render_notice(
+                &format!("{} {}", spinner_frame, description),
+                footer_rows,
+                width,
+                x,
+                y,
+            );
+        } else if let Some(notice) = &self.notice {
+            render_notice(notice, footer_rows, width, x, y);
+        } else if self.flash_until_render > 0 {
+            self.flash_until_render -= 1;
+            render_flash_line(width, x + 1, y + height);
         } else {
-            render_controls_line(self.active_screen, width, self.colors, x + 1, rows);
+            render_controls_line(
+                self.language,
+                ScreenControls {
+                    active_screen: self.active_screen,
+                    allow_delete_resurrectable: !self.resurrectable_sessions.deleting_disabled,
+                    controls: &self.controls,
+                },
+                width,
+                self.colors,
+                x + 1,
+                y + height,
+            );
+        }
+        if self.show_action_log {
+            let log_width = std::cmp::min(60, cols / 2);
+            let log_height = std::cmp::min(12, rows.saturating_sub(2));
+            render_action_log(
+                &self.action_log.last_entries(log_height.saturating_sub(1)),
+                self.colors,
+                cols.saturating_sub(log_width),
+                1,
+                log_width,
+            );
         }
         if self.is_welcome_screen {
-            render_welcome_boundaries(rows, cols); // explicitly done in the end to override some
-                                                   // stuff, see comment in function
+            let cheatsheet = if self.welcome_cheatsheet {
+                cheatsheet_sections(
+                    self.language,
+                    &self.enabled_screens,
+                    !self.resurrectable_sessions.deleting_disabled,
+                    &self.screen_labels,
+                )
+            } else {
+                vec![]
+            };
+            // explicitly done in the end to override some stuff, see comment in function
+            render_welcome_boundaries(rows, cols, &cheatsheet);
+        } else if self.show_frame {
+            render_frame(self.colors, rows, cols);
         }
     }
 }
@@ -196,18 +1366,104 @@ impl State {
             self.error = None;
             return true;
         }
+        // notices are informational, so unlike errors they don't swallow the key
+        self.notice = None;
+        if self.permissions_denied {
+            // every binding needs a permission we were denied, all that's left is hiding
+            if !self.is_welcome_screen
+                && (key.is_key_without_modifier(BareKey::Esc)
+                    || key.is_key_with_ctrl_modifier(BareKey::Char('c')))
+            {
+                hide_self();
+            }
+            return false;
+        }
+        if self.is_debug {
+            if key.bare_key == BareKey::Char('l') && key.has_modifiers(&[KeyModifier::Alt]) {
+                self.show_action_log = !self.show_action_log;
+                return true;
+            }
+            self.action_log
+                .record(format!("key {} on {:?} screen", key, self.active_screen));
+        }
+        log!(
+            LogLevel::Debug,
+            "key {} on {:?} screen",
+            key,
+            self.active_screen
+        );
         match self.active_screen {
             ActiveScreen::New => self.handle_new_session_key(key),
             ActiveScreen::Attach => self.handle_attach_to_session(key),
             ActiveScreen::Resurrect => self.handle_resurrect_session_key(key),
         }
     }
+    fn handle_paste(&mut self, text: String) -> bool {
+        if self.permissions_denied
+            || self.pending_confirmation.is_some()
+            || self.tagging_session.is_some()
+            || self.attach_layout_picker.is_some()
+            || self.tab_picker.is_some()
+            || self.command_line.is_some()
+        {
+            return false;
+        }
+        let was_stripped = match self.active_screen {
+            ActiveScreen::New => self.new_session_info.paste(&text),
+            ActiveScreen::Attach => match self.renaming_session_name.as_mut() {
+                Some(new_session_name) => {
+                    let (session_name, was_stripped) = strip_pasted_session_name(&text);
+                    new_session_name.push_str(&session_name);
+                    was_stripped
+                }
+                None => {
+                    let text: String = text.chars().filter(|c| *c != '\n' && *c != '\r').collect();
+                    self.attach_search.insert(&text);
+                    self.sessions
+                        .update_search_term(&self.attach_search.term, &self.colors);
+                    self.auto_attach_to_single_match();
+                    false
+                }
+            },
+            ActiveScreen::Resurrect
+                if self.resurrect_layout_picker.is_none() && !self.recently_killed.is_visible =>
+            {
+                self.resurrectable_sessions.paste(&text)
+            }
+            ActiveScreen::Resurrect => return false,
+        };
+        if was_stripped {
+            self.show_notice(self.language.text(Message::PastedNameStripped));
+        }
+        true
+    }
     fn handle_new_session_key(&mut self, key: KeyWithModifier) -> bool {
         let mut should_render = false;
 
+        if let Some(pending_confirmation) = self.pending_confirmation.clone() {
+            match key.bare_key {
+                BareKey::Char('y') if key.has_no_modifiers() => {
+                    self.pending_confirmation = None;
+                    self.confirm(pending_confirmation);
+                }
+                BareKey::Char('n') | BareKey::Esc if key.has_no_modifiers() => {
+                    self.pending_confirmation = None;
+                }
+                BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                    self.pending_confirmation = None;
+                }
+                _ => return false,
+            }
+            return true;
+        }
+
         // Universal quit keys - escape and ctrl+c always quit
         match key.bare_key {
             BareKey::Esc if key.has_no_modifiers() && !self.is_welcome_screen => {
+                if self.confirm_discard_new && self.new_session_info.has_unsaved_input() {
+                    self.pending_confirmation = Some(PendingConfirmation::DiscardNewSession);
+                    return true;
+                }
                 hide_self();
                 return false;
             }
@@ -273,58 +1529,45 @@ impl State {
                 self.toggle_active_screen();
                 should_render = true;
             }
+            BareKey::Char(digit @ '1'..='3') if key.has_modifiers(&[KeyModifier::Alt]) => {
+                self.switch_to_screen_number(digit);
+                should_render = true;
+            }
             BareKey::Tab if key.has_modifiers(&[KeyModifier::Shift]) => {
                 self.toggle_active_screen_reverse();
                 should_render = true;
             }
             BareKey::Char('/') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                let request_id = Uuid::new_v4();
-                let mut config = BTreeMap::new();
-                let mut args = BTreeMap::new();
-                self.request_ids.push(request_id.to_string());
-                // we insert this into the config so that a new plugin will be opened (the plugin's
-                // uniqueness is determined by its name/url as well as its config)
-                config.insert("request_id".to_owned(), request_id.to_string());
-                // we also insert this into the args so that the plugin will have an easier access to
-                // it
-                args.insert("request_id".to_owned(), request_id.to_string());
-                pipe_message_to_plugin(
-                    MessageToPlugin::new("filepicker")
-                        .with_plugin_url("filepicker")
-                        .with_plugin_config(config)
-                        .new_plugin_instance_should_have_pane_title(
-                            "Select folder for the new session...",
-                        )
-                        .new_plugin_instance_should_be_focused()
-                        .with_args(args),
-                );
+                self.open_folder_picker();
                 should_render = true;
             }
-            BareKey::Char('/') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                let request_id = Uuid::new_v4();
-                let mut config = BTreeMap::new();
-                let mut args = BTreeMap::new();
-                self.request_ids.push(request_id.to_string());
-                // we insert this into the config so that a new plugin will be opened (the plugin's
-                // uniqueness is determined by its name/url as well as its config)
-                config.insert("request_id".to_owned(), request_id.to_string());
-                // we also insert this into the args so that the plugin will have an easier access to
-                // it
-                args.insert("request_id".to_owned(), request_id.to_string());
-                pipe_message_to_plugin(
-                    MessageToPlugin::new("filepicker")
-                        .with_plugin_url("filepicker")
-                        .with_plugin_config(config)
-                        .new_plugin_instance_should_have_pane_title(
-                            "Select folder for the new session...",
-                        )
-                        .new_plugin_instance_should_be_focused()
-                        .with_args(args),
-                );
+            BareKey::Char('f') if key.has_modifiers(&[KeyModifier::Alt]) => {
+                self.new_session_info.cycle_folder_display();
+                should_render = true;
+            }
+            BareKey::Char('g') if key.has_modifiers(&[KeyModifier::Alt]) => {
+                self.request_git_root();
+                should_render = true;
+            }
+            BareKey::Char('e') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.toggle_entering_env_variables();
+                should_render = true;
+            }
+            BareKey::Char('r') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.toggle_entering_initial_command();
+                should_render = true;
+            }
+            BareKey::Char('l') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                if self.new_session_info.select_current_session_layout() {
+                    self.handle_selection();
+                } else {
+                    self.show_error(self.language.text(Message::CurrentLayoutUnavailable));
+                }
                 should_render = true;
             }
             BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                self.new_session_info.new_session_folder = None;
+                self.new_session_info.reset_folder();
+                self.listed_new_session_folder = None;
                 should_render = true;
             }
             BareKey::Esc if key.has_no_modifiers() => {
@@ -338,12 +1581,8 @@ impl State {
     fn handle_attach_to_session(&mut self, key: KeyWithModifier) -> bool {
         let mut should_render = false;
 
-        // Universal quit keys - escape and ctrl+c always quit
+        // Universal quit key - ctrl+c always quits, escape first clears the search (see below)
         match key.bare_key {
-            BareKey::Esc if key.has_no_modifiers() && !self.is_welcome_screen => {
-                hide_self();
-                return false;
-            }
             BareKey::Char('c')
                 if key.has_modifiers(&[KeyModifier::Ctrl]) && !self.is_welcome_screen =>
             {
@@ -353,25 +1592,55 @@ impl State {
             _ => {}
         }
 
-        if self.show_kill_all_sessions_warning {
+        if self.batch_rename_pattern.is_some() {
+            return self.handle_batch_rename_key(key);
+        }
+        if self.tagging_session.is_some() {
+            return self.handle_tag_key(key);
+        }
+        if self.attach_layout_picker.is_some() {
+            return self.handle_attach_layout_picker_key(key);
+        }
+        if self.tab_picker.is_some() {
+            return self.handle_tab_picker_key(key);
+        }
+        if self.command_line.is_some() {
+            return self.handle_command_line_key(key);
+        }
+
+        // vim style count prefix, with Ctrl+Alt so that digits can still be typed into the search
+        // (Alt+1/2/3 switch screens and Ctrl+0/1/2 set the expansion level)
+        if let BareKey::Char(digit @ '0'..='9') = key.bare_key {
+            if key.has_modifiers(&[KeyModifier::Ctrl, KeyModifier::Alt]) {
+                let digit = digit as usize - '0' as usize;
+                self.pending_count = Some(
+                    self.pending_count
+                        .unwrap_or(0)
+                        .saturating_mul(10)
+                        .saturating_add(digit),
+                );
+                return false;
+            }
+        }
+        let count = self.pending_count.take().unwrap_or(1).max(1);
+
+        if let Some(pending_confirmation) = self.pending_confirmation.clone() {
             match key.bare_key {
                 BareKey::Char('y') if key.has_no_modifiers() => {
-                    let all_other_sessions = self.sessions.all_other_sessions();
-                    kill_sessions(&all_other_sessions);
-                    self.reset_selected_index();
-                    self.search_term.clear();
-                    self.search_cursor = 0;
-                    self.sessions
-                        .update_search_term(&self.search_term, &self.colors);
-                    self.show_kill_all_sessions_warning = false;
+                    self.pending_confirmation = None;
+                    self.confirm(pending_confirmation);
                     should_render = true;
                 }
                 BareKey::Char('n') | BareKey::Esc if key.has_no_modifiers() => {
-                    self.show_kill_all_sessions_warning = false;
+                    if matches!(pending_confirmation, PendingConfirmation::AutoAttach(_)) {
+                        // rather than asking again with every key typed into the search
+                        self.auto_attach_single = false;
+                    }
+                    self.pending_confirmation = None;
                     should_render = true;
                 }
                 BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    self.show_kill_all_sessions_warning = false;
+                    self.pending_confirmation = None;
                     should_render = true;
                 }
                 _ => {}
@@ -379,78 +1648,248 @@ impl State {
         } else {
             match key.bare_key {
                 BareKey::Right if key.has_no_modifiers() => {
-                    self.sessions.result_expand();
+                    if !self.sessions.move_selection_right() && !self.sessions.result_expand() {
+                        self.flash();
+                    }
                     should_render = true;
                 }
                 BareKey::Left if key.has_no_modifiers() => {
-                    self.sessions.result_shrink();
+                    if !self.sessions.move_selection_left() && !self.sessions.result_shrink() {
+                        self.flash();
+                    }
+                    should_render = true;
+                }
+                BareKey::Char('.') | BareKey::Char('>')
+                    if key.has_modifiers(&[KeyModifier::Ctrl, KeyModifier::Shift])
+                        || (key.bare_key == BareKey::Char('>')
+                            && key.has_modifiers(&[KeyModifier::Ctrl])) =>
+                {
+                    // expand all
+                    self.sessions.set_expansion(true);
+                    self.request_missing_session_cwds();
+                    self.sessions
+                        .update_search_term(&self.attach_search.term, &self.colors);
+                    should_render = true;
+                }
+                BareKey::Char(',') | BareKey::Char('<')
+                    if key.has_modifiers(&[KeyModifier::Ctrl, KeyModifier::Shift])
+                        || (key.bare_key == BareKey::Char('<')
+                            && key.has_modifiers(&[KeyModifier::Ctrl])) =>
+                {
+                    // collapse all
+                    self.sessions.set_expansion(false);
+                    self.sessions
+                        .update_search_term(&self.attach_search.term, &self.colors);
                     should_render = true;
                 }
                 BareKey::Char('.') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    self.sessions.result_expand();
+                    if !self.sessions.result_expand() {
+                        self.flash();
+                    }
                     should_render = true;
                 }
                 BareKey::Char(',') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    self.sessions.result_shrink();
+                    if !self.sessions.result_shrink() {
+                        self.flash();
+                    }
+                    should_render = true;
+                }
+                BareKey::Char('s') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                    self.toggle_web_sharing_of_selected_session();
+                    should_render = true;
+                }
+                BareKey::Char('s')
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.save_layout_of_selected_session();
+                    should_render = true;
+                }
+                BareKey::Char('y')
+                    if key.has_modifiers(&[KeyModifier::Ctrl])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.copy_attach_command_of_selected_session();
+                    should_render = true;
+                }
+                BareKey::Char('g')
+                    if key.has_modifiers(&[KeyModifier::Ctrl])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.sessions.toggle_pane_search();
+                    self.sessions
+                        .update_search_term(&self.attach_search.term, &self.colors);
+                    should_render = true;
+                }
+                // with sort = manual Alt+Up/Down reorder the sessions instead (see below)
+                BareKey::Up
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none()
+                        && self.sessions.sort != SessionSort::Manual =>
+                {
+                    if let Some(search_term) = self.search_history.older() {
+                        self.recall_search_term(search_term);
+                    } else {
+                        self.flash();
+                    }
+                    should_render = true;
+                }
+                BareKey::Down
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none()
+                        && self.sessions.sort != SessionSort::Manual =>
+                {
+                    if let Some(search_term) = self.search_history.newer() {
+                        self.recall_search_term(search_term);
+                    } else {
+                        self.flash();
+                    }
                     should_render = true;
                 }
                 BareKey::Down if key.has_no_modifiers() => {
-                    self.sessions.move_selection_down();
+                    self.move_session_selection_down(count);
                     should_render = true;
                 }
                 BareKey::Up if key.has_no_modifiers() => {
-                    self.sessions.move_selection_up();
+                    self.move_session_selection_up(count);
+                    should_render = true;
+                }
+                BareKey::Down
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none()
+                        && self.sessions.sort == SessionSort::Manual =>
+                {
+                    for _ in 0..count {
+                        if !self.sessions.move_item_down() {
+                            self.flash();
+                            break;
+                        }
+                    }
+                    should_render = true;
+                }
+                BareKey::Up
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none()
+                        && self.sessions.sort == SessionSort::Manual =>
+                {
+                    for _ in 0..count {
+                        if !self.sessions.move_item_up() {
+                            self.flash();
+                            break;
+                        }
+                    }
+                    should_render = true;
+                }
+                BareKey::Down if key.has_modifiers(&[KeyModifier::Shift]) => {
+                    for _ in 0..count {
+                        if !self.sessions.extend_selection_down() {
+                            self.flash();
+                            break;
+                        }
+                    }
+                    should_render = true;
+                }
+                BareKey::Up if key.has_modifiers(&[KeyModifier::Shift]) => {
+                    for _ in 0..count {
+                        if !self.sessions.extend_selection_up() {
+                            self.flash();
+                            break;
+                        }
+                    }
                     should_render = true;
                 }
                 BareKey::Char('n') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    self.sessions.move_selection_down();
+                    self.move_session_selection_down(count);
                     should_render = true;
                 }
                 BareKey::Char('p') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    self.sessions.move_selection_up();
+                    self.move_session_selection_up(count);
                     should_render = true;
                 }
                 BareKey::Char('j') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    self.sessions.move_selection_down();
+                    self.move_session_selection_down(count);
                     should_render = true;
                 }
                 BareKey::Char('k') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    self.sessions.move_selection_up();
+                    self.move_session_selection_up(count);
                     should_render = true;
                 }
                 BareKey::Char('h') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    self.sessions.result_shrink();
+                    if !self.sessions.result_shrink() {
+                        self.flash();
+                    }
                     should_render = true;
                 }
                 BareKey::Char('l') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    self.sessions.result_expand();
+                    // redraw, like in most terminal programs (expanding is Right or Ctrl+.)
+                    self.force_redraw = true;
+                    should_render = true;
+                }
+                BareKey::F(5) if key.has_no_modifiers() => {
+                    self.refresh_session_data();
+                    should_render = true;
+                }
+                BareKey::Char(c @ '0'..='2') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                    // jump to an expansion level: collapsed, tabs or tabs and panes
+                    if let Some(expansion_level) =
+                        c.to_digit(10).and_then(ExpansionLevel::from_number)
+                    {
+                        self.sessions.set_expansion_level(expansion_level);
+                        self.request_missing_session_cwds();
+                        self.sessions
+                            .update_search_term(&self.attach_search.term, &self.colors);
+                    }
                     should_render = true;
                 }
                 BareKey::Char('t') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
                     // Toggle session expansion
                     self.sessions.toggle_expansion();
+                    self.request_missing_session_cwds();
                     // Need to update search results since they depend on expansion state
                     self.sessions
-                        .update_search_term(&self.search_term, &self.colors);
+                        .update_search_term(&self.attach_search.term, &self.colors);
                     should_render = true;
                 }
                 BareKey::Enter if key.has_no_modifiers() => {
                     self.handle_selection();
                     should_render = true;
                 }
+                BareKey::Char('o')
+                    if key.has_modifiers(&[KeyModifier::Ctrl])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    // back to the current session's focused pane, without any focus change
+                    self.reset_search_and_hide();
+                    should_render = true;
+                }
+                BareKey::Enter if key.has_modifiers(&[KeyModifier::Alt]) => {
+                    self.handle_attach_selection(AttachFocus::from_preserve_focus(
+                        !self.preserve_focus_on_attach,
+                    ));
+                    should_render = true;
+                }
+                BareKey::Enter if key.has_modifiers(&[KeyModifier::Shift]) => {
+                    self.handle_attach_selection(AttachFocus::Tab);
+                    should_render = true;
+                }
+                BareKey::Char(':')
+                    if key.has_no_modifiers()
+                        && self.attach_search.term.is_empty()
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.command_line = Some(String::new());
+                    should_render = true;
+                }
                 BareKey::Char(character) if key.has_no_modifiers() => {
                     if character == '\n' {
                         self.handle_selection();
                     } else if let Some(new_session_name) = self.renaming_session_name.as_mut() {
                         new_session_name.push(character);
                     } else {
-                        // Insert character at cursor position (with bounds check)
-                        if self.search_cursor <= self.search_term.len() {
-                            self.search_term.insert(self.search_cursor, character);
-                            self.search_cursor += 1;
-                        }
+                        self.attach_search.insert(&character.to_string());
                         self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                            .update_search_term(&self.attach_search.term, &self.colors);
+                        self.auto_attach_to_single_match();
                     }
                     should_render = true;
                 }
@@ -461,15 +1900,9 @@ impl State {
                         } else {
                             new_session_name.pop();
                         }
-                    } else if self.search_cursor > 0 && self.search_cursor <= self.search_term.len()
-                    {
-                        // Delete character before cursor (with bounds check)
-                        self.search_cursor -= 1;
-                        if self.search_cursor < self.search_term.len() {
-                            self.search_term.remove(self.search_cursor);
-                        }
+                    } else if self.attach_search.delete_char_backward() {
                         self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                            .update_search_term(&self.attach_search.term, &self.colors);
                     }
                     should_render = true;
                 }
@@ -477,52 +1910,173 @@ impl State {
                     self.renaming_session_name = Some(String::new());
                     should_render = true;
                 }
+                BareKey::Char('a')
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.toggle_client_filter(ClientFilter::Attached);
+                    should_render = true;
+                }
+                BareKey::Char('i')
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.toggle_client_filter(ClientFilter::Idle);
+                    should_render = true;
+                }
+                BareKey::Char('r')
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    if self.attach_search.term.is_empty() {
+                        self.show_error(self.language.text(Message::FilterSessionsToRenameFirst));
+                    } else if self.sessions.filtered_session_names().is_empty() {
+                        self.show_error(self.language.text(Message::NoSessionsMatchFilter));
+                    } else {
+                        self.batch_rename_pattern = Some(NAME_PLACEHOLDER.to_owned());
+                    }
+                    should_render = true;
+                }
+                BareKey::Char('o')
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.open_selected_session_in_new_window();
+                    should_render = true;
+                }
+                BareKey::Char('h')
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    let hidden_session_count = self.sessions.toggle_show_hidden();
+                    self.sessions
+                        .update_search_term(&self.attach_search.term, &self.colors);
+                    if !self.sessions.show_hidden {
+                        self.show_notice(self.language.text(Message::HidingHiddenSessions));
+                    } else if hidden_session_count == 0 {
+                        self.show_notice(self.language.text(Message::NoHiddenSessions));
+                    } else {
+                        self.show_notice(&self.language.format(
+                            Message::ShowingHiddenSessions,
+                            &[&hidden_session_count.to_string()],
+                        ));
+                    }
+                    should_render = true;
+                }
+                BareKey::Char('w')
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.open_attach_layout_picker();
+                    should_render = true;
+                }
+                BareKey::Char('p')
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.open_tab_picker();
+                    should_render = true;
+                }
+                // like tmux's last-window, terminals send Ctrl+^ as Ctrl+6
+                BareKey::Char('^') | BareKey::Char('6')
+                    if key.has_modifiers(&[KeyModifier::Ctrl])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    self.swap_with_previous_session();
+                    should_render = true;
+                }
+                BareKey::Char('t')
+                    if key.has_modifiers(&[KeyModifier::Alt])
+                        && self.renaming_session_name.is_none() =>
+                {
+                    match self.sessions.get_selected_session_name() {
+                        Some(selected_session_name) => {
+                            self.tagging_session = Some((selected_session_name, String::new()));
+                        }
+                        None => {
+                            self.show_error(self.language.text(Message::MustSelectSessionToTag));
+                        }
+                    }
+                    should_render = true;
+                }
+                BareKey::Delete
+                    if key.has_no_modifiers() && self.sessions.selection_range().is_some() =>
+                {
+                    // a batch kill is always confirmed, regardless of confirm_single_kill
+                    let session_names = self.sessions.selection_range_session_names();
+                    if session_names.is_empty() {
+                        self.show_error(self.language.text(Message::NoOtherSessionsInSelection));
+                    } else {
+                        self.pending_confirmation =
+                            Some(PendingConfirmation::KillSessions(session_names));
+                    }
+                    should_render = true;
+                }
+                BareKey::Delete if key.has_modifiers(&[KeyModifier::Shift]) => {
+                    // always confirmed, regardless of confirm_single_kill
+                    match self.sessions.get_selected_session_name() {
+                        Some(selected_session_name) => {
+                            self.pending_confirmation =
+                                Some(PendingConfirmation::ForceKillSession(selected_session_name));
+                        }
+                        None => {
+                            self.show_error(self.language.text(Message::MustSelectSessionToKill));
+                        }
+                    }
+                    should_render = true;
+                }
                 BareKey::Delete if key.has_no_modifiers() => {
                     if let Some(selected_session_name) = self.sessions.get_selected_session_name() {
-                        kill_sessions(&[selected_session_name]);
-                        self.reset_selected_index();
-                        self.search_term.clear();
-                        self.search_cursor = 0;
-                        self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                        if self.confirm_single_kill {
+                            self.pending_confirmation =
+                                Some(PendingConfirmation::KillSession(selected_session_name));
+                        } else {
+                            self.kill_session(selected_session_name);
+                        }
                     } else {
-                        self.show_error("Must select session before killing it.");
+                        self.show_error(self.language.text(Message::MustSelectSessionToKill));
                     }
                     should_render = true;
                 }
                 BareKey::Char('d') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
                     let all_other_sessions = self.sessions.all_other_sessions();
                     if all_other_sessions.is_empty() {
-                        self.show_error("No other sessions to kill. Quit to kill the current one.");
+                        self.show_error(self.language.text(Message::NoOtherSessionsToKill));
                     } else {
-                        self.show_kill_all_sessions_warning = true;
+                        self.pending_confirmation =
+                            Some(PendingConfirmation::KillAllSessions(all_other_sessions));
                     }
                     should_render = true;
                 }
                 BareKey::Char('x') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    disconnect_other_clients()
+                    if self.confirm_disconnect_others {
+                        self.pending_confirmation = Some(PendingConfirmation::DisconnectOthers);
+                    } else {
+                        self.disconnect_other_clients();
+                    }
+                    should_render = true;
                 }
                 // Readline bindings for search field
                 BareKey::Char('f') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
                     // Move cursor forward (right)
                     if self.renaming_session_name.is_none()
-                        && self.search_cursor < self.search_term.len()
+                        && self.attach_search.cursor < self.attach_search.char_count()
                     {
-                        self.search_cursor += 1;
+                        self.attach_search.cursor += 1;
                         should_render = true;
                     }
                 }
                 BareKey::Char('b') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
                     // Move cursor backward (left)
-                    if self.renaming_session_name.is_none() && self.search_cursor > 0 {
-                        self.search_cursor -= 1;
+                    if self.renaming_session_name.is_none() && self.attach_search.cursor > 0 {
+                        self.attach_search.cursor -= 1;
                         should_render = true;
                     }
                 }
                 BareKey::Char('a') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
                     // Move to beginning of line
                     if self.renaming_session_name.is_none() {
-                        self.search_cursor = 0;
+                        self.attach_search.cursor = 0;
                         should_render = true;
                     }
                 }
@@ -530,65 +2084,51 @@ impl State {
                     // Check if we're in session expansion toggle mode or readline end-of-line
                     if self.renaming_session_name.is_none() {
                         // If search field is focused, move to end of line (readline behavior)
-                        self.search_cursor = self.search_term.len();
+                        self.attach_search.move_cursor_to_end();
                         should_render = true;
                     }
                 }
                 BareKey::Char('k') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
                     // Check if we're using vim navigation or readline kill-to-end
-                    if self.renaming_session_name.is_none() && !self.search_term.is_empty() {
+                    if self.renaming_session_name.is_none() && !self.attach_search.term.is_empty() {
                         // Kill from cursor to end of line (readline behavior)
-                        self.search_term.truncate(self.search_cursor);
+                        self.attach_search.kill_to_end();
                         self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                            .update_search_term(&self.attach_search.term, &self.colors);
                         should_render = true;
                     } else {
                         // Vim-style up navigation
-                        self.sessions.move_selection_up();
+                        self.move_session_selection_up(1);
                         should_render = true;
                     }
                 }
                 BareKey::Char('u') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
                     // Kill entire line (readline)
-                    if self.renaming_session_name.is_none() && !self.search_term.is_empty() {
-                        self.search_term.clear();
-                        self.search_cursor = 0;
+                    if self.renaming_session_name.is_none() && !self.attach_search.term.is_empty() {
+                        self.search_history.record(&self.attach_search.term);
+                        self.attach_search.clear();
                         self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                            .update_search_term(&self.attach_search.term, &self.colors);
                         self.reset_selected_index();
                         should_render = true;
                     }
                 }
                 BareKey::Char('w') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
                     // Delete word backward (readline)
-                    if self.renaming_session_name.is_none() && self.search_cursor > 0 {
-                        let mut new_cursor = self.search_cursor;
-                        let chars: Vec<char> = self.search_term.chars().collect();
-
-                        // Skip whitespace backwards
-                        while new_cursor > 0 && chars[new_cursor - 1].is_whitespace() {
-                            new_cursor -= 1;
-                        }
-
-                        // Delete word backwards
-                        while new_cursor > 0 && !chars[new_cursor - 1].is_whitespace() {
-                            new_cursor -= 1;
-                        }
-
-                        // Remove the characters
-                        self.search_term.drain(new_cursor..self.search_cursor);
-                        self.search_cursor = new_cursor;
+                    if self.renaming_session_name.is_none()
+                        && self.attach_search.delete_word_backward()
+                    {
                         self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                            .update_search_term(&self.attach_search.term, &self.colors);
                         should_render = true;
                     }
                 }
                 BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                    if !self.search_term.is_empty() {
-                        self.search_term.clear();
-                        self.search_cursor = 0;
+                    if !self.attach_search.term.is_empty() {
+                        self.search_history.record(&self.attach_search.term);
+                        self.attach_search.clear();
                         self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                            .update_search_term(&self.attach_search.term, &self.colors);
                         self.reset_selected_index();
                     } else if !self.is_welcome_screen {
                         self.reset_selected_index();
@@ -599,36 +2139,20 @@ impl State {
                 BareKey::Char('d') if key.has_modifiers(&[KeyModifier::Alt]) => {
                     // Delete word forward (readline)
                     if self.renaming_session_name.is_none()
-                        && self.search_cursor < self.search_term.len()
+                        && self.attach_search.delete_word_forward()
                     {
-                        let mut new_cursor = self.search_cursor;
-                        let chars: Vec<char> = self.search_term.chars().collect();
-
-                        // Skip whitespace forward
-                        while new_cursor < chars.len() && chars[new_cursor].is_whitespace() {
-                            new_cursor += 1;
-                        }
-
-                        // Delete word forward
-                        while new_cursor < chars.len() && !chars[new_cursor].is_whitespace() {
-                            new_cursor += 1;
-                        }
-
-                        // Remove the characters
-                        self.search_term.drain(self.search_cursor..new_cursor);
                         self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                            .update_search_term(&self.attach_search.term, &self.colors);
                         should_render = true;
                     }
                 }
                 BareKey::Char('x') if key.has_modifiers(&[KeyModifier::Alt]) => {
                     // Delete character forward (readline)
                     if self.renaming_session_name.is_none()
-                        && self.search_cursor < self.search_term.len()
+                        && self.attach_search.delete_char_forward()
                     {
-                        self.search_term.remove(self.search_cursor);
                         self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                            .update_search_term(&self.attach_search.term, &self.colors);
                         should_render = true;
                     }
                 }
@@ -636,11 +2160,11 @@ impl State {
                     if key.has_modifiers(&[KeyModifier::Alt, KeyModifier::Shift]) =>
                 {
                     // Cut entire line (readline)
-                    if self.renaming_session_name.is_none() && !self.search_term.is_empty() {
-                        self.search_term.clear();
-                        self.search_cursor = 0;
+                    if self.renaming_session_name.is_none() && !self.attach_search.term.is_empty() {
+                        self.search_history.record(&self.attach_search.term);
+                        self.attach_search.clear();
                         self.sessions
-                            .update_search_term(&self.search_term, &self.colors);
+                            .update_search_term(&self.attach_search.term, &self.colors);
                         self.reset_selected_index();
                         should_render = true;
                     }
@@ -649,16 +2173,34 @@ impl State {
                     self.toggle_active_screen();
                     should_render = true;
                 }
+                BareKey::Char(digit @ '1'..='3') if key.has_modifiers(&[KeyModifier::Alt]) => {
+                    self.switch_to_screen_number(digit);
+                    should_render = true;
+                }
                 BareKey::Tab if key.has_modifiers(&[KeyModifier::Shift]) => {
                     self.toggle_active_screen_reverse();
                     should_render = true;
                 }
                 BareKey::Esc if key.has_no_modifiers() => {
-                    if self.renaming_session_name.is_some() {
-                        self.renaming_session_name = None;
-                        should_render = true;
-                    } else if !self.is_welcome_screen {
-                        hide_self();
+                    match AttachEsc::decide(
+                        self.renaming_session_name.is_some(),
+                        &self.attach_search.term,
+                        self.is_welcome_screen,
+                    ) {
+                        AttachEsc::StopRenaming => {
+                            self.renaming_session_name = None;
+                            should_render = true;
+                        }
+                        AttachEsc::ClearSearch => {
+                            self.search_history.record(&self.attach_search.term);
+                            self.attach_search.clear();
+                            self.sessions
+                                .update_search_term(&self.attach_search.term, &self.colors);
+                            self.reset_selected_index();
+                            should_render = true;
+                        }
+                        AttachEsc::Hide => hide_self(),
+                        AttachEsc::Nothing => {}
                     }
                 }
                 _ => {}
@@ -669,6 +2211,29 @@ impl State {
     fn handle_resurrect_session_key(&mut self, key: KeyWithModifier) -> bool {
         let mut should_render = false;
 
+        if self.resurrectable_sessions.renaming_session.is_some() {
+            return self.handle_resurrectable_session_rename_key(key);
+        }
+        if self.resurrect_layout_picker.is_some() {
+            return self.handle_resurrect_layout_picker_key(key);
+        }
+        if let Some(pending_confirmation) = self.pending_confirmation.clone() {
+            match key.bare_key {
+                BareKey::Char('y') if key.has_no_modifiers() => {
+                    self.pending_confirmation = None;
+                    self.confirm(pending_confirmation);
+                }
+                BareKey::Char('n') | BareKey::Esc if key.has_no_modifiers() => {
+                    self.pending_confirmation = None;
+                }
+                BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                    self.pending_confirmation = None;
+                }
+                _ => return false,
+            }
+            return true;
+        }
+
         // Universal quit keys - escape and ctrl+c always quit
         match key.bare_key {
             BareKey::Esc if key.has_no_modifiers() && !self.is_welcome_screen => {
@@ -684,6 +2249,10 @@ impl State {
             _ => {}
         }
 
+        if self.recently_killed.is_visible {
+            return self.handle_recently_killed_key(key);
+        }
+
         match key.bare_key {
             BareKey::Down if key.has_no_modifiers() => {
                 self.resurrectable_sessions.move_selection_down();
@@ -729,17 +2298,48 @@ impl State {
                 self.toggle_active_screen();
                 should_render = true;
             }
+            BareKey::Char(digit @ '1'..='3') if key.has_modifiers(&[KeyModifier::Alt]) => {
+                self.switch_to_screen_number(digit);
+                should_render = true;
+            }
             BareKey::Tab if key.has_modifiers(&[KeyModifier::Shift]) => {
                 self.toggle_active_screen_reverse();
                 should_render = true;
             }
             BareKey::Delete if key.has_no_modifiers() => {
-                self.resurrectable_sessions.delete_selected_session();
+                if let Err(e) = self.resurrectable_sessions.delete_selected_session() {
+                    self.show_error(e);
+                }
                 should_render = true;
             }
             BareKey::Char('d') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                self.resurrectable_sessions
-                    .show_delete_all_sessions_warning();
+                if let Err(e) = self
+                    .resurrectable_sessions
+                    .show_delete_all_sessions_warning()
+                {
+                    self.show_error(e);
+                }
+                should_render = true;
+            }
+            BareKey::Char('s') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.resurrectable_sessions.toggle_sort_mode();
+                should_render = true;
+            }
+            BareKey::Char('f') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.recently_killed.toggle_visible();
+                should_render = true;
+            }
+            BareKey::Char('l') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.open_resurrect_layout_picker();
+                should_render = true;
+            }
+            BareKey::Char('r') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                if !self
+                    .resurrectable_sessions
+                    .start_renaming_selected_session()
+                {
+                    self.show_error(self.language.text(Message::MustSelectSessionToRename));
+                }
                 should_render = true;
             }
             BareKey::Esc if key.has_no_modifiers() => {
@@ -751,118 +2351,1728 @@ impl State {
         }
         should_render
     }
-    fn handle_selection(&mut self) {
+    fn handle_resurrectable_session_rename_key(&mut self, key: KeyWithModifier) -> bool {
+        match key.bare_key {
+            BareKey::Enter if key.has_no_modifiers() => {
+                self.resurrect_renamed_session();
+            }
+            BareKey::Char('\n') if key.has_no_modifiers() => {
+                self.resurrect_renamed_session();
+            }
+            BareKey::Char(character) if key.has_no_modifiers() => {
+                if let Some((_, new_name)) = self.resurrectable_sessions.renaming_session.as_mut() {
+                    new_name.push(character);
+                }
+            }
+            BareKey::Backspace if key.has_no_modifiers() => {
+                if let Some((_, new_name)) = self.resurrectable_sessions.renaming_session.as_mut() {
+                    if new_name.is_empty() {
+                        self.resurrectable_sessions.renaming_session = None;
+                    } else {
+                        new_name.pop();
+                    }
+                }
+            }
+            BareKey::Esc if key.has_no_modifiers() => {
+                self.resurrectable_sessions.renaming_session = None;
+            }
+            BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.resurrectable_sessions.renaming_session = None;
+            }
+            _ => return false,
+        }
+        true
+    }
+    fn handle_batch_rename_key(&mut self, key: KeyWithModifier) -> bool {
+        match key.bare_key {
+            BareKey::Enter if key.has_no_modifiers() => {
+                self.rename_filtered_sessions();
+            }
+            BareKey::Char('\n') if key.has_no_modifiers() => {
+                self.rename_filtered_sessions();
+            }
+            BareKey::Char(character) if key.has_no_modifiers() => {
+                if let Some(pattern) = self.batch_rename_pattern.as_mut() {
+                    pattern.push(character);
+                }
+            }
+            BareKey::Backspace if key.has_no_modifiers() => {
+                if let Some(pattern) = self.batch_rename_pattern.as_mut() {
+                    if pattern.is_empty() {
+                        self.batch_rename_pattern = None;
+                    } else {
+                        pattern.pop();
+                    }
+                }
+            }
+            BareKey::Esc if key.has_no_modifiers() => {
+                self.batch_rename_pattern = None;
+            }
+            BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.batch_rename_pattern = None;
+            }
+            _ => return false,
+        }
+        true
+    }
+    fn handle_tag_key(&mut self, key: KeyWithModifier) -> bool {
+        match key.bare_key {
+            BareKey::Enter | BareKey::Char('\n') if key.has_no_modifiers() => {
+                self.toggle_session_tag();
+            }
+            BareKey::Char(character) if key.has_no_modifiers() && is_tag_character(character) => {
+                if let Some((_, tag)) = self.tagging_session.as_mut() {
+                    tag.push(character);
+                }
+            }
+            BareKey::Backspace if key.has_no_modifiers() => {
+                if let Some((_, tag)) = self.tagging_session.as_mut() {
+                    if tag.is_empty() {
+                        self.tagging_session = None;
+                    } else {
+                        tag.pop();
+                    }
+                }
+            }
+            BareKey::Esc if key.has_no_modifiers() => {
+                self.tagging_session = None;
+            }
+            BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.tagging_session = None;
+            }
+            _ => return false,
+        }
+        true
+    }
+    fn handle_command_line_key(&mut self, key: KeyWithModifier) -> bool {
+        match key.bare_key {
+            BareKey::Enter | BareKey::Char('\n') if key.has_no_modifiers() => {
+                self.run_command_line();
+            }
+            BareKey::Char(character) if key.has_no_modifiers() => {
+                if let Some(command_line) = self.command_line.as_mut() {
+                    command_line.push(character);
+                }
+            }
+            BareKey::Backspace if key.has_no_modifiers() => {
+                if let Some(command_line) = self.command_line.as_mut() {
+                    if command_line.is_empty() {
+                        self.command_line = None;
+                    } else {
+                        command_line.pop();
+                    }
+                }
+            }
+            BareKey::Esc if key.has_no_modifiers() => {
+                self.command_line = None;
+            }
+            BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.command_line = None;
+            }
+            _ => return false,
+        }
+        true
+    }
+    fn run_command_line(&mut self) {
+        // the commands go through the same paths as their keys, so they're validated (and
+        // confirmed) the same way
+        let command_line = match self.command_line.take() {
+            Some(command_line) => command_line,
+            None => return,
+        };
+        let (command, argument) = match command_line.trim().split_once(' ') {
+            Some((command, argument)) => (command.to_owned(), argument.trim().to_owned()),
+            None => (command_line.trim().to_owned(), String::new()),
+        };
+        if command.is_empty() {
+            return;
+        }
+        if argument.is_empty() && ["kill", "new", "rename"].contains(&command.as_str()) {
+            self.show_error(
+                &self
+                    .language
+                    .format(Message::CommandNeedsSessionName, &[&command]),
+            );
+            return;
+        }
+        self.log_action(format!("command {}", command_line.trim()));
+        match command.as_str() {
+            "kill" => {
+                if !self.sessions.has_session(&argument) {
+                    self.show_error(&self.language.format(Message::NoSessionNamed, &[&argument]));
+                } else if self.confirm_single_kill {
+                    self.pending_confirmation = Some(PendingConfirmation::KillSession(argument));
+                } else {
+                    self.kill_session(argument);
+                }
+            }
+            "new" => {
+                if !self.enabled_screens.contains(&ActiveScreen::New) {
+                    self.show_error(self.language.text(Message::NewScreenDisabled));
+                } else if self.new_session_info.has_unsaved_input() {
+                    // the folder, env variables or command filled in there are not meant for
+                    // this session
+                    self.show_error(self.language.text(Message::NewSessionInProgress));
+                } else {
+                    // as if the name was typed on the New screen, which shows any error with it -
+                    // without saving it as the screen to open with next time
+                    self.new_session_info.continue_with_layout(argument);
+                    self.apply_default_new_session_cwd();
+                    self.active_screen = ActiveScreen::New;
+                    self.handle_selection();
+                }
+            }
+            "rename" => {
+                self.renaming_session_name = Some(argument);
+                self.handle_attach_selection(AttachFocus::Selected);
+            }
+            _ => self.show_error(&self.language.format(Message::UnknownCommand, &[&command])),
+        }
+    }
+    fn toggle_session_tag(&mut self) {
+        let (session_name, tag) = match self.tagging_session.take() {
+            Some((session_name, tag)) if !tag.is_empty() => (session_name, tag),
+            _ => return,
+        };
+        if self.sessions.toggle_session_tag(&session_name, &tag) {
+            self.show_notice(
+                &self
+                    .language
+                    .format(Message::TagAdded, &[&session_name, &tag]),
+            );
+        } else {
+            self.show_notice(
+                &self
+                    .language
+                    .format(Message::TagRemoved, &[&tag, &session_name]),
+            );
+        }
+        self.sessions
+            .update_search_term(&self.attach_search.term, &self.colors);
+    }
+    fn rename_filtered_sessions(&mut self) {
+        let pattern = match self.batch_rename_pattern.take() {
+            Some(pattern) => pattern,
+            None => return,
+        };
+        if !self.commands_allowed() {
+            return;
+        }
+        let mut batch_rename = BatchRename::default();
+        let mut new_names: Vec<String> = vec![];
+        for session_name in self.sessions.filtered_session_names() {
+            let new_name = apply_rename_pattern(&pattern, &session_name);
+            if new_name == session_name {
+                continue;
+            }
+            let failure_reason = if new_name.is_empty() {
+                Some(Message::RenameFailedNameEmpty)
+            } else if new_name.len() >= 108 {
+                // socket path limitations, see handle_selection
+                Some(Message::RenameFailedNameTooLong)
+            } else if new_name.contains('/') {
+                Some(Message::RenameFailedHasSlash)
+            } else if self.sessions.has_session(&new_name)
+                || self.sessions.has_forbidden_session(&new_name)
+                || self.resurrectable_sessions.has_session(&new_name)
+                || new_names.contains(&new_name)
+            {
+                Some(Message::RenameFailedNameTaken)
+            } else {
+                None
+            };
+            if let Some(failure_reason) = failure_reason {
+                batch_rename.record_failure(&session_name, self.language.text(failure_reason));
+                continue;
+            }
+            new_names.push(new_name.clone());
+            self.log_action(format!("rename session {} to {}", session_name, new_name));
+            if self.session_name.as_ref() == Some(&session_name) {
+                self.update_current_session_name_in_ui(&new_name);
+                rename_session(&new_name);
+                batch_rename.record_success(&session_name, &new_name);
+            } else {
+                let mut context = BTreeMap::new();
+                context.insert(BATCH_RENAME_REQUEST.to_owned(), session_name.clone());
+                context.insert("new_name".to_owned(), new_name.clone());
+                run_command(
+                    &[
+                        "zellij",
+                        "--session",
+                        &session_name,
+                        "action",
+                        "rename-session",
+                        &new_name,
+                    ],
+                    context,
+                );
+                batch_rename.add_pending();
+            }
+        }
+        self.batch_rename = Some(batch_rename);
+        self.report_batch_rename_if_done();
+    }
+    fn report_batch_rename_if_done(&mut self) {
+        if !self
+            .batch_rename
+            .as_ref()
+            .map(|b| b.is_done())
+            .unwrap_or(false)
+        {
+            return;
+        }
+        if let Some(batch_rename) = self.batch_rename.take() {
+            match batch_rename.report(self.language) {
+                Ok(notice) => self.show_notice(&notice),
+                Err(error) => self.show_error(&error),
+            }
+        }
+    }
+    fn resurrect_renamed_session(&mut self) {
+        let (original_name, new_name) = match self.resurrectable_sessions.renaming_session.take() {
+            Some(renaming_session) => renaming_session,
+            None => return,
+        };
+        // before trimming, which would make a blank name empty
+        if is_blank_session_name(&new_name) {
+            self.show_error(self.language.text(Message::SessionNameBlank));
+            return;
+        }
+        let new_name = self.trimmed_session_name(new_name);
+        if new_name.is_empty() {
+            self.show_error(self.language.text(Message::NewNameEmpty));
+        } else if new_name.contains('/') {
+            self.show_error(self.language.text(Message::SessionNameHasSlash));
+        } else if new_name == original_name {
+            self.log_action(format!("resurrect session {}", original_name));
+            self.attach_to(&original_name, None, None, None);
+            return;
+        } else if self.sessions.has_session(&new_name)
+            || self.sessions.has_forbidden_session(&new_name)
+        {
+            self.show_error(self.language.text(Message::SessionNameTaken));
+        } else if self.resurrectable_sessions.has_session(&new_name) {
+            self.show_error(self.language.text(Message::ResurrectableSessionNameTaken));
+        } else if self.commands_allowed() {
+            // there is no host command to resurrect a session under a different name, so we
+            // resurrect it in the background, rename it and only then switch to it (see
+            // handle_run_command_result)
+            let mut context = BTreeMap::new();
+            context.insert(
+                RESURRECT_RENAME_STEP.to_owned(),
+                "resurrect_in_background".to_owned(),
+            );
+            context.insert("original_name".to_owned(), original_name.clone());
+            context.insert("new_name".to_owned(), new_name);
+            run_command(
+                &["zellij", "attach", "--create-background", &original_name],
+                context,
+            );
+            return;
+        }
+        // let the user correct the name
+        self.resurrectable_sessions.renaming_session = Some((original_name, new_name));
+    }
+    fn handle_run_command_result(
+        &mut self,
+        exit_code: Option<i32>,
+        stdout: Vec<u8>,
+        stderr: Vec<u8>,
+        context: BTreeMap<String, String>,
+    ) {
+        if let Some(session_name) = context.get(NEW_SESSION_WITH_ENV_REQUEST) {
+            if let (Some(0), Some(initial_command)) =
+                (exit_code, context.get(NEW_SESSION_INITIAL_COMMAND))
+            {
+                // the session is ready, open the initial command in a new pane before switching
+                let mut new_pane_context = BTreeMap::new();
+                new_pane_context
+                    .insert(INITIAL_COMMAND_REQUEST.to_owned(), session_name.to_owned());
+                self.log_action(format!(
+                    "run initial command in session {}: {}",
+                    session_name, initial_command
+                ));
+                run_command(
+                    &[
+                        "zellij",
+                        "--session",
+                        session_name,
+                        "action",
+                        "new-pane",
+                        "--",
+                        "sh",
+                        "-c",
+                        initial_command,
+                    ],
+                    new_pane_context,
+                );
+            } else if exit_code == Some(0) {
+                self.attach_to(session_name, None, None, None);
+            } else {
+                self.show_error(&self.language.format(
+                    Message::FailedToCreateSession,
+                    &[String::from_utf8_lossy(&stderr).trim()],
+                ));
+            }
+            return;
+        }
+        if let Some(session_name) = context.get(INITIAL_COMMAND_REQUEST) {
+            if exit_code == Some(0) {
+                self.attach_to(session_name, None, None, None);
+            } else {
+                self.show_error(&self.language.format(
+                    Message::InitialCommandFailed,
+                    &[session_name, String::from_utf8_lossy(&stderr).trim()],
+                ));
+            }
+            return;
+        }
+        if let Some(session_name) = context.get(BATCH_RENAME_REQUEST) {
+            if let Some(batch_rename) = self.batch_rename.as_mut() {
+                batch_rename.finish_pending();
+                match (exit_code, context.get("new_name")) {
+                    (Some(0), Some(new_name)) => {
+                        batch_rename.record_success(session_name, new_name)
+                    }
+                    _ => batch_rename
+                        .record_failure(session_name, String::from_utf8_lossy(&stderr).trim()),
+                }
+            }
+            self.report_batch_rename_if_done();
+            return;
+        }
+        if let Some(session_name) = context.get(POST_CREATE_COMMAND_REQUEST) {
+            if exit_code == Some(0) {
+                self.show_notice(
+                    &self
+                        .language
+                        .format(Message::PostCreateCommandFinished, &[session_name]),
+                );
+            } else {
+                self.show_error(&self.language.format(
+                    Message::PostCreateCommandFailed,
+                    &[session_name, String::from_utf8_lossy(&stderr).trim()],
+                ));
+            }
+            return;
+        }
+        if let Some(session_name) = context.get(POST_ATTACH_COMMAND_REQUEST) {
+            // by now we're attached elsewhere, so a failure is only worth a log entry
+            if exit_code != Some(0) {
+                let error = format!(
+                    "post attach command failed for {}: {}",
+                    session_name,
+                    String::from_utf8_lossy(&stderr).trim()
+                );
+                log!(LogLevel::Error, "{}", error);
+                self.log_action(error);
+            }
+            return;
+        }
+        if let Some(copied_text) = context.get(COPY_ATTACH_COMMAND_REQUEST) {
+            if exit_code == Some(0) {
+                self.show_notice(&self.language.format(Message::Copied, &[copied_text]));
+            } else {
+                self.show_error(&self.language.format(
+                    Message::FailedToCopy,
+                    &[String::from_utf8_lossy(&stderr).trim()],
+                ));
+            }
+            return;
+        }
+        if context.contains_key(HOME_FOLDER_REQUEST) {
+            // without it paths are only shown in full
+            if exit_code == Some(0) {
+                set_home_folder(&String::from_utf8_lossy(&stdout));
+            }
+            return;
+        }
+        if let Some(unexpanded_default_new_session_cwd) = context.get(DEFAULT_CWD_REQUEST) {
+            let stdout = String::from_utf8_lossy(&stdout);
+            // printenv ends each value with a newline
+            let variables: BTreeMap<String, String> =
+                cwd_variable_names(unexpanded_default_new_session_cwd)
+                    .into_iter()
+                    .zip(stdout.split('\0'))
+                    .map(|(name, value)| {
+                        let value = value.strip_suffix('\n').unwrap_or(value);
+                        (name, value.to_owned())
+                    })
+                    .collect();
+            let default_new_session_cwd =
+                expand_cwd(unexpanded_default_new_session_cwd, &variables);
+            if exit_code == Some(0) && !default_new_session_cwd.is_empty() {
+                self.default_new_session_cwd = Some(PathBuf::from(default_new_session_cwd));
+                self.apply_default_new_session_cwd();
+            } else {
+                self.show_error(&self.language.format(
+                    Message::FailedToExpandDefaultCwd,
+                    &[String::from_utf8_lossy(&stderr).trim()],
+                ));
+            }
+            return;
+        }
+        if context.contains_key(GIT_ROOT_REQUEST) {
+            let git_root = String::from_utf8_lossy(&stdout).trim().to_owned();
+            if exit_code == Some(0) && !git_root.is_empty() {
+                let git_root = PathBuf::from(git_root);
+                self.new_session_info.prefill_name_from_folder(&git_root);
+                self.new_session_info.new_session_folder = Some(git_root);
+                self.list_new_session_folder();
+            } else {
+                self.show_error(self.language.text(Message::NotInGitRepo));
+            }
+            return;
+        }
+        if let Some(folder) = context.get(FOLDER_FILES_REQUEST) {
+            // the folder might have changed again in the meantime
+            if self
+                .listed_new_session_folder
+                .as_ref()
+                .map(|f| f.display().to_string())
+                == Some(folder.to_owned())
+            {
+                // without its files we can't tell the project, so all layouts are shown as usual
+                let stdout = String::from_utf8_lossy(&stdout);
+                let file_names: Vec<&str> = if exit_code == Some(0) {
+                    stdout.lines().collect()
+                } else {
+                    vec![]
+                };
+                self.new_session_info.set_folder_file_names(&file_names);
+            }
+            return;
+        }
+        if context.contains_key(NOTIFY_REQUEST) {
+            // a missing notification is not worth an error, the operation itself went fine
+            if exit_code != Some(0) {
+                log!(
+                    LogLevel::Error,
+                    "failed to send notification: {}",
+                    String::from_utf8_lossy(&stderr).trim()
+                );
+            }
+            return;
+        }
+        if let Some(session_name) = context.get(SESSION_CWD_REQUEST) {
+            // a failure here only means we don't show the cwd, it's not worth an error
+            if exit_code == Some(0) {
+                if let Some(cwd) = cwd_from_layout(&String::from_utf8_lossy(&stdout)) {
+                    self.sessions.set_session_cwd(session_name, cwd);
+                }
+            }
+            return;
+        }
+        if let Some(step) = context.get(RESURRECT_RENAME_STEP) {
+            let (original_name, new_name) =
+                match (context.get("original_name"), context.get("new_name")) {
+                    (Some(original_name), Some(new_name)) => (original_name, new_name),
+                    _ => return,
+                };
+            if exit_code != Some(0) {
+                self.show_error(&self.language.format(
+                    Message::FailedToResurrectSession,
+                    &[String::from_utf8_lossy(&stderr).trim()],
+                ));
+                return;
+            }
+            match step.as_str() {
+                "resurrect_in_background" => {
+                    let mut context = context.clone();
+                    context.insert(RESURRECT_RENAME_STEP.to_owned(), "rename".to_owned());
+                    run_command(
+                        &[
+                            "zellij",
+                            "--session",
+                            original_name,
+                            "action",
+                            "rename-session",
+                            new_name,
+                        ],
+                        context,
+                    );
+                }
+                "rename" => {
+                    self.log_action(format!("resurrect {} as {}", original_name, new_name));
+                    self.attach_to(new_name, None, None, None);
+                }
+                _ => {}
+            }
+        }
+    }
+    fn attach_to(
+        &mut self,
+        session_name: &str,
+        tab_position: Option<usize>,
+        pane_id: Option<(u32, bool)>,
+        layout: Option<(LayoutInfo, Option<PathBuf>)>, // with its cwd, a layout replaces the focus
+    ) {
+        // every switch to another session goes through here, so that none of them misses the
+        // history, the session_attached event or the post attach command
+        self.log_action(format!(
+            "attach to session {} (tab: {:?}, pane: {:?})",
+            session_name, tab_position, pane_id
+        ));
+        match layout {
+            Some((layout, cwd)) => switch_session_with_layout(Some(session_name), layout, cwd),
+            None => switch_session_with_focus(session_name, tab_position, pane_id),
+        }
+        self.attached_to(session_name, tab_position, pane_id);
+    }
+    fn attached_to(
+        &mut self,
+        session_name: &str,
+        tab_position: Option<usize>,
+        pane_id: Option<(u32, bool)>,
+    ) {
+        self.session_history.touch(session_name);
+        self.emit_session_attached(session_name, tab_position, pane_id);
+        self.run_post_attach_command(session_name);
+    }
+    fn emit_session_attached(
+        &self,
+        session_name: &str,
+        tab_position: Option<usize>,
+        pane_id: Option<(u32, bool)>,
+    ) {
+        let mut args = BTreeMap::new();
+        if let Some(tab_position) = tab_position {
+            args.insert("tab_position".to_owned(), tab_position.to_string());
+        }
+        if let Some((pane_id, is_plugin)) = pane_id {
+            args.insert("pane_id".to_owned(), pane_id.to_string());
+            args.insert("is_plugin".to_owned(), is_plugin.to_string());
+        }
+        self.emit_event("session_attached", session_name, args);
+    }
+    fn emit_event(&self, event_name: &str, session_name: &str, args: BTreeMap<String, String>) {
+        // the payload is the session name, the args hold whatever else the event has
+        if let Some(event_plugin) = &self.event_plugin {
+            pipe_message_to_plugin(
+                MessageToPlugin::new(event_name)
+                    .with_plugin_url(event_plugin)
+                    .with_payload(session_name)
+                    .with_args(args),
+            );
+        }
+    }
+    fn run_post_create_command(&mut self, session_name: &str, session_folder: Option<PathBuf>) {
+        // the session is created asynchronously, so the command might run before it's ready
+        let post_create_command = match &self.post_create_command {
+            Some(post_create_command) => post_create_command.clone(),
+            None => return,
+        };
+        let mut env_variables = BTreeMap::new();
+        env_variables.insert(
+            POST_CREATE_SESSION_NAME_ENV_VARIABLE.to_owned(),
+            session_name.to_owned(),
+        );
+        let mut context = BTreeMap::new();
+        context.insert(
+            POST_CREATE_COMMAND_REQUEST.to_owned(),
+            session_name.to_owned(),
+        );
+        self.log_action(format!(
+            "run post create command for {}: {}",
+            session_name, post_create_command
+        ));
+        run_command_with_env_variables_and_cwd(
+            &["sh", "-c", &post_create_command],
+            env_variables,
+            session_folder.unwrap_or_else(|| PathBuf::from(".")),
+            context,
+        );
+    }
+    fn run_post_attach_command(&mut self, session_name: &str) {
+        let post_attach_command = match &self.post_attach_command {
+            Some(post_attach_command) => post_attach_command.clone(),
+            None => return,
+        };
+        let mut env_variables = BTreeMap::new();
+        env_variables.insert(
+            POST_ATTACH_SESSION_NAME_ENV_VARIABLE.to_owned(),
+            session_name.to_owned(),
+        );
+        env_variables.insert(
+            POST_CREATE_SESSION_NAME_ENV_VARIABLE.to_owned(),
+            session_name.to_owned(),
+        );
+        let mut context = BTreeMap::new();
+        context.insert(
+            POST_ATTACH_COMMAND_REQUEST.to_owned(),
+            session_name.to_owned(),
+        );
+        self.log_action(format!(
+            "run post attach command for {}: {}",
+            session_name, post_attach_command
+        ));
+        run_command_with_env_variables_and_cwd(
+            &["sh", "-c", &post_attach_command],
+            env_variables,
+            get_plugin_ids().initial_cwd,
+            context,
+        );
+    }
+    fn toggle_entering_env_variables(&mut self) {
+        if self.new_session_info.entering_env_variables() {
+            if let Err(e) = self.new_session_info.env_variables() {
+                self.show_error(&e);
+                return;
+            }
+        } else if self.new_session_info.entering_initial_command() {
+            self.toggle_entering_initial_command();
+            if self.new_session_info.entering_initial_command() {
+                return;
+            }
+        }
+        self.new_session_info.toggle_entering_env_variables();
+    }
+    fn toggle_entering_initial_command(&mut self) {
+        if self.new_session_info.entering_initial_command() {
+            if let Err(e) = self.new_session_info.initial_command() {
+                self.show_error(&e);
+                return;
+            }
+        } else if self.new_session_info.entering_env_variables() {
+            self.toggle_entering_env_variables();
+            if self.new_session_info.entering_env_variables() {
+                return;
+            }
+        }
+        self.new_session_info.toggle_entering_initial_command();
+    }
+    fn handle_selection(&mut self) {
         match self.active_screen {
             ActiveScreen::New => {
+                if self.new_session_info.entering_env_variables() {
+                    // enter finishes editing the env variables
+                    self.toggle_entering_env_variables();
+                    return;
+                }
+                if self.new_session_info.entering_initial_command() {
+                    // enter finishes editing the initial command
+                    self.toggle_entering_initial_command();
+                    return;
+                }
+                // before trimming, which would make a blank name empty and so generated
+                if is_blank_session_name(self.new_session_info.name()) {
+                    self.show_error(self.language.text(Message::SessionNameBlank));
+                    return;
+                }
+                if self.trim_session_names {
+                    let trimmed_name = self.new_session_info.name().trim().to_owned();
+                    self.new_session_info.set_name(trimmed_name);
+                }
+                if self.new_session_info.entering_layout_search_term()
+                    && self.new_session_info.name().is_empty()
+                {
+                    let sessions = &self.sessions;
+                    let resurrectable_sessions = &self.resurrectable_sessions;
+                    let generated_name = generate_session_name(self.session_name_scheme, |name| {
+                        sessions.has_session(name)
+                            || sessions.has_forbidden_session(name)
+                            || resurrectable_sessions.has_session(name)
+                    });
+                    self.new_session_info.set_name(generated_name);
+                }
+                let has_env_variables = match self.new_session_info.env_variables() {
+                    Ok(env_variables) => !env_variables.is_empty(),
+                    Err(e) => {
+                        self.show_error(&e);
+                        return;
+                    }
+                };
+                let has_initial_command = match self.new_session_info.initial_command() {
+                    Ok(initial_command) => initial_command.is_some(),
+                    Err(e) => {
+                        self.show_error(&e);
+                        return;
+                    }
+                };
+                if has_initial_command
+                    && self.new_session_info.entering_layout_search_term()
+                    && !has_env_variables
+                {
+                    if self.sessions.has_session(self.new_session_info.name()) {
+                        self.show_error(
+                            self.language
+                                .text(Message::InitialCommandOnlyForNewSessions),
+                        );
+                        return;
+                    } else if matches!(
+                        self.new_session_info.selected_layout_info(),
+                        Some(LayoutInfo::Stringified(_))
+                    ) {
+                        self.show_error(
+                            self.language.text(Message::InitialCommandWithCurrentLayout),
+                        );
+                        return;
+                    }
+                }
+                if (has_env_variables || has_initial_command)
+                    && self.new_session_info.entering_layout_search_term()
+                    && !self.commands_allowed()
+                {
+                    // such sessions are created in the background from the cli
+                    return;
+                }
+                if has_env_variables && self.new_session_info.entering_layout_search_term() {
+                    if self.sessions.has_session(self.new_session_info.name()) {
+                        self.show_error(
+                            self.language.text(Message::EnvVariablesOnlyForNewSessions),
+                        );
+                        return;
+                    } else if matches!(
+                        self.new_session_info.selected_layout_info(),
+                        Some(LayoutInfo::Stringified(_))
+                    ) {
+                        self.show_error(self.language.text(Message::EnvVariablesWithCurrentLayout));
+                        return;
+                    }
+                }
                 if self.new_session_info.name().len() >= 108 {
                     // this is due to socket path limitations
                     // TODO: get this from Zellij (for reference: this is part of the interprocess
                     // package, we should get if from there if possible because it's configurable
                     // through the package)
-                    self.show_error("Session name must be shorter than 108 bytes");
+                    self.show_error(self.language.text(Message::SessionNameTooLong));
                     return;
                 } else if self.new_session_info.name().contains('/') {
-                    self.show_error("Session name cannot contain '/'");
+                    self.show_error(self.language.text(Message::SessionNameHasSlash));
                     return;
                 } else if self
                     .sessions
                     .has_forbidden_session(self.new_session_info.name())
                 {
-                    self.show_error("This session exists and web clients cannot attach to it.");
+                    self.show_error(self.language.text(Message::ForbiddenSession));
+                    return;
+                }
+                let mut created_session = None;
+                let mut attached_session = None;
+                if self.new_session_info.entering_layout_search_term() {
+                    // sessions with env variables or an initial command are attached to once
+                    // their background creation finishes
+                    if !has_env_variables
+                        && !has_initial_command
+                        && !self.new_session_info.name().is_empty()
+                        && self.session_name.as_deref() != Some(self.new_session_info.name())
+                    {
+                        attached_session = Some(self.new_session_info.name().to_owned());
+                    }
+                    self.log_action(format!(
+                        "create session {:?} with layout {:?}",
+                        self.new_session_info.name(),
+                        self.new_session_info
+                            .selected_layout_info()
+                            .map(|l| self.new_session_info.layout_name(&l).to_owned())
+                    ));
+                    if !self.sessions.has_session(self.new_session_info.name()) {
+                        created_session = Some((
+                            self.new_session_info.name().to_owned(),
+                            self.new_session_info.new_session_folder.clone(),
+                        ));
+                    }
+                }
+                if let Err(e) = self.new_session_info.handle_selection(&self.session_name) {
+                    self.show_error(&e);
                     return;
                 }
-                self.new_session_info.handle_selection(&self.session_name);
+                if let Some((session_name, session_folder)) = created_session {
+                    self.start_pending_operation(PendingOperation::Creating(session_name.clone()));
+                    self.run_post_create_command(&session_name, session_folder);
+                }
+                if let Some(session_name) = attached_session {
+                    // new_session_info switched to it, with the layout and folder it was given
+                    self.attached_to(&session_name, None, None);
+                }
+            }
+            ActiveScreen::Attach => {
+                self.handle_attach_selection(AttachFocus::from_preserve_focus(
+                    self.preserve_focus_on_attach,
+                ));
+            }
+            ActiveScreen::Resurrect => {
+                if let Some(session_name_to_resurrect) =
+                    self.resurrectable_sessions.get_selected_session_name()
+                {
+                    self.log_action(format!("resurrect session {}", session_name_to_resurrect));
+                    self.attach_to(&session_name_to_resurrect, None, None, None);
+                }
+            }
+        }
+    }
+    fn recall_search_term(&mut self, search_term: String) {
+        self.attach_search.term = search_term;
+        self.attach_search.move_cursor_to_end();
+        self.sessions
+            .update_search_term(&self.attach_search.term, &self.colors);
+        if self.attach_search.term.is_empty() {
+            self.reset_selected_index();
+        }
+    }
+    fn attach_focus_target(
+        &self,
+        attach_focus: AttachFocus,
+    ) -> (Option<usize>, Option<(u32, bool)>) {
+        // without a tab/pane the session keeps its own last focus
+        match attach_focus {
+            AttachFocus::Selected => (
+                self.sessions.get_selected_tab_position(),
+                self.sessions.get_selected_pane_id(),
+            ),
+            AttachFocus::Tab => (self.sessions.get_selected_tab_position(), None),
+            AttachFocus::SessionFocus => (None, None),
+        }
+    }
+    fn attach_target(&self) -> String {
+        // what Enter focuses
+        let attach_focus = AttachFocus::from_preserve_focus(self.preserve_focus_on_attach);
+        match self.attach_focus_target(attach_focus) {
+            (Some(tab_position), Some((pane_id, true))) => {
+                format!("⏎ tab {}, plugin pane {}", tab_position + 1, pane_id)
+            }
+            (Some(tab_position), Some((pane_id, false))) => {
+                format!("⏎ tab {}, pane {}", tab_position + 1, pane_id)
+            }
+            (Some(tab_position), None) => format!("⏎ tab {}", tab_position + 1),
+            _ => "⏎ last focused pane".to_owned(),
+        }
+    }
+    fn handle_attach_selection(&mut self, attach_focus: AttachFocus) {
+        if let Some(renaming_session_name) = self.renaming_session_name.take() {
+            // before trimming, which would make a blank name empty
+            if is_blank_session_name(&renaming_session_name) {
+                self.show_error(self.language.text(Message::SessionNameBlank));
+                return; // so that we don't hide self
+            }
+            let renaming_session_name = self.trimmed_session_name(renaming_session_name);
+            if renaming_session_name.is_empty() {
+                self.show_error(self.language.text(Message::NewNameEmpty));
+                return; // so that we don't hide self
+            } else if &self.session_name == &Some(renaming_session_name.clone()) {
+                // noop - we're already called that!
+                return; // so that we don't hide self
+            } else if self.sessions.has_session(&renaming_session_name) {
+                self.show_error(self.language.text(Message::SessionNameTaken));
+                return; // so that we don't hide self
+            } else if self
+                .resurrectable_sessions
+                .has_session(&renaming_session_name)
+            {
+                self.show_error(self.language.text(Message::ResurrectableSessionNameTaken));
+                return; // s that we don't hide self
+            } else {
+                if renaming_session_name.contains('/') {
+                    self.show_error(self.language.text(Message::SessionNameHasSlash));
+                    return;
+                }
+                self.update_current_session_name_in_ui(&renaming_session_name);
+                self.log_action(format!("rename session to {}", renaming_session_name));
+                self.start_pending_operation(PendingOperation::Renaming(
+                    renaming_session_name.clone(),
+                ));
+                rename_session(&renaming_session_name);
+                return; // s that we don't hide self
+            }
+        }
+        // typing a full session name is authoritative, even if the fuzzy ranking put another
+        // partial match above it
+        self.sessions
+            .select_exact_session_match(&self.attach_search.term);
+        if self.sessions.get_selected_session_name().is_none()
+            && self
+                .sessions
+                .has_forbidden_session(&self.attach_search.term)
+        {
+            // sessions that disallow web clients are not listed for web users, but their names
+            // can be typed
+            self.show_error(self.language.text(Message::ForbiddenSession));
+            return; // so that we don't hide self
+        }
+        if self.sessions.selected_is_remote_session() {
+            // the remote session is served by another web server, attaching to it means
+            // opening it there
+            if let (Some(remote_url), Some(selected_session_name)) = (
+                self.remote_url.clone(),
+                self.sessions.get_selected_session_name(),
+            ) {
+                self.show_notice(&self.language.format(
+                    Message::OpenRemoteSession,
+                    &[&format!("{}/{}", remote_url, selected_session_name)],
+                ));
+            }
+            return; // so that we don't hide self
+        }
+        if let Some(selected_session_name) = self.sessions.get_selected_session_name() {
+            let (selected_tab, selected_pane) = self.attach_focus_target(attach_focus);
+            let is_current_session = self.sessions.selected_is_current_session();
+            if is_current_session {
+                match self.current_session_enter {
+                    CurrentSessionEnter::Focus => {
+                        if let Some((pane_id, is_plugin)) = selected_pane {
+                            if is_plugin {
+                                focus_plugin_pane(pane_id, true);
+                            } else {
+                                focus_terminal_pane(pane_id, true);
+                            }
+                        } else if let Some(tab_position) = selected_tab {
+                            go_to_tab(tab_position as u32);
+                        }
+                        // otherwise we're already attached, so we just return to where we were
+                    }
+                    CurrentSessionEnter::Hide => {} // nothing to focus, we only hide below
+                    CurrentSessionEnter::Noop => {
+                        self.show_notice(
+                            &self
+                                .language
+                                .format(Message::AlreadyAttached, &[&selected_session_name]),
+                        );
+                        return; // so that we don't hide self
+                    }
+                }
+            } else {
+                self.attach_to(&selected_session_name, selected_tab, selected_pane, None);
+            }
+        }
+        self.reset_search_and_hide();
+    }
+    fn kill_session(&mut self, session_name: String) {
+        self.log_action(format!("kill session {}", session_name));
+        kill_sessions(&[&session_name]);
+        self.start_pending_operation(PendingOperation::Killing(vec![session_name.clone()]));
+        self.session_killed(&session_name);
+        self.reset_selected_index();
+        self.search_history.record(&self.attach_search.term);
+        self.attach_search.clear();
+        self.sessions
+            .update_search_term(&self.attach_search.term, &self.colors);
+    }
+    fn force_kill_session(&mut self, session_name: String) {
+        // the host has no force kill for plugins (only kill_sessions), so this falls back to a
+        // regular kill and says so
+        self.kill_session(session_name.clone());
+        self.show_notice(
+            &self
+                .language
+                .format(Message::ForceKillUnsupported, &[&session_name]),
+        );
+    }
+    fn kill_selection_range(&mut self, session_names: Vec<String>) {
+        self.log_action(format!("kill sessions {:?}", session_names));
+        kill_sessions(&session_names);
+        self.start_pending_operation(PendingOperation::Killing(session_names.clone()));
+        for session_name in &session_names {
+            self.session_killed(session_name);
+        }
+        self.reset_selected_index();
+        self.search_history.record(&self.attach_search.term);
+        self.attach_search.clear();
+        self.sessions
+            .update_search_term(&self.attach_search.term, &self.colors);
+        self.show_notice(
+            &self
+                .language
+                .format(Message::KilledSessions, &[&session_names.len().to_string()]),
+        );
+    }
+    fn session_killed(&mut self, session_name: &str) {
+        // remember the session as it was, so that it can be re-created from the resurrect screen
+        let killed_session = match self.sessions.get_session_ui_info(session_name) {
+            Some(session_ui_info) => KilledSession {
+                name: session_name.to_owned(),
+                layout: Some(session_ui_info.to_layout_kdl()),
+                cwd: session_ui_info.cwd.as_ref().map(PathBuf::from),
+                tab_count: session_ui_info.tabs.len(),
+            },
+            None => KilledSession {
+                name: session_name.to_owned(),
+                layout: None,
+                cwd: None,
+                tab_count: 0,
+            },
+        };
+        self.recently_killed.record(killed_session);
+        self.emit_event("session_killed", session_name, BTreeMap::new());
+    }
+    fn recreate_killed_session(&mut self) {
+        let killed_session = match self.recently_killed.selected() {
+            Some(killed_session) => killed_session.clone(),
+            None => return,
+        };
+        if self.sessions.has_session(&killed_session.name) {
+            self.show_error(
+                &self
+                    .language
+                    .format(Message::SessionAlreadyRunning, &[&killed_session.name]),
+            );
+            return;
+        }
+        self.recently_killed.take_selected();
+        self.log_action(format!("re-create killed session {}", killed_session.name));
+        let KilledSession {
+            name, layout, cwd, ..
+        } = killed_session;
+        let layout = layout.map(|layout| (LayoutInfo::Stringified(layout), cwd));
+        self.attach_to(&name, None, None, layout);
+    }
+    fn open_attach_layout_picker(&mut self) {
+        if self.sessions.selected_is_remote_session() {
+            self.show_error(self.language.text(Message::LayoutOnlyForLocalSessions));
+            return;
+        }
+        match self.sessions.get_selected_session_name() {
+            Some(session_name) => {
+                self.new_session_info.start_layout_picker();
+                self.attach_layout_picker = Some(session_name);
+            }
+            None => self.show_error(self.language.text(Message::MustSelectSessionToAttach)),
+        }
+    }
+    fn handle_attach_layout_picker_key(&mut self, key: KeyWithModifier) -> bool {
+        match key.bare_key {
+            BareKey::Esc if key.has_no_modifiers() => {
+                self.attach_layout_picker = None;
+            }
+            BareKey::Enter if key.has_no_modifiers() => {
+                self.attach_with_picked_layout();
+            }
+            _ => return self.new_session_info.handle_layout_picker_key(key),
+        }
+        true
+    }
+    fn attach_with_picked_layout(&mut self) {
+        let session_name = match self.attach_layout_picker.take() {
+            Some(session_name) => session_name,
+            None => return,
+        };
+        let layout = match self.new_session_info.selected_layout_info() {
+            Some(layout) => layout,
+            None => {
+                self.show_error(self.language.text(Message::NoLayoutMatches));
+                return;
+            }
+        };
+        self.log_action(format!(
+            "attach to session {} with layout {}",
+            session_name,
+            self.new_session_info.layout_name(&layout)
+        ));
+        // the host cannot rearrange the panes of a running session, applying a layout to it opens
+        // the layout in new tabs next to the existing ones
+        if self.session_name.as_ref() == Some(&session_name) {
+            new_tabs_with_layout_info(layout);
+        } else {
+            self.attach_to(&session_name, None, None, Some((layout, None)));
+        }
+        self.reset_search_and_hide();
+    }
+    fn open_tab_picker(&mut self) {
+        if self.sessions.selected_is_remote_session() {
+            self.show_error(self.language.text(Message::TabsOnlyForLocalSessions));
+            return;
+        }
+        let tab_picker = match self.sessions.get_selected_session_ui_info() {
+            Some(session_ui_info) => {
+                TabPicker::new(session_ui_info.name.clone(), &session_ui_info.tabs)
+            }
+            None => {
+                self.show_error(self.language.text(Message::MustSelectSessionToPickTab));
+                return;
+            }
+        };
+        if tab_picker.is_empty() {
+            self.show_error(self.language.text(Message::SessionHasNoTabs));
+            return;
+        }
+        self.tab_picker = Some(tab_picker);
+    }
+    fn handle_tab_picker_key(&mut self, key: KeyWithModifier) -> bool {
+        let tab_picker = match self.tab_picker.as_mut() {
+            Some(tab_picker) => tab_picker,
+            None => return false,
+        };
+        match key.bare_key {
+            BareKey::Esc if key.has_no_modifiers() => {
+                self.tab_picker = None;
+            }
+            BareKey::Enter if key.has_no_modifiers() => {
+                self.attach_to_picked_tab();
+            }
+            BareKey::Down if key.has_no_modifiers() => tab_picker.move_selection_down(),
+            BareKey::Up if key.has_no_modifiers() => tab_picker.move_selection_up(),
+            BareKey::Char('n') | BareKey::Char('j') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                tab_picker.move_selection_down()
+            }
+            BareKey::Char('p') | BareKey::Char('k') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                tab_picker.move_selection_up()
+            }
+            _ => return false,
+        }
+        true
+    }
+    fn attach_to_picked_tab(&mut self) {
+        let (session_name, tab_position) = match self.tab_picker.take() {
+            Some(tab_picker) => match tab_picker.selected_tab_position() {
+                Some(tab_position) => (tab_picker.session_name, tab_position),
+                None => return,
+            },
+            None => return,
+        };
+        if self.session_name.as_ref() == Some(&session_name) {
+            go_to_tab(tab_position as u32);
+        } else {
+            self.attach_to(&session_name, Some(tab_position), None, None);
+        }
+        self.reset_search_and_hide();
+    }
+    fn open_resurrect_layout_picker(&mut self) {
+        if self.resurrectable_sessions.deleting_disabled {
+            // the stored layout is only replaced by deleting the resurrectable session
+            self.show_error(self.language.text(Message::ResurrectWithLayoutDisabled));
+            return;
+        }
+        if let Some(session_name) = self.resurrectable_sessions.get_selected_session_name() {
+            self.new_session_info.start_layout_picker();
+            self.resurrect_layout_picker = Some(session_name);
+        }
+    }
+    fn handle_resurrect_layout_picker_key(&mut self, key: KeyWithModifier) -> bool {
+        match key.bare_key {
+            BareKey::Esc if key.has_no_modifiers() => {
+                self.resurrect_layout_picker = None;
+            }
+            BareKey::Enter if key.has_no_modifiers() => {
+                self.resurrect_with_picked_layout();
+            }
+            _ => return self.new_session_info.handle_layout_picker_key(key),
+        }
+        true
+    }
+    fn resurrect_with_picked_layout(&mut self) {
+        let session_name = match self.resurrect_layout_picker.take() {
+            Some(session_name) => session_name,
+            None => return,
+        };
+        match self.new_session_info.selected_layout_info() {
+            Some(layout) => {
+                self.pending_confirmation = Some(PendingConfirmation::ResurrectWithLayout(
+                    session_name,
+                    layout,
+                ));
+            }
+            None => self.show_error(self.language.text(Message::NoLayoutMatches)),
+        }
+    }
+    fn resurrect_with_layout(&mut self, session_name: String, layout: LayoutInfo) {
+        self.log_action(format!(
+            "resurrect session {} with layout {}",
+            session_name,
+            self.new_session_info.layout_name(&layout)
+        ));
+        // a resurrected session always gets its stored layout, so we drop it and create the
+        // session anew under the same name
+        delete_dead_session(&session_name);
+        self.attach_to(&session_name, None, None, Some((layout, None)));
+    }
+    fn handle_recently_killed_key(&mut self, key: KeyWithModifier) -> bool {
+        let mut should_render = false;
+        match key.bare_key {
+            BareKey::Down if key.has_no_modifiers() => {
+                self.recently_killed.move_selection_down();
+                should_render = true;
+            }
+            BareKey::Up if key.has_no_modifiers() => {
+                self.recently_killed.move_selection_up();
+                should_render = true;
+            }
+            BareKey::Char('n') | BareKey::Char('j') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.recently_killed.move_selection_down();
+                should_render = true;
+            }
+            BareKey::Char('p') | BareKey::Char('k') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.recently_killed.move_selection_up();
+                should_render = true;
+            }
+            BareKey::Enter if key.has_no_modifiers() => {
+                self.recreate_killed_session();
+                should_render = true;
+            }
+            BareKey::Char('f') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.recently_killed.toggle_visible();
+                should_render = true;
+            }
+            BareKey::Tab if key.has_no_modifiers() => {
+                self.toggle_active_screen();
+                should_render = true;
+            }
+            BareKey::Char(digit @ '1'..='3') if key.has_modifiers(&[KeyModifier::Alt]) => {
+                self.switch_to_screen_number(digit);
+                should_render = true;
+            }
+            BareKey::Tab if key.has_modifiers(&[KeyModifier::Shift]) => {
+                self.toggle_active_screen_reverse();
+                should_render = true;
+            }
+            _ => {}
+        }
+        should_render
+    }
+    fn reset_search_and_hide(&mut self) {
+        self.reset_selected_index();
+        self.search_history.record(&self.attach_search.term);
+        self.attach_search.clear();
+        self.sessions
+            .update_search_term(&self.attach_search.term, &self.colors);
+        if !self.is_welcome_screen {
+            // we usually don't want to hide_self() if we're the welcome screen because
+            // unless the user did something odd like opening an extra pane/tab in the
+            // welcome screen, this will result in the current session closing, as this is
+            // the last selectable pane...
+            hide_self();
+        }
+    }
+    fn swap_with_previous_session(&mut self) {
+        // back to the session we came from, or (the first time) to the selected one - which
+        // then remembers this one as its previous session, so that the same key swaps back
+        let previous_session = self
+            .previous_session
+            .clone()
+            .filter(|s| self.sessions.has_session(s));
+        match previous_session {
+            Some(session_name) => {
+                self.log_action(format!("swap to previous session {}", session_name));
+                self.attach_to(&session_name, None, None, None);
+                self.reset_search_and_hide();
             }
-            ActiveScreen::Attach => {
-                if let Some(renaming_session_name) = self.renaming_session_name.take() {
-                    if renaming_session_name.is_empty() {
-                        self.show_error("New name must not be empty.");
-                        return; // so that we don't hide self
-                    } else if &self.session_name == &Some(renaming_session_name.clone()) {
-                        // noop - we're already called that!
-                        return; // so that we don't hide self
-                    } else if self.sessions.has_session(&renaming_session_name) {
-                        self.show_error("A session by this name already exists.");
-                        return; // so that we don't hide self
-                    } else if self
-                        .resurrectable_sessions
-                        .has_session(&renaming_session_name)
-                    {
-                        self.show_error("A resurrectable session by this name already exists.");
-                        return; // s that we don't hide self
-                    } else {
-                        if renaming_session_name.contains('/') {
-                            self.show_error("Session names cannot contain '/'");
-                            return;
-                        }
-                        self.update_current_session_name_in_ui(&renaming_session_name);
-                        rename_session(&renaming_session_name);
-                        return; // s that we don't hide self
-                    }
-                }
-                if let Some(selected_session_name) = self.sessions.get_selected_session_name() {
-                    let selected_tab = self.sessions.get_selected_tab_position();
-                    let selected_pane = self.sessions.get_selected_pane_id();
-                    let is_current_session = self.sessions.selected_is_current_session();
-                    if is_current_session {
-                        if let Some((pane_id, is_plugin)) = selected_pane {
-                            if is_plugin {
-                                focus_plugin_pane(pane_id, true);
-                            } else {
-                                focus_terminal_pane(pane_id, true);
-                            }
-                        } else if let Some(tab_position) = selected_tab {
-                            go_to_tab(tab_position as u32);
-                        } else {
-                            self.show_error("Already attached...");
-                        }
-                    } else {
-                        switch_session_with_focus(
-                            &selected_session_name,
-                            selected_tab,
-                            selected_pane,
-                        );
-                    }
-                }
-                self.reset_selected_index();
-                self.search_term.clear();
-                self.search_cursor = 0;
-                self.sessions
-                    .update_search_term(&self.search_term, &self.colors);
-                if !self.is_welcome_screen {
-                    // we usually don't want to hide_self() if we're the welcome screen because
-                    // unless the user did something odd like opening an extra pane/tab in the
-                    // welcome screen, this will result in the current session closing, as this is
-                    // the last selectable pane...
-                    hide_self();
-                }
+            None if self.sessions.get_selected_session_name().is_some()
+                && !self.sessions.selected_is_current_session() =>
+            {
+                self.handle_attach_selection(AttachFocus::SessionFocus);
             }
-            ActiveScreen::Resurrect => {
-                if let Some(session_name_to_resurrect) =
-                    self.resurrectable_sessions.get_selected_session_name()
-                {
-                    switch_session(Some(&session_name_to_resurrect));
+            None => self.show_error(self.language.text(Message::NoPreviousSession)),
+        }
+    }
+    fn attach_to_most_recent_session(&mut self, pipe_source: &PipeSource) {
+        let sessions = &self.sessions;
+        let most_recent_session = self
+            .session_history
+            .most_recent_session(self.session_name.as_deref(), |s| sessions.has_session(s));
+        match most_recent_session {
+            Some(session_name) => {
+                self.log_action(format!("attach to most recent session {}", session_name));
+                self.attach_to(&session_name, None, None, None);
+            }
+            None => {
+                if let PipeSource::Cli(pipe_id) = pipe_source {
+                    cli_pipe_output(pipe_id, "No recently used session to attach to\n");
                 }
             }
         }
     }
+    fn open_folder_picker(&mut self) {
+        let request_id = Uuid::new_v4();
+        let mut config = BTreeMap::new();
+        let mut args = BTreeMap::new();
+        self.request_ids.push(request_id.to_string());
+        // we insert this into the config so that a new plugin will be opened (the plugin's
+        // uniqueness is determined by its name/url as well as its config)
+        config.insert("request_id".to_owned(), request_id.to_string());
+        // we also insert this into the args so that the plugin will have an easier access to
+        // it
+        args.insert("request_id".to_owned(), request_id.to_string());
+        let mut message = MessageToPlugin::new("filepicker")
+            .with_plugin_url("filepicker")
+            .with_plugin_config(config)
+            .new_plugin_instance_should_have_pane_title("Select folder for the new session...")
+            .new_plugin_instance_should_be_focused()
+            .with_args(args);
+        // start from the previously picked folder so the user can drill down further rather
+        // than starting over
+        if let Some(last_picked_folder) = self.new_session_info.last_picked_folder.clone() {
+            message = message.new_plugin_instance_should_have_cwd(last_picked_folder);
+        }
+        pipe_message_to_plugin(message);
+    }
     fn toggle_active_screen(&mut self) {
-        self.active_screen = match self.active_screen {
-            ActiveScreen::New => ActiveScreen::Attach,
-            ActiveScreen::Attach => ActiveScreen::Resurrect,
-            ActiveScreen::Resurrect => ActiveScreen::New,
+        let previous_screen = self.active_screen;
+        let screen_count = self.enabled_screens.len();
+        if let Some(position) = self
+            .enabled_screens
+            .iter()
+            .position(|s| *s == self.active_screen)
+        {
+            self.active_screen = self.enabled_screens[(position + 1) % screen_count];
+        } else if let Some(first_screen) = self.enabled_screens.first() {
+            self.active_screen = *first_screen;
+        }
+        self.screen_switched(previous_screen);
+    }
+    fn switch_to_screen_number(&mut self, digit: char) {
+        let screen = match digit {
+            '1' => ActiveScreen::New,
+            '2' => ActiveScreen::Attach,
+            _ => ActiveScreen::Resurrect,
         };
+        if self.enabled_screens.contains(&screen) {
+            let previous_screen = self.active_screen;
+            self.active_screen = screen;
+            self.screen_switched(previous_screen);
+        } else {
+            self.flash();
+        }
     }
     fn toggle_active_screen_reverse(&mut self) {
-        self.active_screen = match self.active_screen {
-            ActiveScreen::New => ActiveScreen::Resurrect,
-            ActiveScreen::Attach => ActiveScreen::New,
-            ActiveScreen::Resurrect => ActiveScreen::Attach,
+        let previous_screen = self.active_screen;
+        let screen_count = self.enabled_screens.len();
+        if let Some(position) = self
+            .enabled_screens
+            .iter()
+            .position(|s| *s == self.active_screen)
+        {
+            self.active_screen = self.enabled_screens[(position + screen_count - 1) % screen_count];
+        } else if let Some(first_screen) = self.enabled_screens.first() {
+            self.active_screen = *first_screen;
+        }
+        self.screen_switched(previous_screen);
+    }
+    fn screen_switched(&mut self, previous_screen: ActiveScreen) {
+        save_active_screen(self.active_screen);
+        if self.active_screen == ActiveScreen::New {
+            self.apply_default_new_session_cwd();
+        }
+        if previous_screen == ActiveScreen::Attach
+            && self.active_screen == ActiveScreen::New
+            && self.carry_search_to_new
+            && self.new_session_info.name().is_empty()
+        {
+            // what didn't match is likely the session to create, without the #tag filters
+            let (_, search_text) = parse_tag_filters(&self.attach_search.term);
+            let (session_name, _) = strip_pasted_session_name(search_text.trim());
+            self.new_session_info.set_name(session_name);
+        }
+        if self.active_screen == ActiveScreen::Attach {
+            // the sessions might have changed while we were away, so the search we return to is
+            // applied again rather than showing stale results
+            self.sessions
+                .update_search_term(&self.attach_search.term, &self.colors);
+        }
+    }
+    fn trimmed_session_name(&self, session_name: String) -> String {
+        if self.trim_session_names {
+            session_name.trim().to_owned()
+        } else {
+            session_name
+        }
+    }
+    fn confirm(&mut self, pending_confirmation: PendingConfirmation) {
+        match pending_confirmation {
+            PendingConfirmation::KillAllSessions(all_other_sessions) => {
+                // the sessions counted in the warning, not the ones running now, so that what is
+                // killed is what was confirmed
+                self.log_action(format!("kill sessions {:?}", all_other_sessions));
+                kill_sessions(&all_other_sessions);
+                self.start_pending_operation(PendingOperation::Killing(all_other_sessions.clone()));
+                for session_name in &all_other_sessions {
+                    self.session_killed(session_name);
+                }
+                self.reset_selected_index();
+                self.search_history.record(&self.attach_search.term);
+                self.attach_search.clear();
+                self.sessions
+                    .update_search_term(&self.attach_search.term, &self.colors);
+            }
+            PendingConfirmation::KillSessions(session_names) => {
+                self.kill_selection_range(session_names)
+            }
+            PendingConfirmation::KillSession(session_name) => self.kill_session(session_name),
+            PendingConfirmation::ForceKillSession(session_name) => {
+                self.force_kill_session(session_name)
+            }
+            PendingConfirmation::DisconnectOthers => self.disconnect_other_clients(),
+            PendingConfirmation::AutoAttach(_) => {
+                // best effort, if it cannot be saved we ask again next time
+                let _ = std::fs::write(AUTO_ATTACH_CONFIRMED_FILE, "");
+                self.attach_to_single_match();
+            }
+            PendingConfirmation::DiscardNewSession => hide_self(),
+            PendingConfirmation::ResurrectWithLayout(session_name, layout) => {
+                self.resurrect_with_layout(session_name, layout)
+            }
+            PendingConfirmation::OverwriteLayoutFile(file_name, layout) => {
+                self.write_layout_file(file_name, layout)
+            }
+        }
+    }
+    fn auto_attach_to_single_match(&mut self) {
+        let session_name = match self.sessions.single_match.clone() {
+            Some(session_name) if self.auto_attach_single => session_name,
+            _ => return,
         };
+        if std::fs::metadata(AUTO_ATTACH_CONFIRMED_FILE).is_err() {
+            self.pending_confirmation = Some(PendingConfirmation::AutoAttach(session_name));
+            return;
+        }
+        self.attach_to_single_match();
+    }
+    fn attach_to_single_match(&mut self) {
+        self.sessions.selected_search_index = Some(0);
+        self.handle_attach_selection(AttachFocus::from_preserve_focus(
+            self.preserve_focus_on_attach,
+        ));
     }
     fn show_error(&mut self, error_text: &str) {
         self.error = Some(error_text.to_owned());
     }
+    fn show_notice(&mut self, notice_text: &str) {
+        self.notice = Some(notice_text.to_owned());
+    }
+    fn log_action(&mut self, description: String) {
+        log!(LogLevel::Info, "{}", description);
+        if self.is_debug {
+            self.action_log.record(description);
+        }
+    }
+    fn update_remote_sessions(&mut self) {
+        // attaching to a remote session opens it on the remote web server, which only a web
+        // client can follow, so terminal clients do not list them
+        let remote_session_names = if self.remote_sessions && self.is_web_client {
+            self.remote_session_names.clone()
+        } else {
+            vec![]
+        };
+        self.sessions.set_remote_sessions(remote_session_names);
+    }
+    fn commands_allowed(&mut self) -> bool {
+        // for the actions that shell out, the RunCommands permission is only asked for with
+        // run_commands (or a configured command)
+        if !self.run_commands {
+            self.show_error(
+                &self
+                    .language
+                    .format(Message::NeedsConfig, &["run_commands"]),
+            );
+        }
+        self.run_commands
+    }
+    fn move_session_selection_down(&mut self, count: usize) {
+        for _ in 0..count {
+            if !self.sessions.move_selection_down() {
+                self.flash();
+                break;
+            }
+        }
+    }
+    fn move_session_selection_up(&mut self, count: usize) {
+        for _ in 0..count {
+            if !self.sessions.move_selection_up() {
+                self.flash();
+                break;
+            }
+        }
+    }
+    fn flash(&mut self) {
+        // subtle feedback for minor invalid actions that do not deserve an error
+        if self.feedback_flash {
+            self.flash_until_render = 1;
+            set_timeout(0.15);
+        }
+    }
+    fn send_notification(&mut self, text: &str) {
+        if !self.notify {
+            return;
+        }
+        let mut env_variables = BTreeMap::new();
+        env_variables.insert(NOTIFY_TEXT_ENV_VARIABLE.to_owned(), text.to_owned());
+        let mut context = BTreeMap::new();
+        context.insert(NOTIFY_REQUEST.to_owned(), String::new());
+        run_command_with_env_variables_and_cwd(
+            &["sh", "-c", NOTIFY_COMMAND],
+            env_variables,
+            get_plugin_ids().initial_cwd,
+            context,
+        );
+    }
+    fn start_pending_operation(&mut self, pending_operation: PendingOperation) {
+        self.pending_operation = Some(pending_operation);
+        self.pending_operation_secs = 0.0;
+        if !self.spinner_timer_running {
+            self.start_spinner_timer();
+        }
+    }
+    fn start_spinner_timer(&mut self) {
+        self.spinner_timer_running = true;
+        set_timeout(SPINNER_INTERVAL_SECS);
+    }
+    fn restart_auto_hide_timer(&mut self) {
+        // the welcome screen is all there is to the session, so it never hides itself
+        if self.auto_hide_secs > 0 && !self.is_welcome_screen {
+            self.auto_hide_timers += 1;
+            set_timeout(self.auto_hide_secs as f64);
+        }
+    }
+    fn is_auto_hide_timer(&self, elapsed: f64) -> bool {
+        // timer events only tell how long they slept (never less than asked), and the flash timer
+        // is far shorter than a second
+        self.auto_hide_secs > 0 && elapsed >= self.auto_hide_secs as f64
+    }
+    fn open_selected_session_in_new_window(&mut self) {
+        if !self.is_web_client {
+            // a terminal client only has the one window, so this is a regular attach
+            self.handle_attach_selection(AttachFocus::from_preserve_focus(
+                self.preserve_focus_on_attach,
+            ));
+            return;
+        }
+        let remote_url = match &self.remote_url {
+            Some(remote_url) => remote_url.clone(),
+            None => {
+                self.show_error(self.language.text(Message::NewWindowNeedsRemoteUrl));
+                return;
+            }
+        };
+        let session_name = match self.sessions.get_selected_session_name() {
+            Some(session_name) => session_name,
+            None => {
+                self.show_error(self.language.text(Message::MustSelectSessionToOpen));
+                return;
+            }
+        };
+        // plugins have no host action that opens a browser window, so we hand out the url of
+        // the session for the user to open next to this one
+        let session_url = format!("{}/{}", remote_url, session_name);
+        self.log_action(format!("open {} in a new window", session_url));
+        self.show_notice(
+            &self
+                .language
+                .format(Message::OpenInNewWindow, &[&session_url]),
+        );
+    }
+    fn copy_attach_command_of_selected_session(&mut self) {
+        let session_name = match self.sessions.get_selected_session_name() {
+            Some(session_name) => session_name,
+            None => {
+                self.show_error(self.language.text(Message::MustSelectSessionToCopy));
+                return;
+            }
+        };
+        if !self.commands_allowed() {
+            return;
+        }
+        // web users share the url rather than a command they might not be able to run
+        let text = match &self.remote_url {
+            Some(remote_url)
+                if self.is_web_client || self.sessions.selected_is_remote_session() =>
+            {
+                format!("{}/{}", remote_url, session_name)
+            }
+            _ => format!("zellij attach {}", shell_quote(&session_name)),
+        };
+        let copy_command = self
+            .copy_command
+            .clone()
+            .unwrap_or_else(|| DEFAULT_COPY_COMMAND.to_owned());
+        let mut env_variables = BTreeMap::new();
+        env_variables.insert(COPY_TEXT_ENV_VARIABLE.to_owned(), text.clone());
+        let mut context = BTreeMap::new();
+        context.insert(COPY_ATTACH_COMMAND_REQUEST.to_owned(), text.clone());
+        self.log_action(format!("copy {:?} with {}", text, copy_command));
+        run_command_with_env_variables_and_cwd(
+            &[
+                "sh",
+                "-c",
+                &format!(
+                    "printf '%s' \"${}\" | {{ {}; }}",
+                    COPY_TEXT_ENV_VARIABLE, copy_command
+                ),
+            ],
+            env_variables,
+            get_plugin_ids().initial_cwd,
+            context,
+        );
+    }
+    fn save_layout_of_selected_session(&mut self) {
+        // there is no clipboard api for plugins, so the layout is written next to where the
+        // plugin was started (mapped to /host)
+        if !self.sessions.is_expanded() {
+            self.show_error(self.language.text(Message::ExpandToSaveLayout));
+            return;
+        }
+        let (session_name, layout) = match self.sessions.get_selected_session_ui_info() {
+            Some(session_ui_info) if !session_ui_info.is_remote => (
+                session_ui_info.name.clone(),
+                session_ui_info.to_layout_kdl(),
+            ),
+            Some(_) => {
+                self.show_error(self.language.text(Message::RemoteLayoutUnknown));
+                return;
+            }
+            None => {
+                self.show_error(self.language.text(Message::MustSelectSessionToSaveLayout));
+                return;
+            }
+        };
+        let file_name = format!("{}.kdl", session_name);
+        if std::path::Path::new("/host").join(&file_name).exists() {
+            // eg. a layout saved earlier and edited since
+            self.pending_confirmation =
+                Some(PendingConfirmation::OverwriteLayoutFile(file_name, layout));
+        } else {
+            self.write_layout_file(file_name, layout);
+        }
+    }
+    fn write_layout_file(&mut self, file_name: String, layout: String) {
+        match std::fs::write(std::path::Path::new("/host").join(&file_name), layout) {
+            Ok(()) => {
+                self.log_action(format!("save layout to {}", file_name));
+                self.show_notice(
+                    &self.language.format(
+                        Message::SavedLayout,
+                        &[&get_plugin_ids()
+                            .initial_cwd
+                            .join(&file_name)
+                            .display()
+                            .to_string()],
+                    ),
+                );
+            }
+            Err(e) => self.show_error(
+                &self
+                    .language
+                    .format(Message::FailedToSaveLayout, &[&e.to_string()]),
+            ),
+        }
+    }
+    fn toggle_web_sharing_of_selected_session(&mut self) {
+        if !self.web_sharing {
+            self.show_error(&self.language.format(Message::NeedsConfig, &["web_sharing"]));
+            return;
+        } else if !self.sessions.selected_is_current_session() {
+            self.show_error(self.language.text(Message::WebSharingOnlyCurrentSession));
+            return;
+        } else if self.permissions_denied {
+            self.show_error(self.language.text(Message::WebSharingNotPermitted));
+            return;
+        }
+        // the indicator updates with the next SessionUpdate
+        match self.sessions.current_session_allows_web_clients() {
+            Some(true) => {
+                self.log_action("stop sharing the current session".to_owned());
+                stop_sharing_current_session();
+                self.show_notice(self.language.text(Message::StoppedWebSharing));
+            }
+            Some(false) => {
+                self.log_action("share the current session".to_owned());
+                share_current_session();
+                self.show_notice(self.language.text(Message::StartedWebSharing));
+            }
+            None => {}
+        }
+    }
+    fn disconnect_other_clients(&mut self) {
+        self.log_action("disconnect other clients".to_owned());
+        disconnect_other_clients();
+        self.show_notice(self.language.text(Message::DisconnectedOtherClients));
+    }
     fn update_current_session_name_in_ui(&mut self, new_name: &str) {
         if let Some(old_session_name) = &self.session_name {
             self.sessions
@@ -871,20 +4081,25 @@ impl State {
         self.session_name = Some(new_name.to_owned());
     }
     fn update_session_infos(&mut self, session_infos: Vec<SessionInfo>) {
+        // there is no "only my sessions" filter: SessionInfo carries no owner and the sessions we
+        // get are the ones in the current user's socket folder anyway
         let session_ui_infos: Vec<SessionUiInfo> = session_infos
             .iter()
             .filter_map(|s| {
                 if self.is_web_client && !s.web_clients_allowed {
                     None
-                } else if self.is_welcome_screen && s.is_current_session {
+                } else {
+                    let mut session_ui_info = SessionUiInfo::from_session_info(s);
                     // do not display current session if we're the welcome screen
                     // because:
                     // 1. attaching to the welcome screen from the welcome screen is not a thing
                     // 2. it can cause issues on the web (since we're disconnecting and
                     //    reconnecting to a session we just closed by disconnecting...)
-                    None
-                } else {
-                    Some(SessionUiInfo::from_session_info(s))
+                    // it is still kept (hidden) so that the session list can reveal it
+                    session_ui_info.is_hidden = (self.is_welcome_screen
+                        || self.hide_current_session)
+                        && s.is_current_session;
+                    Some(session_ui_info)
                 }
             })
             .collect();
@@ -906,10 +4121,163 @@ impl State {
             }
         });
         if let Some(current_session_name) = current_session_name {
+            self.session_history.touch(&current_session_name);
             self.session_name = Some(current_session_name);
         }
+        // each session has its own instance of this plugin, so which one came before is taken
+        // from the shared session history, which every attach updates
+        self.previous_session = self
+            .session_history
+            .most_recent_session(self.session_name.as_deref(), |s| {
+                session_infos.iter().any(|i| i.name == s)
+            });
+        if let Some(current_session) = session_infos.iter().find(|s| s.is_current_session) {
+            // the layout dump is relatively expensive, so we only ask for it again when the
+            // shape of the current session changes
+            let layout_signature = (
+                current_session.tabs.len(),
+                current_session
+                    .panes
+                    .panes
+                    .values()
+                    .map(|p| p.len())
+                    .sum::<usize>(),
+            );
+            if !self.is_welcome_screen && self.current_layout_signature != Some(layout_signature) {
+                self.current_layout_signature = Some(layout_signature);
+                dump_session_layout();
+            }
+        }
         self.sessions
             .set_sessions(session_ui_infos, forbidden_sessions);
+        self.request_missing_session_cwds();
+        self.expand_default_new_session_cwd();
+        self.request_home_folder();
+        self.list_new_session_folder();
+    }
+    fn request_home_folder(&mut self) {
+        // once, after the first session update like expand_default_new_session_cwd
+        if !self.run_commands || self.home_folder_requested {
+            return;
+        }
+        self.home_folder_requested = true;
+        let mut context = BTreeMap::new();
+        context.insert(HOME_FOLDER_REQUEST.to_owned(), String::new());
+        run_command_with_env_variables_and_cwd(
+            &["sh", "-c", "printf '%s' \"$HOME\""],
+            BTreeMap::new(),
+            get_plugin_ids().initial_cwd,
+            context,
+        );
+    }
+    fn expand_default_new_session_cwd(&mut self) {
+        // only once, and only after the first session update since by then we're allowed to run
+        // commands
+        let unexpanded_default_new_session_cwd =
+            match self.unexpanded_default_new_session_cwd.take() {
+                Some(unexpanded_default_new_session_cwd) => unexpanded_default_new_session_cwd,
+                None => return,
+            };
+        // the variable names are passed as arguments, so the config value itself never reaches
+        // the shell
+        let variable_names = cwd_variable_names(&unexpanded_default_new_session_cwd);
+        let mut command = vec!["sh", "-c", PRINT_ENV_VARIABLES_COMMAND, "sh"];
+        command.extend(variable_names.iter().map(|v| v.as_str()));
+        let mut context = BTreeMap::new();
+        context.insert(
+            DEFAULT_CWD_REQUEST.to_owned(),
+            unexpanded_default_new_session_cwd,
+        );
+        run_command_with_env_variables_and_cwd(
+            &command,
+            BTreeMap::new(),
+            get_plugin_ids().initial_cwd,
+            context,
+        );
+    }
+    fn request_git_root(&mut self) {
+        if !self.commands_allowed() {
+            return;
+        }
+        let cwd = self
+            .new_session_info
+            .new_session_folder
+            .clone()
+            .unwrap_or_else(|| get_plugin_ids().initial_cwd);
+        let mut context = BTreeMap::new();
+        context.insert(GIT_ROOT_REQUEST.to_owned(), String::new());
+        run_command_with_env_variables_and_cwd(
+            &["git", "rev-parse", "--show-toplevel"],
+            BTreeMap::new(),
+            cwd,
+            context,
+        );
+    }
+    fn list_new_session_folder(&mut self) {
+        // only a hint, so without commands the layouts are simply not fit to the folder
+        let new_session_folder = match &self.new_session_info.new_session_folder {
+            Some(new_session_folder) if self.run_commands => new_session_folder.clone(),
+            _ => return,
+        };
+        if self.listed_new_session_folder.as_ref() == Some(&new_session_folder) {
+            return;
+        }
+        self.listed_new_session_folder = Some(new_session_folder.clone());
+        let mut context = BTreeMap::new();
+        context.insert(
+            FOLDER_FILES_REQUEST.to_owned(),
+            new_session_folder.display().to_string(),
+        );
+        run_command_with_env_variables_and_cwd(
+            &["ls", "-A"],
+            BTreeMap::new(),
+            new_session_folder,
+            context,
+        );
+    }
+    fn apply_default_new_session_cwd(&mut self) {
+        // a picked folder stays as it is
+        if self.new_session_info.new_session_folder.is_none() {
+            self.new_session_info.new_session_folder = self.default_new_session_cwd.clone();
+        }
+    }
+    fn toggle_client_filter(&mut self, client_filter: ClientFilter) {
+        self.sessions.client_filter = self.sessions.client_filter.toggled(client_filter);
+        self.sessions.reset_selected_index();
+        self.sessions
+            .update_search_term(&self.attach_search.term, &self.colors);
+    }
+    fn refresh_session_data(&mut self) {
+        // there is no api to ask for a SessionUpdate, so we refetch what we fetch ourselves (the
+        // cwds and the current session layout) and redraw
+        self.log_action("refresh session data".to_owned());
+        self.sessions.forget_session_cwds();
+        self.request_missing_session_cwds();
+        if !self.is_welcome_screen {
+            dump_session_layout();
+        }
+        self.force_redraw = true;
+        self.show_notice(self.language.text(Message::Refreshed));
+    }
+    fn request_missing_session_cwds(&mut self) {
+        if !self.run_commands || (!self.sessions.is_expanded() && !self.show_session_details) {
+            return;
+        }
+        // SessionInfo does not include the cwd, so we get it from each session's layout
+        for session_name in self.sessions.sessions_with_unknown_cwd() {
+            let mut context = BTreeMap::new();
+            context.insert(SESSION_CWD_REQUEST.to_owned(), session_name.clone());
+            run_command(
+                &[
+                    "zellij",
+                    "--session",
+                    &session_name,
+                    "action",
+                    "dump-layout",
+                ],
+                context,
+            );
+        }
     }
     fn main_menu_size(&self, rows: usize, cols: usize) -> (usize, usize, usize, usize) {
         // x, y, width, height
@@ -929,35 +4297,389 @@ impl State {
             0
         };
         let height = rows.saturating_sub(y);
+        if self.show_frame && !self.is_welcome_screen {
+            // the welcome screen has its own boundaries
+            // two columns in so that the list's arrows are not drawn over by the frame
+            return (
+                x + 2,
+                y + 1,
+                width.saturating_sub(4),
+                height.saturating_sub(2),
+            );
+        }
         (x, y, width, height)
     }
-    fn render_kill_all_sessions_warning(&self, rows: usize, columns: usize, x: usize, y: usize) {
-        if rows == 0 || columns == 0 {
-            return;
-        }
-        let session_count = self.sessions.all_other_sessions().len();
-        let session_count_len = session_count.to_string().chars().count();
-        let warning_description_text = format!("This will kill {session_count} active sessions");
-        let confirmation_text = "Are you sure? (y/n)";
-        let warning_y_location = y + (rows / 2).saturating_sub(1);
-        let confirmation_y_location = y + (rows / 2) + 1;
-        let warning_x_location =
-            x + columns.saturating_sub(warning_description_text.chars().count()) / 2;
-        let confirmation_x_location =
-            x + columns.saturating_sub(confirmation_text.chars().count()) / 2;
-        print_text_with_coordinates(
-            Text::new(warning_description_text).color_range(0, 15..16 + session_count_len),
-            warning_x_location,
-            warning_y_location,
-            None,
-            None,
-        );
-        print_text_with_coordinates(
-            Text::new(confirmation_text).color_indices(2, vec![15, 17]),
-            confirmation_x_location,
-            confirmation_y_location,
-            None,
-            None,
+    fn render_compact(&self, rows: usize, cols: usize) {
+        if rows == 0 || cols == 0 {
+            return;
+        }
+        if rows == 1 {
+            // no room for a separate prompt line, place the matches right after the prompt
+            let prompt_width = prompt_width(
+                &self.attach_search.term,
+                &self.sessions.search_prompt(self.language),
+            );
+            render_prompt(
+                &self.attach_search.term,
+                self.attach_search.cursor,
+                &self.sessions.search_prompt(self.language),
+                self.colors,
+                0,
+                0,
+                cols,
+            );
+            if let Some(error) = &self.error {
+                render_error(
+                    self.language,
+                    error,
+                    0,
+                    cols.saturating_sub(prompt_width + 1),
+                    prompt_width + 1,
+                    0,
+                );
+            } else {
+                let (entries, selected) = self.sessions.compact_entries();
+                render_compact_matches(
+                    &entries,
+                    selected,
+                    self.colors,
+                    prompt_width + 1,
+                    0,
+                    cols.saturating_sub(prompt_width + 1),
+                );
+            }
+        } else {
+            render_prompt(
+                &self.attach_search.term,
+                self.attach_search.cursor,
+                &self.sessions.search_prompt(self.language),
+                self.colors,
+                0,
+                0,
+                cols,
+            );
+            if let Some(error) = &self.error {
+                render_error(self.language, error, 1, cols, 0, 0);
+            } else {
+                let (entries, selected) = self.sessions.compact_entries();
+                render_compact_matches(&entries, selected, self.colors, 0, 1, cols);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_esc_clears_the_search_and_the_second_hides() {
+        let mut search = ScreenSearch {
+            term: "api".to_owned(),
+            cursor: 3,
+        };
+        assert_eq!(
+            AttachEsc::decide(false, &search.term, false),
+            AttachEsc::ClearSearch
+        );
+        search.term.clear();
+        assert_eq!(
+            AttachEsc::decide(false, &search.term, false),
+            AttachEsc::Hide
+        );
+    }
+
+    #[test]
+    fn esc_stops_renaming_before_touching_the_search() {
+        assert_eq!(
+            AttachEsc::decide(true, "api", false),
+            AttachEsc::StopRenaming
+        );
+        assert_eq!(AttachEsc::decide(true, "", false), AttachEsc::StopRenaming);
+    }
+
+    #[test]
+    fn esc_never_hides_the_welcome_screen() {
+        assert_eq!(
+            AttachEsc::decide(false, "api", true),
+            AttachEsc::ClearSearch
+        );
+        assert_eq!(AttachEsc::decide(false, "", true), AttachEsc::Nothing);
+    }
+
+    #[test]
+    fn kill_all_warning_counts_the_sessions_it_kills() {
+        let confirmation =
+            PendingConfirmation::KillAllSessions(vec!["api".to_owned(), "gateway".to_owned()]);
+        let (title, highlighted_ranges) = confirmation.title(Language::English);
+        assert_eq!(title, "This will kill 2 active sessions");
+        assert_eq!(highlighted_ranges, vec![15..16]);
+    }
+
+    #[test]
+    fn cwd_variable_names_are_looked_up_once() {
+        assert_eq!(
+            cwd_variable_names("~/src/$PROJECT/${PROJECT}_$USER"),
+            vec!["HOME", "PROJECT", "USER"]
+        );
+        assert!(cwd_variable_names("~other/src/$/${}").is_empty());
+    }
+
+    #[test]
+    fn expand_cwd_expands_the_home_folder_and_variables() {
+        let variables = BTreeMap::from([
+            ("HOME".to_owned(), "/home/user".to_owned()),
+            ("PROJECT".to_owned(), "api".to_owned()),
+        ]);
+        assert_eq!(expand_cwd("~", &variables), "/home/user");
+        assert_eq!(
+            expand_cwd("~/src/$PROJECT", &variables),
+            "/home/user/src/api"
+        );
+        assert_eq!(expand_cwd("/src/${PROJECT}_v2", &variables), "/src/api_v2");
+        assert_eq!(expand_cwd("/src/$UNSET/x", &variables), "/src//x");
+        assert_eq!(expand_cwd("~other/$/${}", &variables), "~other/$/${}");
+    }
+
+    #[test]
+    fn expand_cwd_does_not_run_commands() {
+        let variables = BTreeMap::new();
+        assert_eq!(expand_cwd("/src/$(whoami)", &variables), "/src/$(whoami)");
+        assert_eq!(expand_cwd("/src/`whoami`", &variables), "/src/`whoami`");
+    }
+
+    #[test]
+    fn a_blank_new_session_name_is_refused_even_when_trimming() {
+        for trim_session_names in [false, true] {
+            let mut state = State {
+                active_screen: ActiveScreen::New,
+                trim_session_names,
+                ..Default::default()
+            };
+            state.new_session_info.set_name(" \t ".to_owned());
+            state.handle_selection();
+            assert_eq!(
+                state.error.as_deref(),
+                Some(Language::English.text(Message::SessionNameBlank))
+            );
+            assert_eq!(state.new_session_info.name(), " \t ");
+        }
+    }
+
+    #[test]
+    fn a_blank_rename_is_refused_even_when_trimming() {
+        for trim_session_names in [false, true] {
+            let mut state = State {
+                trim_session_names,
+                renaming_session_name: Some("   ".to_owned()),
+                ..Default::default()
+            };
+            state.handle_attach_selection(AttachFocus::Selected);
+            assert_eq!(
+                state.error.as_deref(),
+                Some(Language::English.text(Message::SessionNameBlank))
+            );
+            let mut state = State {
+                trim_session_names,
+                ..Default::default()
+            };
+            state.resurrectable_sessions.renaming_session =
+                Some(("api".to_owned(), "   ".to_owned()));
+            state.resurrect_renamed_session();
+            assert_eq!(
+                state.error.as_deref(),
+                Some(Language::English.text(Message::SessionNameBlank))
+            );
+        }
+    }
+
+    #[test]
+    fn an_empty_rename_is_still_reported_as_empty() {
+        let mut state = State {
+            renaming_session_name: Some(String::new()),
+            ..Default::default()
+        };
+        state.handle_attach_selection(AttachFocus::Selected);
+        assert_eq!(
+            state.error.as_deref(),
+            Some(Language::English.text(Message::NewNameEmpty))
+        );
+    }
+
+    #[test]
+    fn alt_digits_switch_screens() {
+        let mut state = State {
+            enabled_screens: vec![
+                ActiveScreen::New,
+                ActiveScreen::Attach,
+                ActiveScreen::Resurrect,
+            ],
+            ..Default::default()
+        };
+        state.handle_key(alt(BareKey::Char('3')));
+        assert_eq!(state.active_screen, ActiveScreen::Resurrect);
+        state.handle_key(alt(BareKey::Char('1')));
+        assert_eq!(state.active_screen, ActiveScreen::New);
+        state.handle_key(alt(BareKey::Char('2')));
+        assert_eq!(state.active_screen, ActiveScreen::Attach);
+        assert_eq!(state.pending_count, None);
+    }
+
+    #[test]
+    fn ctrl_alt_digits_are_a_count_prefix() {
+        let mut state = State::default();
+        state.handle_key(
+            KeyWithModifier::new(BareKey::Char('1'))
+                .with_ctrl_modifier()
+                .with_alt_modifier(),
+        );
+        state.handle_key(
+            KeyWithModifier::new(BareKey::Char('2'))
+                .with_ctrl_modifier()
+                .with_alt_modifier(),
+        );
+        assert_eq!(state.pending_count, Some(12));
+        assert_eq!(state.active_screen, ActiveScreen::Attach);
+    }
+
+    fn local_session(name: &str) -> SessionUiInfo {
+        SessionUiInfo {
+            is_remote: false,
+            web_clients_allowed: true,
+            ..SessionUiInfo::remote(name)
+        }
+    }
+
+    fn alt(bare_key: BareKey) -> KeyWithModifier {
+        KeyWithModifier::new(bare_key).with_alt_modifier()
+    }
+
+    #[test]
+    fn alt_down_and_up_reorder_the_sessions_with_manual_sort() {
+        let mut state = State::default();
+        state.sessions.sort = SessionSort::Manual;
+        state.sessions.set_sessions(
+            vec![
+                local_session("api"),
+                local_session("gateway"),
+                local_session("web"),
+            ],
+            vec![],
+        );
+        state.sessions.selected_index.0 = Some(0);
+        state.handle_key(alt(BareKey::Down));
+        assert_eq!(
+            state.sessions.all_other_sessions(),
+            vec!["gateway", "api", "web"]
+        );
+        state.handle_key(alt(BareKey::Down));
+        assert_eq!(
+            state.sessions.all_other_sessions(),
+            vec!["gateway", "web", "api"]
+        );
+        state.handle_key(alt(BareKey::Up));
+        assert_eq!(
+            state.sessions.all_other_sessions(),
+            vec!["gateway", "api", "web"]
         );
     }
+
+    #[test]
+    fn alt_up_recalls_the_search_history_with_other_sorts() {
+        let mut state = State::default();
+        state
+            .sessions
+            .set_sessions(vec![local_session("api"), local_session("gateway")], vec![]);
+        state.sessions.selected_index.0 = Some(0);
+        state.search_history.record("gate");
+        state.handle_key(alt(BareKey::Up));
+        assert_eq!(state.attach_search.term, "gate");
+        assert_eq!(state.sessions.all_other_sessions(), vec!["api", "gateway"]);
+    }
+
+    fn search_at(term: &str, cursor: usize) -> ScreenSearch {
+        ScreenSearch {
+            term: term.to_owned(),
+            cursor,
+        }
+    }
+
+    #[test]
+    fn byte_index_counts_characters_not_bytes() {
+        let search = search_at("🦀x", 0);
+        assert_eq!(search.byte_index(0), 0);
+        assert_eq!(search.byte_index(1), 4);
+        assert_eq!(search.byte_index(2), 5);
+        assert_eq!(search.byte_index(9), 5);
+    }
+
+    #[test]
+    fn inserting_between_multibyte_characters() {
+        let mut search = search_at("日本語", 1);
+        assert!(search.insert("x"));
+        assert_eq!(search.term, "日x本語");
+        assert_eq!(search.cursor, 2);
+        assert!(search.insert("🦀🦀"));
+        assert_eq!(search.term, "日x🦀🦀本語");
+        assert_eq!(search.cursor, 4);
+    }
+
+    #[test]
+    fn backspace_removes_a_whole_multibyte_character() {
+        let mut search = search_at("日本語", 2);
+        assert!(search.delete_char_backward());
+        assert_eq!(search.term, "日語");
+        assert_eq!(search.cursor, 1);
+        let mut search = search_at_end("🦀x");
+        assert!(search.delete_char_backward());
+        assert!(search.delete_char_backward());
+        assert_eq!(search.term, "");
+        assert!(!search.delete_char_backward());
+    }
+
+    #[test]
+    fn kill_to_end_cuts_at_the_cursor_character() {
+        let mut search = search_at("日本語", 1);
+        assert!(search.kill_to_end());
+        assert_eq!(search.term, "日");
+        let mut search = search_at("🦀x", 1);
+        assert!(search.kill_to_end());
+        assert_eq!(search.term, "🦀");
+        assert!(!search.kill_to_end());
+    }
+
+    #[test]
+    fn word_deletion_around_multibyte_words() {
+        let mut search = search_at_end("🦀x 日本語");
+        assert!(search.delete_word_backward());
+        assert_eq!(search.term, "🦀x ");
+        assert_eq!(search.cursor, 3);
+        assert!(search.delete_word_backward());
+        assert_eq!(search.term, "");
+        assert_eq!(search.cursor, 0);
+
+        let mut search = search_at("🦀x 日本語", 0);
+        assert!(search.delete_word_forward());
+        assert_eq!(search.term, " 日本語");
+        assert!(search.delete_word_forward());
+        assert_eq!(search.term, "");
+        assert!(!search.delete_word_forward());
+    }
+
+    #[test]
+    fn delete_char_forward_removes_a_whole_multibyte_character() {
+        let mut search = search_at("🦀x", 0);
+        assert!(search.delete_char_forward());
+        assert_eq!(search.term, "x");
+        let mut search = search_at("日本語", 2);
+        assert!(search.delete_char_forward());
+        assert_eq!(search.term, "日本");
+        assert!(!search.delete_char_forward());
+    }
+
+    fn search_at_end(term: &str) -> ScreenSearch {
+        let mut search = search_at(term, 0);
+        search.move_cursor_to_end();
+        search
+    }
 }
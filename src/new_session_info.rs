@@ -1,14 +1,66 @@
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use uuid::Uuid;
 use zellij_tile::prelude::*;
 
+use crate::strings::{Language, Message};
+
+// context key (holding the session name) of the command creating a session with env variables
+pub const NEW_SESSION_WITH_ENV_REQUEST: &str = "new_session_with_env_request";
+// context key (holding the command) of a session that should run a command in a new pane once
+// it's created
+pub const NEW_SESSION_INITIAL_COMMAND: &str = "new_session_initial_command";
+
+// files that tell what kind of project a folder holds - layouts have no metadata, so a layout
+// fits a project if its name has the kind in it, eg. rust-dev for a folder with a Cargo.toml
+const PROJECT_MARKERS: &[(&str, &str)] = &[
+    ("Cargo.toml", "rust"),
+    ("package.json", "node"),
+    ("go.mod", "go"),
+    ("pyproject.toml", "python"),
+    ("requirements.txt", "python"),
+    ("Gemfile", "ruby"),
+    ("mix.exs", "elixir"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("CMakeLists.txt", "cpp"),
+    ("flake.nix", "nix"),
+];
+
 #[derive(Default)]
 pub struct NewSessionInfo {
     name: String,
     layout_list: LayoutList,
     entering_new_session_info: EnteringState,
     pub new_session_folder: Option<PathBuf>,
+    pub last_picked_folder: Option<PathBuf>,
+    env_variables: String, // space separated KEY=VALUE pairs
+    entering_env_variables: bool,
+    initial_command: String,
+    entering_initial_command: bool,
+    folder_display: FolderDisplay,
+    pub language: Language,
+}
+
+// how the new session folder is shown, eg. the full path when two folders share a name
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FolderDisplay {
+    #[default]
+    Absolute,
+    HomeRelative, // ~/...
+    Basename,
+}
+
+impl FolderDisplay {
+    fn next(&self) -> Self {
+        match self {
+            FolderDisplay::Absolute => FolderDisplay::HomeRelative,
+            FolderDisplay::HomeRelative => FolderDisplay::Basename,
+            FolderDisplay::Basename => FolderDisplay::Absolute,
+        }
+    }
 }
 
 #[derive(Eq, PartialEq)]
@@ -27,6 +79,25 @@ impl NewSessionInfo {
     pub fn name(&self) -> &str {
         &self.name
     }
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+    pub fn folder_display(&self) -> FolderDisplay {
+        self.folder_display
+    }
+    pub fn cycle_folder_display(&mut self) {
+        self.folder_display = self.folder_display.next();
+    }
+    pub fn prefill_name_from_folder(&mut self, folder: &std::path::Path) {
+        // a session per project folder, unless a name was already typed
+        if !self.name.is_empty() {
+            return;
+        }
+        if let Some(folder_name) = folder.file_name() {
+            let (session_name, _) = strip_pasted_session_name(&folder_name.to_string_lossy());
+            self.name = session_name.trim().to_owned();
+        }
+    }
     pub fn layout_search_term(&self) -> &str {
         &self.layout_list.layout_search_term
     }
@@ -36,7 +107,64 @@ impl NewSessionInfo {
     pub fn entering_layout_search_term(&self) -> bool {
         self.entering_new_session_info == EnteringState::EnteringLayoutSearch
     }
+    pub fn entering_env_variables(&self) -> bool {
+        self.entering_env_variables
+    }
+    pub fn env_variables_text(&self) -> &str {
+        &self.env_variables
+    }
+    pub fn toggle_entering_env_variables(&mut self) {
+        self.entering_env_variables = !self.entering_env_variables;
+    }
+    pub fn entering_initial_command(&self) -> bool {
+        self.entering_initial_command
+    }
+    pub fn initial_command_text(&self) -> &str {
+        &self.initial_command
+    }
+    pub fn toggle_entering_initial_command(&mut self) {
+        self.entering_initial_command = !self.entering_initial_command;
+    }
+    pub fn initial_command(&self) -> Result<Option<&str>, String> {
+        // the field is only shown while editing or once it has a command
+        let initial_command = self.initial_command.trim();
+        if initial_command.is_empty() && self.entering_initial_command {
+            Err(self.language.text(Message::InitialCommandEmpty).to_owned())
+        } else if initial_command.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(initial_command))
+        }
+    }
+    pub fn env_variables(&self) -> Result<BTreeMap<String, String>, String> {
+        let mut env_variables = BTreeMap::new();
+        for entry in self.env_variables.split_whitespace() {
+            match entry.split_once('=') {
+                Some((key, value))
+                    if !key.is_empty()
+                        && !key.starts_with(|c: char| c.is_ascii_digit())
+                        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+                {
+                    env_variables.insert(key.to_owned(), value.to_owned());
+                },
+                _ => {
+                    return Err(self
+                        .language
+                        .format(Message::MalformedEnvVariable, &[entry]))
+                },
+            }
+        }
+        Ok(env_variables)
+    }
     pub fn add_char(&mut self, character: char) {
+        if self.entering_env_variables {
+            self.env_variables.push(character);
+            return;
+        }
+        if self.entering_initial_command {
+            self.initial_command.push(character);
+            return;
+        }
         match self.entering_new_session_info {
             EnteringState::EnteringName => {
                 self.name.push(character);
@@ -47,7 +175,31 @@ impl NewSessionInfo {
             },
         }
     }
+    pub fn paste(&mut self, text: &str) -> bool {
+        // returns true if characters a session name cannot have were left out of it
+        if self.entering_env_variables
+            || self.entering_initial_command
+            || self.entering_new_session_info == EnteringState::EnteringLayoutSearch
+        {
+            // these are single line fields too
+            for character in text.chars().filter(|c| *c != '\n' && *c != '\r') {
+                self.add_char(character);
+            }
+            return false;
+        }
+        let (session_name, was_stripped) = strip_pasted_session_name(text);
+        self.name.push_str(&session_name);
+        was_stripped
+    }
     pub fn handle_backspace(&mut self) {
+        if self.entering_env_variables {
+            self.env_variables.pop();
+            return;
+        }
+        if self.entering_initial_command {
+            self.initial_command.pop();
+            return;
+        }
         match self.entering_new_session_info {
             EnteringState::EnteringName => {
                 self.name.pop();
@@ -59,6 +211,15 @@ impl NewSessionInfo {
         }
     }
     pub fn handle_break(&mut self) {
+        if self.entering_env_variables {
+            self.entering_env_variables = false;
+            return;
+        }
+        if self.entering_initial_command {
+            self.initial_command.clear();
+            self.entering_initial_command = false;
+            return;
+        }
         match self.entering_new_session_info {
             EnteringState::EnteringName => {
                 self.name.clear();
@@ -93,7 +254,10 @@ impl NewSessionInfo {
             _ => {},
         }
     }
-    pub fn handle_selection(&mut self, current_session_name: &Option<String>) {
+    pub fn handle_selection(
+        &mut self,
+        current_session_name: &Option<String>,
+    ) -> Result<(), String> {
         match self.entering_new_session_info {
             EnteringState::EnteringLayoutSearch => {
                 let new_session_layout: Option<LayoutInfo> = self.selected_layout_info();
@@ -102,7 +266,54 @@ impl NewSessionInfo {
                 } else {
                     Some(self.name.as_str())
                 };
-                if new_session_name != current_session_name.as_ref().map(|s| s.as_str()) {
+                let env_variables = self.env_variables()?;
+                let initial_command = self.initial_command()?;
+                let needs_background_creation =
+                    !env_variables.is_empty() || initial_command.is_some();
+                if let (Some(new_session_name), true) = (new_session_name, needs_background_creation)
+                {
+                    // the session switching api does not accept env variables or commands, so we
+                    // create the session in the background from the cli (whose environment the
+                    // new session inherits) and switch to it once it's ready
+                    let mut command = vec!["zellij", "attach", "--create-background", new_session_name];
+                    match &new_session_layout {
+                        // the cli only takes a layout name or path, not the layout itself
+                        Some(LayoutInfo::Stringified(_)) if initial_command.is_some() => {
+                            return Err(self
+                                .language
+                                .text(Message::InitialCommandWithCurrentLayout)
+                                .to_owned());
+                        },
+                        Some(LayoutInfo::Stringified(_)) => {
+                            return Err(self
+                                .language
+                                .text(Message::EnvVariablesWithCurrentLayout)
+                                .to_owned());
+                        },
+                        Some(new_session_layout) => {
+                            let layout_name = new_session_layout.name();
+                            command.extend(["options", "--default-layout", layout_name]);
+                        },
+                        None => {},
+                    }
+                    let mut context = BTreeMap::new();
+                    context.insert(
+                        NEW_SESSION_WITH_ENV_REQUEST.to_owned(),
+                        new_session_name.to_owned(),
+                    );
+                    if let Some(initial_command) = initial_command {
+                        context.insert(
+                            NEW_SESSION_INITIAL_COMMAND.to_owned(),
+                            initial_command.to_owned(),
+                        );
+                    }
+                    run_command_with_env_variables_and_cwd(
+                        &command,
+                        env_variables,
+                        self.new_session_folder.clone().unwrap_or_else(|| PathBuf::from(".")),
+                        context,
+                    );
+                } else if new_session_name != current_session_name.as_ref().map(|s| s.as_str()) {
                     match new_session_layout {
                         Some(new_session_layout) => {
                             let cwd = self.new_session_folder.as_ref().map(|c| PathBuf::from(c));
@@ -114,6 +325,9 @@ impl NewSessionInfo {
                     }
                 }
                 self.name.clear();
+                self.env_variables.clear();
+                self.initial_command.clear();
+                self.entering_initial_command = false;
                 self.layout_list.clear_selection();
                 hide_self();
             },
@@ -121,10 +335,63 @@ impl NewSessionInfo {
                 self.entering_new_session_info = EnteringState::EnteringLayoutSearch;
             },
         }
+        Ok(())
+    }
+    pub fn has_unsaved_input(&self) -> bool {
+        // a folder only counts if it was picked, not if it's the configured default
+        !self.name.is_empty()
+            || !self.env_variables.is_empty()
+            || !self.initial_command.is_empty()
+            || self.last_picked_folder.is_some()
+    }
+    pub fn reset_folder(&mut self) {
+        self.new_session_folder = None;
+        self.last_picked_folder = None;
+        self.layout_list.set_project_kinds(vec![]);
+    }
+    pub fn set_folder_file_names(&mut self, file_names: &[&str]) {
+        // the files of the new session folder, which bring the layouts fitting its project first
+        let mut project_kinds: Vec<String> = PROJECT_MARKERS
+            .iter()
+            .filter(|(marker, _)| file_names.contains(marker))
+            .map(|(_, project_kind)| project_kind.to_string())
+            .collect();
+        project_kinds.dedup();
+        self.layout_list.set_project_kinds(project_kinds);
+    }
+    pub fn layout_project_kind(&self, layout_info: &LayoutInfo) -> Option<&str> {
+        self.layout_list.project_kind(layout_info)
     }
     pub fn update_layout_list(&mut self, layout_info: Vec<LayoutInfo>) {
         self.layout_list.update_layout_list(layout_info);
     }
+    pub fn set_layout_sort(&mut self, layout_sort: LayoutSort) {
+        self.layout_list.layout_sort = layout_sort;
+    }
+    pub fn update_current_session_layout(&mut self, layout: String) {
+        self.layout_list
+            .update_current_session_layout(LayoutInfo::Stringified(layout));
+    }
+    pub fn layout_name<'a>(&self, layout_info: &'a LayoutInfo) -> &'a str {
+        self.layout_list.layout_name(layout_info)
+    }
+    pub fn continue_with_layout(&mut self, name: String) {
+        // the name was given elsewhere (eg. :new in the Attach screen's command line), so we go
+        // on with whatever layout is selected
+        self.name = name;
+        self.entering_new_session_info = EnteringState::EnteringLayoutSearch;
+    }
+    pub fn select_current_session_layout(&mut self) -> bool {
+        if self.layout_list.current_session_layout.is_none() {
+            return false;
+        }
+        self.layout_list.layout_search_term.clear();
+        self.update_layout_search_term();
+        // the current session layout is always the first entry
+        self.layout_list.clear_selection();
+        self.entering_new_session_info = EnteringState::EnteringLayoutSearch;
+        true
+    }
     pub fn layout_list(&self, max_rows: usize) -> Vec<(LayoutInfo, bool)> {
         // bool - is_selected
         let range_to_render = self.range_to_render(
@@ -208,6 +475,10 @@ impl NewSessionInfo {
     pub fn selected_layout_info(&self) -> Option<LayoutInfo> {
         self.layout_list.selected_layout_info()
     }
+    pub fn selected_layout_description(&self) -> Option<String> {
+        self.selected_layout_info()
+            .and_then(|layout_info| layout_description(&layout_info, self.language))
+    }
     fn update_layout_search_term(&mut self) {
         if self.layout_list.layout_search_term.is_empty() {
             self.layout_list.clear_selection();
@@ -216,8 +487,10 @@ impl NewSessionInfo {
             let mut matches = vec![];
             let matcher = SkimMatcherV2::default().use_cache(true);
             for layout_info in &self.layout_list.layout_list {
-                if let Some((score, indices)) =
-                    matcher.fuzzy_indices(&layout_info.name(), &self.layout_list.layout_search_term)
+                if let Some((score, indices)) = matcher.fuzzy_indices(
+                    self.layout_list.layout_name(layout_info),
+                    &self.layout_list.layout_search_term,
+                )
                 {
                     matches.push(LayoutSearchResult {
                         layout_info: layout_info.clone(),
@@ -234,6 +507,33 @@ impl NewSessionInfo {
     fn move_selection_up(&mut self) {
         self.layout_list.move_selection_up();
     }
+    pub fn start_layout_picker(&mut self) {
+        self.layout_list.layout_search_term.clear();
+        self.update_layout_search_term();
+    }
+    pub fn handle_layout_picker_key(&mut self, key: KeyWithModifier) -> bool {
+        // the layout list on its own, eg. to resurrect a session with another layout
+        match key.bare_key {
+            BareKey::Up if key.has_no_modifiers() => self.move_selection_up(),
+            BareKey::Down if key.has_no_modifiers() => self.move_selection_down(),
+            BareKey::Char('p') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.move_selection_up()
+            },
+            BareKey::Char('n') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.move_selection_down()
+            },
+            BareKey::Backspace if key.has_no_modifiers() => {
+                self.layout_list.layout_search_term.pop();
+                self.update_layout_search_term();
+            },
+            BareKey::Char(character) if key.has_no_modifiers() => {
+                self.layout_list.layout_search_term.push(character);
+                self.update_layout_search_term();
+            },
+            _ => return false,
+        }
+        true
+    }
     fn move_selection_down(&mut self) {
         self.layout_list.move_selection_down();
     }
@@ -241,19 +541,103 @@ impl NewSessionInfo {
 
 #[derive(Default)]
 pub struct LayoutList {
-    layout_list: Vec<LayoutInfo>,
+    layout_list: Vec<LayoutInfo>, // the current session layout (if any) followed by the available layouts
+    available_layouts: Vec<LayoutInfo>,
+    current_session_layout: Option<LayoutInfo>,
     layout_search_results: Vec<LayoutSearchResult>,
     selected_layout_index: usize,
     layout_search_term: String,
+    layout_sort: LayoutSort,
+    project_kinds: Vec<String>, // of the new session folder, if we could tell
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutSort {
+    #[default]
+    Host, // the order zellij lists them in
+    Alphabetical,
+}
+
+impl LayoutSort {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "host" => Some(LayoutSort::Host),
+            "alphabetical" => Some(LayoutSort::Alphabetical),
+            _ => None,
+        }
+    }
 }
 
 impl LayoutList {
-    pub fn update_layout_list(&mut self, layout_list: Vec<LayoutInfo>) {
+    pub fn update_layout_list(&mut self, mut layout_list: Vec<LayoutInfo>) {
+        if self.layout_sort == LayoutSort::Alphabetical {
+            layout_list.sort_by_key(|l| l.name().to_lowercase());
+        }
+        self.available_layouts = layout_list;
+        self.rebuild_layout_list();
+    }
+    pub fn update_current_session_layout(&mut self, current_session_layout: LayoutInfo) {
+        self.current_session_layout = Some(current_session_layout);
+        self.rebuild_layout_list();
+    }
+    pub fn set_project_kinds(&mut self, project_kinds: Vec<String>) {
+        self.project_kinds = project_kinds;
+        self.rebuild_layout_list();
+    }
+    pub fn project_kind(&self, layout_info: &LayoutInfo) -> Option<&str> {
+        if matches!(layout_info, LayoutInfo::Stringified(_)) {
+            return None;
+        }
+        // whole words of the name, so eg. "rust-dev" fits a rust project but "frustrated" does
+        // not (and layout files are named by their path)
+        let layout_name = layout_info.name().to_lowercase();
+        let words: Vec<&str> = layout_name.split(['-', '_', '.', '/']).collect();
+        self.project_kinds
+            .iter()
+            .find(|project_kind| words.contains(&project_kind.as_str()))
+            .map(|project_kind| project_kind.as_str())
+    }
+    pub fn layout_name<'a>(&self, layout_info: &'a LayoutInfo) -> &'a str {
+        match layout_info {
+            // stringified layouts are named by their contents, the only one we list is the
+            // current session's
+            LayoutInfo::Stringified(_) => "current session layout",
+            _ => layout_info.name(),
+        }
+    }
+    fn rebuild_layout_list(&mut self) {
         let old_layout_length = self.layout_list.len();
-        self.layout_list = layout_list;
-        if old_layout_length != self.layout_list.len() {
-            // honestly, this is just the UX choice that sucks the least...
-            self.clear_selection();
+        let selected_layout = self.layout_list.get(self.selected_layout_index).cloned();
+        // the layouts fitting the folder's project first, otherwise in their sort order
+        let (fitting_layouts, other_layouts): (Vec<&LayoutInfo>, Vec<&LayoutInfo>) = self
+            .available_layouts
+            .iter()
+            .partition(|l| self.project_kind(l).is_some());
+        self.layout_list = self
+            .current_session_layout
+            .iter()
+            .chain(fitting_layouts)
+            .chain(other_layouts)
+            .cloned()
+            .collect();
+        if !self.layout_search_term.is_empty() {
+            // the selection is in the search results, which this does not touch
+            return;
+        }
+        // the current session layout changes with the session, so it's followed by position
+        let selected_layout_index = selected_layout.and_then(|selected_layout| {
+            self.layout_list.iter().position(|l| match (l, &selected_layout) {
+                (LayoutInfo::Stringified(_), LayoutInfo::Stringified(_)) => true,
+                _ => *l == selected_layout,
+            })
+        });
+        match selected_layout_index {
+            Some(selected_layout_index) => self.selected_layout_index = selected_layout_index,
+            None if old_layout_length != self.layout_list.len() => {
+                // honestly, this is just the UX choice that sucks the least...
+                self.clear_selection();
+            },
+            None => {},
         }
     }
     pub fn selected_layout_info(&self) -> Option<LayoutInfo> {
@@ -299,3 +683,177 @@ pub struct LayoutSearchResult {
     pub score: i64,
     pub indices: Vec<usize>,
 }
+
+// LayoutInfo carries no metadata, so we describe the layouts we know (the built-ins) or can
+// inspect (stringified ones) and leave the rest name-only - layout files and urls live on the host
+fn layout_description(layout_info: &LayoutInfo, language: Language) -> Option<String> {
+    match layout_info {
+        LayoutInfo::BuiltIn(name) => match name.as_str() {
+            "default" => Some(Message::LayoutDefault),
+            "compact" => Some(Message::LayoutCompact),
+            "classic" => Some(Message::LayoutClassic),
+            "disable-status-bar" => Some(Message::LayoutDisableStatusBar),
+            "strider" => Some(Message::LayoutStrider),
+            "welcome" => Some(Message::LayoutWelcome),
+            _ => None,
+        }
+        .map(|description| language.text(description).to_owned()),
+        LayoutInfo::Stringified(layout) => {
+            let (mut tab_count, mut pane_count) = (0, 0);
+            for line in layout.lines() {
+                match line.split_whitespace().next() {
+                    Some("tab") => tab_count += 1,
+                    Some("pane") => pane_count += 1,
+                    _ => {},
+                }
+            }
+            if tab_count == 0 && pane_count == 0 {
+                None
+            } else {
+                Some(language.format(
+                    Message::LayoutTabsAndPanes,
+                    &[
+                        &std::cmp::max(tab_count, 1).to_string(),
+                        &pane_count.to_string(),
+                    ],
+                ))
+            }
+        },
+        LayoutInfo::File(_) | LayoutInfo::Url(_) => None,
+    }
+}
+
+pub fn strip_pasted_session_name(text: &str) -> (String, bool) {
+    // session names are a single line and cannot have slashes, since they name a socket file
+    let session_name: String = text
+        .chars()
+        .filter(|c| *c != '\n' && *c != '\r' && *c != '/')
+        .collect();
+    let was_stripped = session_name.chars().count() != text.chars().count();
+    (session_name, was_stripped)
+}
+
+pub fn is_blank_session_name(name: &str) -> bool {
+    // only whitespace, an empty name is handled by each caller (eg. a generated one)
+    !name.is_empty() && name.trim().is_empty()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SessionNameScheme {
+    #[default]
+    WordPair, // eg. quiet-river
+    Numbered, // eg. session-3
+}
+
+impl SessionNameScheme {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "word_pair" => Some(SessionNameScheme::WordPair),
+            "numbered" => Some(SessionNameScheme::Numbered),
+            _ => None,
+        }
+    }
+}
+
+const ADJECTIVES: &[&str] = &[
+    "amber", "brave", "calm", "clever", "crisp", "eager", "fancy", "gentle", "golden", "happy",
+    "jolly", "lively", "lucky", "mellow", "nimble", "quiet", "rapid", "shiny", "silent", "sunny",
+];
+const NOUNS: &[&str] = &[
+    "badger", "canyon", "comet", "falcon", "forest", "glacier", "harbor", "island", "lagoon",
+    "meadow", "otter", "panda", "pebble", "planet", "river", "rocket", "summit", "tiger", "valley",
+    "willow",
+];
+
+pub fn generate_session_name(scheme: SessionNameScheme, is_taken: impl Fn(&str) -> bool) -> String {
+    match scheme {
+        SessionNameScheme::Numbered => (1..)
+            .map(|i| format!("session-{}", i))
+            .find(|name| !is_taken(name))
+            .unwrap_or_default(),
+        SessionNameScheme::WordPair => {
+            // random bytes from a v4 uuid, so we don't need another dependency
+            for _ in 0..100 {
+                let random_bytes = Uuid::new_v4().into_bytes();
+                let name = format!(
+                    "{}-{}",
+                    ADJECTIVES[random_bytes[0] as usize % ADJECTIVES.len()],
+                    NOUNS[random_bytes[1] as usize % NOUNS.len()]
+                );
+                if !is_taken(&name) {
+                    return name;
+                }
+            }
+            // all the pairs we tried are taken, a number makes it unique
+            (1..)
+                .map(|i| format!("{}-{}-{}", ADJECTIVES[0], NOUNS[0], i))
+                .find(|name| !is_taken(name))
+                .unwrap_or_default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn creating_with(env_variables: &str, initial_command: &str) -> NewSessionInfo {
+        NewSessionInfo {
+            name: "api".to_owned(),
+            entering_new_session_info: EnteringState::EnteringLayoutSearch,
+            env_variables: env_variables.to_owned(),
+            initial_command: initial_command.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn creating_with_malformed_env_variables_is_an_error() {
+        let mut new_session_info = creating_with("FOO=bar BAZ", "");
+        assert_eq!(
+            new_session_info.handle_selection(&None),
+            Err("Malformed environment variable: BAZ".to_owned())
+        );
+    }
+
+    #[test]
+    fn creating_in_the_background_with_the_current_layout_is_an_error() {
+        let current_layout = "layout {\n    pane\n}".to_owned();
+        let mut new_session_info = creating_with("FOO=bar", "");
+        new_session_info.update_current_session_layout(current_layout.clone());
+        assert_eq!(
+            new_session_info.handle_selection(&None),
+            Err(Language::English
+                .text(Message::EnvVariablesWithCurrentLayout)
+                .to_owned())
+        );
+        let mut new_session_info = creating_with("", "htop");
+        new_session_info.update_current_session_layout(current_layout);
+        assert_eq!(
+            new_session_info.handle_selection(&None),
+            Err(Language::English
+                .text(Message::InitialCommandWithCurrentLayout)
+                .to_owned())
+        );
+    }
+
+    #[test]
+    fn names_of_only_whitespace_are_blank() {
+        assert!(is_blank_session_name(" "));
+        assert!(is_blank_session_name("   "));
+        assert!(is_blank_session_name("\t"));
+        assert!(is_blank_session_name("\n"));
+        assert!(is_blank_session_name(" \t\r\n "));
+    }
+
+    #[test]
+    fn empty_names_are_left_to_the_caller() {
+        assert!(!is_blank_session_name(""));
+    }
+
+    #[test]
+    fn names_with_surrounding_spaces_are_not_blank() {
+        assert!(!is_blank_session_name(" api "));
+        assert!(!is_blank_session_name("\tapi\n"));
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use zellij_tile::prelude::*;
+
+use crate::strings::{Language, Message};
+
+const MAX_RECENTLY_KILLED_LEN: usize = 10;
+
+// sessions killed from this plugin, kept with the layout they had so that they can be re-created -
+// unlike the resurrectable sessions this is only kept in memory
+#[derive(Debug, Default)]
+pub struct RecentlyKilled {
+    sessions: VecDeque<KilledSession>, // most recent first
+    selected_index: usize,
+    pub is_visible: bool, // shown instead of the resurrectable sessions
+}
+
+#[derive(Debug, Clone)]
+pub struct KilledSession {
+    pub name: String,
+    pub layout: Option<String>, // kdl, we only have it if the session was listed when killed
+    pub cwd: Option<PathBuf>,
+    pub tab_count: usize,
+}
+
+impl RecentlyKilled {
+    pub fn record(&mut self, killed_session: KilledSession) {
+        self.sessions.retain(|s| s.name != killed_session.name);
+        self.sessions.push_front(killed_session);
+        self.sessions.truncate(MAX_RECENTLY_KILLED_LEN);
+        self.selected_index = 0;
+    }
+    pub fn toggle_visible(&mut self) {
+        self.is_visible = !self.is_visible;
+        self.selected_index = 0;
+    }
+    pub fn move_selection_down(&mut self) {
+        if self.selected_index + 1 >= self.sessions.len() {
+            self.selected_index = 0;
+        } else {
+            self.selected_index += 1;
+        }
+    }
+    pub fn move_selection_up(&mut self) {
+        if self.selected_index == 0 {
+            self.selected_index = self.sessions.len().saturating_sub(1);
+        } else {
+            self.selected_index -= 1;
+        }
+    }
+    pub fn selected(&self) -> Option<&KilledSession> {
+        self.sessions.get(self.selected_index)
+    }
+    pub fn take_selected(&mut self) -> Option<KilledSession> {
+        let killed_session = self.sessions.remove(self.selected_index);
+        self.selected_index = self
+            .selected_index
+            .min(self.sessions.len().saturating_sub(1));
+        killed_session
+    }
+    pub fn render(&self, language: Language, rows: usize, columns: usize, x: usize, y: usize) {
+        let recently_killed = language.text(Message::RecentlyKilledTitle);
+        let recently_killed_len = recently_killed.chars().count();
+        let (go_back, shortcut_ranges) =
+            language.format_with_ranges(Message::GoBack, &["<Ctrl f>"]);
+        let mut title = Text::new(format!("{} {}", recently_killed, go_back))
+            .color_range(2, ..recently_killed_len);
+        for range in shortcut_ranges {
+            title = title.color_range(
+                3,
+                range.start + recently_killed_len + 1..range.end + recently_killed_len + 1,
+            );
+        }
+        print_text_with_coordinates(title, x.saturating_sub(1), y + 2, None, None);
+        if self.sessions.is_empty() {
+            print_text_with_coordinates(
+                Text::new(language.text(Message::NoKilledSessions)),
+                x.saturating_sub(1),
+                y + 4,
+                None,
+                None,
+            );
+            return;
+        }
+        let table_rows = rows.saturating_sub(5); // title row, toggle row and some padding
+        let mut table = Table::new().add_row(vec![" ", " ", " "]); // skip the title row
+        for (i, killed_session) in self
+            .sessions
+            .iter()
+            .enumerate()
+            .take(table_rows.saturating_sub(1))
+        {
+            let is_selected = i == self.selected_index;
+            let tab_count = match killed_session.layout {
+                Some(_) => {
+                    language.format(Message::TabCount, &[&killed_session.tab_count.to_string()])
+                },
+                None => language.text(Message::DefaultLayout).to_owned(),
+            };
+            let mut table_cells = vec![
+                Text::new(&killed_session.name).color_range(0, ..),
+                Text::new(tab_count),
+                if is_selected {
+                    Text::new(format!(
+                        "<ENTER> - {}",
+                        language.text(Message::RecreateSession)
+                    ))
+                    .color_range(3, 0..7)
+                } else {
+                    Text::new(" ")
+                },
+            ];
+            if is_selected {
+                table_cells = table_cells.drain(..).map(|t| t.selected()).collect();
+            }
+            table = table.add_styled_row(table_cells);
+        }
+        print_table_with_coordinates(table, x, y + 3, Some(columns), Some(table_rows));
+    }
+}
@@ -6,6 +6,10 @@ use std::time::Duration;
 
 use zellij_tile::shim::*;
 
+use crate::new_session_info::strip_pasted_session_name;
+use crate::strings::{Language, Message};
+use crate::ui::components::{render_confirmation, render_renaming_session_screen};
+
 #[derive(Debug, Default)]
 pub struct ResurrectableSessions {
     pub all_resurrectable_sessions: Vec<(String, Duration)>,
@@ -15,23 +19,142 @@ pub struct ResurrectableSessions {
     pub is_searching: bool,
     pub search_term: String,
     pub delete_all_dead_sessions_warning: bool,
+    pub renaming_session: Option<(String, String)>, // original name, new name
+    pub sort_mode: SortMode,
+    pub deleting_disabled: bool, // eg. in managed environments that keep resurrect history
+    pub language: Language,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    #[default]
+    MostRecentFirst,
+    Alphabetical,
+}
+
+impl SortMode {
+    fn toggled(&self) -> Self {
+        match self {
+            SortMode::MostRecentFirst => SortMode::Alphabetical,
+            SortMode::Alphabetical => SortMode::MostRecentFirst,
+        }
+    }
+    fn description(&self, language: Language) -> &'static str {
+        language.text(match self {
+            SortMode::MostRecentFirst => Message::SortMostRecentFirst,
+            SortMode::Alphabetical => Message::SortAlphabetical,
+        })
+    }
+}
+
+// an age filter in the search term, eg. `>2d` for sessions created more than two days ago - only
+// resurrectable sessions have an age, running sessions do not come with a creation time
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AgePredicate {
+    is_older_than: bool,
+    age: Duration,
+    age_text: String, // as typed, eg. 2d
+}
+
+impl AgePredicate {
+    fn parse(token: &str) -> Option<Self> {
+        let is_older_than = match token.chars().next()? {
+            '>' => true,
+            '<' => false,
+            _ => return None,
+        };
+        let age_text = &token[1..];
+        let unit_secs = match age_text.chars().last()? {
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            _ => return None,
+        };
+        let count: u64 = age_text[..age_text.len() - 1].parse().ok()?;
+        Some(AgePredicate {
+            is_older_than,
+            age: Duration::from_secs(count.saturating_mul(unit_secs)),
+            age_text: age_text.to_owned(),
+        })
+    }
+    fn allows(&self, ctime: &Duration) -> bool {
+        if self.is_older_than {
+            *ctime > self.age
+        } else {
+            *ctime < self.age
+        }
+    }
+    fn description(&self) -> String {
+        let comparison = if self.is_older_than { "older" } else { "newer" };
+        format!("{} than {}", comparison, self.age_text)
+    }
+}
+
+fn parse_search_term(search_term: &str) -> (Vec<AgePredicate>, String) {
+    // age predicates anywhere in the search term, and the rest of it to fuzzy match the names
+    let mut age_predicates = vec![];
+    let mut text = vec![];
+    for token in search_term.split_whitespace() {
+        match AgePredicate::parse(token) {
+            Some(age_predicate) => age_predicates.push(age_predicate),
+            None => text.push(token),
+        }
+    }
+    (age_predicates, text.join(" "))
 }
 
 impl ResurrectableSessions {
-    pub fn update(&mut self, mut list: Vec<(String, Duration)>) {
-        list.sort_by(|a, b| a.1.cmp(&b.1));
+    pub fn update(&mut self, list: Vec<(String, Duration)>) {
         self.all_resurrectable_sessions = list;
+        self.sort();
         if self.is_searching {
             self.update_search_term();
         }
     }
+    pub fn toggle_sort_mode(&mut self) {
+        let selected_session_name = self.selected_index.and_then(|i| {
+            self.all_resurrectable_sessions
+                .get(i)
+                .map(|session| session.0.clone())
+        });
+        self.sort_mode = self.sort_mode.toggled();
+        self.sort();
+        // keep the same session selected after re-sorting
+        if let Some(selected_session_name) = selected_session_name {
+            self.selected_index = self
+                .all_resurrectable_sessions
+                .iter()
+                .position(|session| session.0 == selected_session_name);
+        }
+    }
+    fn sort(&mut self) {
+        match self.sort_mode {
+            // the duration is the time elapsed since the session was created
+            SortMode::MostRecentFirst => self.all_resurrectable_sessions.sort_by_key(|s| s.1),
+            SortMode::Alphabetical => self
+                .all_resurrectable_sessions
+                .sort_by_key(|s| s.0.to_lowercase()),
+        }
+    }
     pub fn render(&self, rows: usize, columns: usize, x: usize, y: usize) {
         if self.delete_all_dead_sessions_warning {
             self.render_delete_all_sessions_warning(rows, columns, x, y);
             return;
         }
-        let search_indication =
-            Text::new(format!("Search: {}_", self.search_term)).color_range(2, ..7);
+        if let Some((original_name, new_name)) = &self.renaming_session {
+            render_renaming_session_screen(
+                self.language,
+                &self.language.format(Message::NewNameFor, &[original_name]),
+                new_name,
+                rows,
+                columns,
+                x,
+                y + 2,
+            );
+            return;
+        }
+        let search_prompt = self.language.text(Message::Search);
+        let search_indication = Text::new(format!("{} {}_", search_prompt, self.search_term))
+            .color_range(2, ..search_prompt.chars().count());
         let table_rows = rows.saturating_sub(5); // search row, toggle row and some padding
         let table_columns = columns;
         let table = if self.is_searching {
@@ -40,8 +163,55 @@ impl ResurrectableSessions {
             self.render_all_entries(table_rows, columns)
         };
         print_text_with_coordinates(search_indication, x.saturating_sub(1), y + 2, None, None);
+        self.render_age_predicates(x, y + 2);
+        self.render_sort_mode(columns, x, y + 2);
         print_table_with_coordinates(table, x, y + 3, Some(table_columns), Some(table_rows));
     }
+    fn render_age_predicates(&self, x: usize, y: usize) {
+        // as chips after the search term, so that it's clear they were not fuzzy matched
+        let mut chip_x = x + self.search_indication_len();
+        for age_predicate in parse_search_term(&self.search_term).0 {
+            let chip_text = format!(" {} ", age_predicate.description());
+            let chip_len = chip_text.chars().count();
+            print_text_with_coordinates(
+                Text::new(chip_text).color_range(2, ..).selected(),
+                chip_x,
+                y,
+                None,
+                None,
+            );
+            chip_x += chip_len + 1;
+        }
+    }
+    fn search_indication_len(&self) -> usize {
+        // the prompt, a space, the search term and the cursor
+        self.language.text(Message::Search).chars().count() + self.search_term.chars().count() + 2
+    }
+    fn age_predicates_len(&self) -> usize {
+        parse_search_term(&self.search_term)
+            .0
+            .iter()
+            .fold(0, |acc, p| acc + p.description().chars().count() + 3)
+    }
+    fn render_sort_mode(&self, columns: usize, x: usize, y: usize) {
+        let sort_mode_text = format!(
+            "<Ctrl s> {}",
+            self.language
+                .format(Message::Sort, &[self.sort_mode.description(self.language)])
+        );
+        let sort_mode_text_len = sort_mode_text.chars().count();
+        let search_indication_len = self.search_indication_len() + self.age_predicates_len();
+        if columns < search_indication_len + sort_mode_text_len + 1 {
+            return;
+        }
+        print_text_with_coordinates(
+            Text::new(sort_mode_text).color_range(3, ..8),
+            x + columns.saturating_sub(sort_mode_text_len),
+            y,
+            None,
+            None,
+        );
+    }
     fn render_search_results(&self, table_rows: usize, _table_columns: usize) -> Table {
         let mut table = Table::new().add_row(vec![" ", " ", " "]); // skip the title row
         let (first_row_index_to_render, last_row_index_to_render) = self.range_to_render(
@@ -104,33 +274,19 @@ impl ResurrectableSessions {
         table
     }
     fn render_delete_all_sessions_warning(&self, rows: usize, columns: usize, x: usize, y: usize) {
-        if rows == 0 || columns == 0 {
-            return;
-        }
         let session_count = self.all_resurrectable_sessions.len();
-        let session_count_len = session_count.to_string().chars().count();
-        let warning_description_text =
-            format!("This will delete {} resurrectable sessions", session_count,);
-        let confirmation_text = "Are you sure? (y/n)";
-        let warning_y_location = y + (rows / 2).saturating_sub(1);
-        let confirmation_y_location = y + (rows / 2) + 1;
-        let warning_x_location =
-            x + columns.saturating_sub(warning_description_text.chars().count()) / 2;
-        let confirmation_x_location =
-            x + columns.saturating_sub(confirmation_text.chars().count()) / 2;
-        print_text_with_coordinates(
-            Text::new(warning_description_text).color_range(0, 17..18 + session_count_len),
-            warning_x_location,
-            warning_y_location,
-            None,
-            None,
+        let (warning_description_text, highlighted_ranges) = self.language.format_with_ranges(
+            Message::DeleteResurrectableSessionsWarning,
+            &[&session_count.to_string()],
         );
-        print_text_with_coordinates(
-            Text::new(confirmation_text).color_indices(2, vec![15, 17]),
-            confirmation_x_location,
-            confirmation_y_location,
-            None,
-            None,
+        render_confirmation(
+            &warning_description_text,
+            highlighted_ranges,
+            self.language.text(Message::AreYouSure),
+            rows,
+            columns,
+            x,
+            y,
         );
     }
     fn range_to_render(
@@ -174,8 +330,12 @@ impl ResurrectableSessions {
         if formatted_duration.is_empty() {
             formatted_duration.push_str("<1m");
         }
-        let duration_len = formatted_duration.chars().count();
-        Text::new(format!("Created {} ago", formatted_duration)).color_range(2, 8..9 + duration_len)
+        let (created_text, duration_ranges) = self
+            .language
+            .format_with_ranges(Message::CreatedAgo, &[&formatted_duration]);
+        duration_ranges
+            .into_iter()
+            .fold(Text::new(created_text), |text, range| text.color_range(2, range))
     }
     fn render_more_indication_or_enter_as_needed(
         &self,
@@ -186,15 +346,25 @@ impl ResurrectableSessions {
         is_selected: bool,
     ) -> Text {
         if is_selected {
-            Text::new(format!("<ENTER> - Resurrect Session")).color_range(3, 0..7)
+            Text::new(format!(
+                "<ENTER> - {}",
+                self.language.text(Message::ResurrectSession)
+            ))
+            .color_range(3, 0..7)
         } else if i == first_row_index_to_render && i > 0 {
-            Text::new(format!("+ {} more", first_row_index_to_render)).color_range(1, ..)
+            Text::new(
+                self.language
+                    .format(Message::MoreItems, &[&first_row_index_to_render.to_string()]),
+            )
+            .color_range(1, ..)
         } else if i == last_row_index_to_render.saturating_sub(1)
             && last_row_index_to_render < results_len
         {
-            Text::new(format!(
-                "+ {} more",
-                results_len.saturating_sub(last_row_index_to_render)
+            Text::new(self.language.format(
+                Message::MoreItems,
+                &[&results_len
+                    .saturating_sub(last_row_index_to_render)
+                    .to_string()],
             ))
             .color_range(1, ..)
         } else {
@@ -258,7 +428,10 @@ impl ResurrectableSessions {
                 .map(|session_name_and_creation_time| session_name_and_creation_time.0.clone())
         }
     }
-    pub fn delete_selected_session(&mut self) {
+    pub fn delete_selected_session(&mut self) -> Result<(), &'static str> {
+        if self.deleting_disabled {
+            return Err(self.language.text(Message::DeletingDisabled));
+        }
         if self.is_searching {
             self.selected_search_index
                 .and_then(|i| self.search_results.get(i))
@@ -282,6 +455,7 @@ impl ResurrectableSessions {
                     delete_dead_session(&session_name_and_creation_time.0)
                 });
         }
+        Ok(())
     }
     fn delete_all_sessions(&mut self) {
         // optimistic update
@@ -289,8 +463,21 @@ impl ResurrectableSessions {
         self.delete_all_dead_sessions_warning = false;
         delete_all_dead_sessions();
     }
-    pub fn show_delete_all_sessions_warning(&mut self) {
+    pub fn start_renaming_selected_session(&mut self) -> bool {
+        match self.get_selected_session_name() {
+            Some(session_name) => {
+                self.renaming_session = Some((session_name, String::new()));
+                true
+            },
+            None => false,
+        }
+    }
+    pub fn show_delete_all_sessions_warning(&mut self) -> Result<(), &'static str> {
+        if self.deleting_disabled {
+            return Err(self.language.text(Message::DeletingDisabled));
+        }
         self.delete_all_dead_sessions_warning = true;
+        Ok(())
     }
     pub fn handle_character(&mut self, character: char) {
         if self.delete_all_dead_sessions_warning && character == 'y' {
@@ -302,6 +489,20 @@ impl ResurrectableSessions {
             self.update_search_term();
         }
     }
+    pub fn paste(&mut self, text: &str) -> bool {
+        // returns true if characters a session name cannot have were left out of the new name
+        if let Some((_, new_name)) = self.renaming_session.as_mut() {
+            let (session_name, was_stripped) = strip_pasted_session_name(text);
+            new_name.push_str(&session_name);
+            return was_stripped;
+        }
+        if !self.delete_all_dead_sessions_warning {
+            self.search_term
+                .extend(text.chars().filter(|c| *c != '\n' && *c != '\r'));
+            self.update_search_term();
+        }
+        false
+    }
     pub fn handle_backspace(&mut self) {
         self.search_term.pop();
         self.update_search_term();
@@ -314,9 +515,17 @@ impl ResurrectableSessions {
     fn update_search_term(&mut self) {
         let mut matches = vec![];
         let matcher = SkimMatcherV2::default().use_cache(true);
+        let (age_predicates, search_text) = parse_search_term(&self.search_term);
         for (session_name, ctime) in &self.all_resurrectable_sessions {
-            if let Some((score, indices)) = matcher.fuzzy_indices(&session_name, &self.search_term)
-            {
+            if !age_predicates.iter().all(|p| p.allows(ctime)) {
+                continue;
+            }
+            let found = if search_text.is_empty() {
+                Some((0, vec![])) // only filtered by age, keeps the sort order
+            } else {
+                matcher.fuzzy_indices(&session_name, &search_text)
+            };
+            if let Some((score, indices)) = found {
                 matches.push(SearchResult {
                     session_name: session_name.to_owned(),
                     ctime: ctime.clone(),
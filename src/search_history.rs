@@ -0,0 +1,46 @@
+use std::collections::VecDeque;
+
+const MAX_SEARCH_HISTORY_LEN: usize = 20;
+
+// recent searches on the attach screen, recalled with Alt+Up/Alt+Down unless those reorder the
+// sessions (sort = manual) - unlike the session history this is only kept in memory
+#[derive(Debug, Default)]
+pub struct SearchHistory {
+    search_terms: VecDeque<String>, // most recent first
+    recall_index: Option<usize>,    // the search term currently recalled into the search field
+}
+
+impl SearchHistory {
+    pub fn record(&mut self, search_term: &str) {
+        self.recall_index = None;
+        if search_term.is_empty() {
+            return;
+        }
+        self.search_terms.retain(|s| s != search_term);
+        self.search_terms.push_front(search_term.to_owned());
+        self.search_terms.truncate(MAX_SEARCH_HISTORY_LEN);
+    }
+    pub fn older(&mut self) -> Option<String> {
+        let recall_index = self.recall_index.map(|i| i + 1).unwrap_or(0);
+        if recall_index < self.search_terms.len() {
+            self.recall_index = Some(recall_index);
+        }
+        self.recall_index
+            .and_then(|i| self.search_terms.get(i))
+            .cloned()
+    }
+    pub fn newer(&mut self) -> Option<String> {
+        // going past the most recent search term brings back an empty search field
+        match self.recall_index {
+            None => None,
+            Some(0) => {
+                self.recall_index = None;
+                Some(String::new())
+            },
+            Some(i) => {
+                self.recall_index = Some(i - 1);
+                self.search_terms.get(i - 1).cloned()
+            },
+        }
+    }
+}
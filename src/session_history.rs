@@ -0,0 +1,53 @@
+use std::fs;
+
+use crate::logging::{log, LogLevel};
+
+// the cache folder is shared between all instances of this plugin, across sessions, so each
+// session records itself here when it becomes current
+const SESSION_HISTORY_FILE: &str = "/cache/session_history";
+const MAX_SESSION_HISTORY_LEN: usize = 50;
+
+#[derive(Debug, Default)]
+pub struct SessionHistory {
+    session_names: Vec<String>, // most recently used first
+}
+
+impl SessionHistory {
+    pub fn touch(&mut self, session_name: &str) {
+        self.reload();
+        if self.session_names.first().map(|s| s.as_str()) == Some(session_name) {
+            return;
+        }
+        self.session_names.retain(|s| s != session_name);
+        self.session_names.insert(0, session_name.to_owned());
+        self.session_names.truncate(MAX_SESSION_HISTORY_LEN);
+        self.save();
+    }
+    pub fn most_recent_session(
+        &mut self,
+        current_session_name: Option<&str>,
+        is_live_session: impl Fn(&str) -> bool,
+    ) -> Option<String> {
+        self.reload();
+        self.session_names
+            .iter()
+            .find(|s| Some(s.as_str()) != current_session_name && is_live_session(s))
+            .cloned()
+    }
+    fn reload(&mut self) {
+        // persistence is best effort, if the cache folder is not available we keep using
+        // whatever we have in memory
+        if let Ok(contents) = fs::read_to_string(SESSION_HISTORY_FILE) {
+            self.session_names = contents
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(|l| l.to_owned())
+                .collect();
+        }
+    }
+    fn save(&self) {
+        if let Err(e) = fs::write(SESSION_HISTORY_FILE, self.session_names.join("\n")) {
+            log!(LogLevel::Error, "Failed to save session history: {}", e);
+        }
+    }
+}
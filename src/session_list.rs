@@ -1,6 +1,10 @@
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
+use crate::session_order::SessionOrder;
+use crate::session_tags::{parse_tag_filters, SessionTags};
+use crate::strings::{Language, Message};
 use crate::ui::{
     components::{Colors, LineToRender, ListItem},
     SessionUiInfo,
@@ -9,33 +13,286 @@ use crate::ui::{
 #[derive(Debug, Default)]
 pub struct SessionList {
     pub session_ui_infos: Vec<SessionUiInfo>,
+    all_session_ui_infos: Vec<SessionUiInfo>, // as we got them, including the hidden ones
+    pub show_hidden: bool,                    // also list the sessions that are normally hidden
     pub forbidden_sessions: Vec<SessionUiInfo>,
     pub selected_index: SelectedIndex,
     pub selected_search_index: Option<usize>,
     pub search_results: Vec<SearchResult>,
     pub is_searching: bool,
+    pub single_match: Option<String>, // the session a search narrowed the list down to
     pub show_expanded_content: bool, // Toggle for showing tabs/panes
+    expansion_level: ExpansionLevel,  // how deep the expanded content goes
+    pub search_scope: SearchScope,
+    pub is_pane_search: bool, // search pane titles across all sessions as a flat list
+    pub show_relative_line_numbers: bool, // for count prefixed movement, eg. Ctrl+Alt+5 Ctrl+j
+    pub max_results: Option<usize>, // search results are shown in pages of this many results
+    pub client_filter: ClientFilter,
+    pub sort: SessionSort,
+    session_order: SessionOrder, // the order of the sessions with SessionSort::Manual
+    pub remote_host: Option<String>, // shown in the header of the remote sessions
+    pub show_activity: bool, // a sparkline of recent changes after each session, takes width
+    session_activity: BTreeMap<String, SessionActivity>,
+    session_tags: SessionTags, // kept by name, so they outlive the session infos of each update
+    remote_session_names: Vec<String>,
+    resurrectable_session_names: Vec<String>, // so the manual order can forget deleted sessions
+    session_cwds: BTreeMap<String, String>, // session name -> cwd, filled lazily
+    requested_session_cwds: BTreeSet<String>,
+    grid_columns: usize, // the collapsed list flows into this many columns on wide panes
+    selection_anchor: Option<String>, // the session a Shift+arrow range was extended from
+}
+
+// SessionInfo has no timestamps, so activity is what changed in a session's tabs and panes
+// (titles, exit codes) and clients between the session updates we got
+#[derive(Debug, Default)]
+struct SessionActivity {
+    signature: BTreeSet<String>,
+    levels: VecDeque<usize>, // most recent last
+}
+
+pub const ACTIVITY_SPARKLINE_LEN: usize = 8;
+
+impl SessionActivity {
+    fn update(&mut self, session_ui_info: &SessionUiInfo) {
+        let mut signature = BTreeSet::new();
+        signature.insert(format!("clients:{}", session_ui_info.connected_users));
+        for tab in &session_ui_info.tabs {
+            signature.insert(format!("tab:{}:{}", tab.position, tab.name));
+            for pane in &tab.panes {
+                signature.insert(format!(
+                    "pane:{}:{}:{:?}",
+                    pane.pane_id, pane.name, pane.exit_code
+                ));
+            }
+        }
+        let level = if self.signature.is_empty() {
+            0 // first time we see it, nothing to compare with
+        } else {
+            self.signature.symmetric_difference(&signature).count()
+        };
+        self.signature = signature;
+        self.levels.push_back(level);
+        while self.levels.len() > ACTIVITY_SPARKLINE_LEN {
+            self.levels.pop_front();
+        }
+    }
+}
+
+// below this width (or with sessions expanded) the list is a single column
+const GRID_MIN_WIDTH: usize = 160;
+pub const GRID_CELL_GUTTER: usize = 6; // the arrows of the selected line or the indentation
+pub const GRID_COLUMN_GAP: usize = 2;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SearchScope {
+    #[default]
+    Names,
+    All, // also match tab and pane titles while sessions are collapsed
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExpansionLevel {
+    #[default]
+    Collapsed,
+    Tabs, // tabs can be selected and searched, their panes cannot
+    TabsAndPanes,
+}
+
+impl ExpansionLevel {
+    pub fn from_number(number: u32) -> Option<Self> {
+        match number {
+            0 => Some(ExpansionLevel::Collapsed),
+            1 => Some(ExpansionLevel::Tabs),
+            2 => Some(ExpansionLevel::TabsAndPanes),
+            _ => None,
+        }
+    }
+    fn prompt_qualifier(&self) -> Option<&'static str> {
+        match self {
+            ExpansionLevel::Collapsed => None,
+            ExpansionLevel::Tabs => Some("tabs"),
+            ExpansionLevel::TabsAndPanes => Some("expanded"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SessionSort {
+    #[default]
+    Alphabetical,
+    Manual, // the order the user put the sessions in with Alt+Up/Down, new ones sort last
+}
+
+impl SessionSort {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "alphabetical" => Some(SessionSort::Alphabetical),
+            "manual" => Some(SessionSort::Manual),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ClientFilter {
+    #[default]
+    All,
+    Attached, // sessions with connected clients
+    Idle,     // sessions without connected clients
+}
+
+impl ClientFilter {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "all" => Some(ClientFilter::All),
+            "attached" => Some(ClientFilter::Attached),
+            "idle" => Some(ClientFilter::Idle),
+            _ => None,
+        }
+    }
+    pub fn toggled(&self, client_filter: ClientFilter) -> Self {
+        // toggling the active filter turns filtering off
+        if *self == client_filter {
+            ClientFilter::All
+        } else {
+            client_filter
+        }
+    }
+    fn allows(&self, session_ui_info: &SessionUiInfo) -> bool {
+        match self {
+            ClientFilter::All => true,
+            ClientFilter::Attached => session_ui_info.connected_users > 0,
+            ClientFilter::Idle => session_ui_info.connected_users == 0,
+        }
+    }
+    fn prompt_qualifier(&self) -> Option<&'static str> {
+        match self {
+            ClientFilter::All => None,
+            ClientFilter::Attached => Some("attached"),
+            ClientFilter::Idle => Some("idle"),
+        }
+    }
+}
+
+impl SearchScope {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "names" => Some(SearchScope::Names),
+            "all" => Some(SearchScope::All),
+            _ => None,
+        }
+    }
 }
 
 impl SessionList {
     pub fn set_sessions(
+        &mut self,
+        session_ui_infos: Vec<SessionUiInfo>,
+        forbidden_sessions: Vec<SessionUiInfo>,
+    ) {
+        // a session update is the only thing that ticks the activity, relisting the same
+        // sessions (eg. when toggling the hidden ones) is not activity
+        if self.show_activity {
+            self.session_activity
+                .retain(|name, _| session_ui_infos.iter().any(|s| &s.name == name));
+            for session_ui_info in &session_ui_infos {
+                self.session_activity
+                    .entry(session_ui_info.name.clone())
+                    .or_default()
+                    .update(session_ui_info);
+            }
+        }
+        // the cwds are kept across expanding and collapsing, only for the sessions still running
+        self.session_cwds
+            .retain(|name, _| session_ui_infos.iter().any(|s| &s.name == name));
+        self.requested_session_cwds
+            .retain(|name| session_ui_infos.iter().any(|s| &s.name == name));
+        self.relist_sessions(session_ui_infos, forbidden_sessions);
+    }
+    fn relist_sessions(
         &mut self,
         mut session_ui_infos: Vec<SessionUiInfo>,
         mut forbidden_sessions: Vec<SessionUiInfo>,
     ) {
+        self.all_session_ui_infos = session_ui_infos.clone();
+        if !self.show_hidden {
+            session_ui_infos.retain(|s| !s.is_hidden);
+        }
+        // the list might be reordered, so we follow the selected session by name
+        let previously_selected_session_name = self
+            .selected_index
+            .0
+            .and_then(|i| self.session_ui_infos.get(i))
+            .map(|s| s.name.clone());
+        if self.sort == SessionSort::Manual {
+            self.session_order.reload();
+        }
+        let session_order = &self.session_order;
+        let is_manual_sort = self.sort == SessionSort::Manual;
         session_ui_infos.sort_unstable_by(|a, b| {
             if a.is_current_session {
                 std::cmp::Ordering::Less
             } else if b.is_current_session {
                 std::cmp::Ordering::Greater
+            } else if is_manual_sort {
+                // sessions that were never moved have no position and go last
+                let position = |name: &str| session_order.position(name).unwrap_or(usize::MAX);
+                position(&a.name)
+                    .cmp(&position(&b.name))
+                    .then(a.name.cmp(&b.name))
             } else {
                 a.name.cmp(&b.name)
             }
         });
         forbidden_sessions.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        for session_ui_info in session_ui_infos.iter_mut() {
+            session_ui_info.cwd = self.session_cwds.get(&session_ui_info.name).cloned();
+        }
+        if self.show_activity {
+            for session_ui_info in session_ui_infos.iter_mut() {
+                session_ui_info.activity = self
+                    .session_activity
+                    .get(&session_ui_info.name)
+                    .map(|a| a.levels.iter().copied().collect());
+            }
+        }
+        // remote sessions are listed after the local ones, a local session shadows a remote one
+        // by the same name
+        let remote_sessions: Vec<SessionUiInfo> = self
+            .remote_session_names
+            .iter()
+            .filter(|name| !session_ui_infos.iter().any(|s| &s.name == *name))
+            .map(|name| SessionUiInfo::remote(name))
+            .collect();
+        session_ui_infos.extend(remote_sessions);
+        self.session_tags.reload();
+        for session_ui_info in session_ui_infos.iter_mut() {
+            session_ui_info.tags = self.session_tags.tags(&session_ui_info.name);
+        }
         self.session_ui_infos = session_ui_infos;
         self.forbidden_sessions = forbidden_sessions;
-        
+        if let Some(selected_session_position) = previously_selected_session_name
+            .and_then(|name| self.session_ui_infos.iter().position(|s| s.name == name))
+        {
+            self.selected_index.0 = Some(selected_session_position);
+            let selected_session = &self.session_ui_infos[selected_session_position];
+            match self.selected_index.1.map(|t| selected_session.tabs.get(t)) {
+                Some(None) => {
+                    // the selected tab is gone
+                    self.selected_index.1 = None;
+                    self.selected_index.2 = None;
+                },
+                Some(Some(selected_tab))
+                    if self.selected_index.2 >= Some(selected_tab.panes.len()) =>
+                {
+                    // the selected pane is gone
+                    self.selected_index.2 = None;
+                },
+                _ => {},
+            }
+            return;
+        }
+
         // Validate selection indices after session update to prevent out-of-bounds errors
         if let Some(selected) = self.selected_index.0 {
             if selected >= self.session_ui_infos.len() {
@@ -63,13 +320,32 @@ impl SessionList {
         }
     }
     pub fn update_search_term(&mut self, search_term: &str, colors: &Colors) {
+        if self.is_pane_search {
+            self.update_pane_search_term(search_term, colors);
+            return;
+        }
+        let is_searching = !search_term.is_empty();
+        let (tag_filters, search_term) = parse_tag_filters(search_term);
+        let search_term = search_term.as_str();
         let mut flattened_assets = self.flatten_assets(colors);
         let mut matches = vec![];
         let matcher = SkimMatcherV2::default().use_cache(true);
+        let search_children =
+            self.search_scope == SearchScope::All && !self.show_expanded_content;
         for (list_item, session_name, tab_position, pane_id, is_current_session) in
             flattened_assets.drain(..)
         {
-            if let Some((score, indices)) = matcher.fuzzy_indices(&list_item.name, &search_term) {
+            let is_filtered_out = self
+                .session_ui_infos
+                .iter()
+                .find(|s| s.name == session_name)
+                .map(|s| !self.client_filter.allows(s))
+                .unwrap_or(false)
+                || !self.session_tags.has_tags(&session_name, &tag_filters);
+            if is_filtered_out {
+                continue;
+            }
+            if let Some((score, indices)) = score_match(&matcher, &list_item.name, search_term) {
                 matches.push(SearchResult::new(
                     score,
                     indices,
@@ -79,13 +355,99 @@ impl SessionList {
                     pane_id,
                     is_current_session,
                 ));
+            } else if search_children {
+                if let Some(child_match) = self
+                    .session_ui_infos
+                    .iter()
+                    .find(|s| s.name == session_name)
+                    .and_then(|s| best_child_match(s, search_term, &matcher))
+                {
+                    let mut search_result = SearchResult::new(
+                        child_match.score,
+                        vec![], // the indices belong to the child, not to the session name
+                        list_item.with_match_hint(child_match.kind, &child_match.name),
+                        session_name,
+                        child_match.tab_position,
+                        child_match.pane_id,
+                        is_current_session,
+                    );
+                    search_result.is_child_match = true;
+                    matches.push(search_result);
+                }
+            }
+        }
+        // direct session name matches always rank above matches in their tabs or panes, equal
+        // scores prefer the shorter (closer) name
+        matches.sort_by(|a, b| {
+            a.is_child_match
+                .cmp(&b.is_child_match)
+                .then(b.score.cmp(&a.score))
+                .then(a.list_item.name.len().cmp(&b.list_item.name.len()))
+        });
+        self.search_results = matches;
+        // the client filter lists the sessions it allows even without a search term
+        self.is_searching = is_searching || self.client_filter != ClientFilter::All;
+        self.selected_search_index = Some(0);
+        self.single_match = match self.search_results.as_slice() {
+            [search_result] if is_searching => Some(search_result.session_name.clone()),
+            _ => None,
+        };
+    }
+    fn update_pane_search_term(&mut self, search_term: &str, colors: &Colors) {
+        self.single_match = None; // pane results are not whole sessions
+        let mut matches = vec![];
+        let matcher = SkimMatcherV2::default().use_cache(true);
+        for session in &self.session_ui_infos {
+            if !self.client_filter.allows(session) {
+                continue;
+            }
+            for tab in &session.tabs {
+                for pane in &tab.panes {
+                    if let Some((score, indices)) = score_match(&matcher, &pane.name, search_term) {
+                        matches.push(SearchResult::new(
+                            score,
+                            indices,
+                            ListItem::from_pane_path(session, tab, pane, *colors),
+                            session.name.clone(),
+                            Some(tab.position),
+                            Some((pane.pane_id, pane.is_plugin)),
+                            session.is_current_session,
+                        ));
+                    }
+                }
             }
         }
-        matches.sort_by(|a, b| b.score.cmp(&a.score));
+        matches.sort_by_key(|m| std::cmp::Reverse(m.score));
         self.search_results = matches;
-        self.is_searching = !search_term.is_empty();
+        // with an empty search term we list all panes
+        self.is_searching = true;
         self.selected_search_index = Some(0);
     }
+    pub fn toggle_pane_search(&mut self) {
+        self.is_pane_search = !self.is_pane_search;
+        if !self.is_pane_search {
+            self.is_searching = false;
+        }
+    }
+    pub fn search_prompt(&self, language: Language) -> String {
+        let qualifiers: Vec<&str> = [
+            if self.is_pane_search {
+                Some("panes")
+            } else {
+                self.expansion_level.prompt_qualifier()
+            },
+            self.client_filter.prompt_qualifier(),
+        ]
+        .iter()
+        .flatten()
+        .copied()
+        .collect();
+        if qualifiers.is_empty() {
+            language.text(Message::Search).to_owned()
+        } else {
+            language.format(Message::SearchWithQualifiers, &[&qualifiers.join(", ")])
+        }
+    }
     fn flatten_assets(
         &self,
         colors: &Colors,
@@ -113,6 +475,9 @@ impl SessionList {
                         None,
                         is_current_session,
                     ));
+                    if self.expansion_level != ExpansionLevel::TabsAndPanes {
+                        continue;
+                    }
                     for pane in &tab.panes {
                         let pane_id = (pane.pane_id, pane.is_plugin);
                         list_items.push((
@@ -140,6 +505,27 @@ impl SessionList {
                 .map(|s_i| s_i.name.clone())
         }
     }
+    pub fn select_exact_session_match(&mut self, search_term: &str) {
+        // a selected tab or pane of the exact match is kept
+        if !self.is_searching
+            || !self.has_session(search_term)
+            || self.get_selected_session_name().as_deref() == Some(search_term)
+        {
+            return;
+        }
+        if let Some(exact_match_index) = self.search_results.iter().position(|s| {
+            s.session_name == search_term && s.tab_position.is_none() && s.pane_id.is_none()
+        }) {
+            self.selected_search_index = Some(exact_match_index);
+        }
+    }
+    pub fn get_selected_session_ui_info(&self) -> Option<&SessionUiInfo> {
+        self.get_selected_session_name()
+            .and_then(|name| self.session_ui_infos.iter().find(|s| s.name == name))
+    }
+    pub fn get_session_ui_info(&self, session_name: &str) -> Option<&SessionUiInfo> {
+        self.session_ui_infos.iter().find(|s| s.name == session_name)
+    }
     pub fn selected_is_current_session(&self) -> bool {
         if self.is_searching {
             self.selected_search_index
@@ -194,7 +580,80 @@ impl SessionList {
                 })
         }
     }
-    pub fn move_selection_down(&mut self) {
+    pub fn update_columns(&mut self, max_cols: usize, colors: Colors) {
+        self.grid_columns = if max_cols < GRID_MIN_WIDTH || self.show_expanded_content {
+            1
+        } else {
+            let widest_session_line = self
+                .session_ui_infos
+                .iter()
+                .map(|s| s.line_width(colors))
+                .max()
+                .unwrap_or(0);
+            max_cols / (widest_session_line + GRID_CELL_GUTTER + GRID_COLUMN_GAP)
+        };
+    }
+    pub fn grid_columns(&self) -> Option<usize> {
+        // None when the list is rendered as a single column
+        if self.grid_columns > 1 && !self.is_searching && !self.show_expanded_content {
+            Some(self.grid_columns)
+        } else {
+            None
+        }
+    }
+    pub fn move_selection_right(&mut self) -> bool {
+        // returns false when there's no column to the right (or no columns at all)
+        match (self.grid_columns(), self.selected_index.0) {
+            (Some(columns), Some(selected_session))
+                if (selected_session + 1) % columns != 0
+                    && selected_session + 1 < self.session_ui_infos.len() =>
+            {
+                self.selection_anchor = None;
+                self.selected_index.0 = Some(selected_session + 1);
+                true
+            },
+            _ => false,
+        }
+    }
+    pub fn move_selection_left(&mut self) -> bool {
+        // returns false when there's no column to the left (or no columns at all)
+        match (self.grid_columns(), self.selected_index.0) {
+            (Some(columns), Some(selected_session)) if selected_session % columns != 0 => {
+                self.selection_anchor = None;
+                self.selected_index.0 = Some(selected_session - 1);
+                true
+            },
+            _ => false,
+        }
+    }
+    pub fn move_selection_down(&mut self) -> bool {
+        // returns false if the selection stayed where it was, eg. at the bottom of a grid column
+        let previous_selection = (self.selected_index.clone(), self.selected_search_index);
+        self.step_selection_down();
+        (self.selected_index.clone(), self.selected_search_index) != previous_selection
+    }
+    pub fn move_selection_up(&mut self) -> bool {
+        // returns false if the selection stayed where it was
+        let previous_selection = (self.selected_index.clone(), self.selected_search_index);
+        self.step_selection_up();
+        (self.selected_index.clone(), self.selected_search_index) != previous_selection
+    }
+    fn step_selection_down(&mut self) {
+        self.selection_anchor = None;
+        if let Some(columns) = self.grid_columns() {
+            // a row down, staying in the same column
+            match self.selected_index.0 {
+                Some(selected_session)
+                    if selected_session + columns < self.session_ui_infos.len() =>
+                {
+                    self.selected_index.0 = Some(selected_session + columns);
+                },
+                Some(_) => {},
+                None if !self.session_ui_infos.is_empty() => self.selected_index.0 = Some(0),
+                None => {},
+            }
+            return;
+        }
         if self.is_searching {
             match self.selected_search_index.as_mut() {
                 Some(search_index) => {
@@ -249,7 +708,19 @@ impl SessionList {
             }
         }
     }
-    pub fn move_selection_up(&mut self) {
+    fn step_selection_up(&mut self) {
+        self.selection_anchor = None;
+        if let Some(columns) = self.grid_columns() {
+            match self.selected_index.0 {
+                Some(selected_session) if selected_session >= columns => {
+                    self.selected_index.0 = Some(selected_session - columns);
+                },
+                Some(_) => {},
+                None if !self.session_ui_infos.is_empty() => self.selected_index.0 = Some(0),
+                None => {},
+            }
+            return;
+        }
         if self.is_searching {
             match self.selected_search_index.as_mut() {
                 Some(search_index) => {
@@ -302,14 +773,78 @@ impl SessionList {
             }
         }
     }
+    pub fn extend_selection_down(&mut self) -> bool {
+        // returns false if there is no selected session to extend the range from
+        match self.selected_index {
+            SelectedIndex(Some(selected_session), None, None) if !self.is_searching => {
+                self.start_selection_range(selected_session);
+                // unlike plain movement, the range neither wraps around nor moves by grid rows
+                if selected_session + 1 < self.session_ui_infos.len() {
+                    self.selected_index.0 = Some(selected_session + 1);
+                }
+                true
+            },
+            _ => false,
+        }
+    }
+    pub fn extend_selection_up(&mut self) -> bool {
+        match self.selected_index {
+            SelectedIndex(Some(selected_session), None, None) if !self.is_searching => {
+                self.start_selection_range(selected_session);
+                self.selected_index.0 = Some(selected_session.saturating_sub(1));
+                true
+            },
+            _ => false,
+        }
+    }
+    fn start_selection_range(&mut self, selected_session: usize) {
+        if self.selection_range().is_none() {
+            self.selection_anchor = self.get_session(selected_session).map(|s| s.name.clone());
+        }
+    }
+    pub fn selection_range(&self) -> Option<(usize, usize)> {
+        // first and last session index of the range, which also includes the selected session -
+        // the anchor is kept by name so that the range survives sessions being added or removed
+        if self.is_searching || self.selected_index.1.is_some() {
+            return None;
+        }
+        let selected_session = self.selected_index.0?;
+        let anchor = self
+            .session_ui_infos
+            .iter()
+            .position(|s| Some(&s.name) == self.selection_anchor.as_ref())?;
+        if anchor == selected_session {
+            None
+        } else {
+            Some((anchor.min(selected_session), anchor.max(selected_session)))
+        }
+    }
+    pub fn is_in_selection_range(&self, session_index: usize) -> bool {
+        self.selection_range()
+            .map(|(first, last)| first <= session_index && session_index <= last)
+            .unwrap_or(false)
+    }
+    pub fn selection_range_session_names(&self) -> Vec<String> {
+        // like all_other_sessions, the current session is never part of a batch kill
+        match self.selection_range() {
+            Some((first, last)) => self.session_ui_infos[first..=last]
+                .iter()
+                .filter(|s| !s.is_current_session && !s.is_remote)
+                .map(|s| s.name.clone())
+                .collect(),
+            None => vec![],
+        }
+    }
     fn get_session(&self, index: usize) -> Option<&SessionUiInfo> {
         self.session_ui_infos.get(index)
     }
-    pub fn result_expand(&mut self) {
+    pub fn result_expand(&mut self) -> bool {
+        // returns false if there was nothing to expand
         // Only allow expansion if expanded content is being shown
         if !self.show_expanded_content {
-            return;
+            return false;
         }
+        let previously_selected_index = self.selected_index.clone();
         // we can't move this to SelectedIndex because the borrow checker is mean
         match self.selected_index {
             SelectedIndex(Some(selected_session), None, None) => {
@@ -321,7 +856,9 @@ impl SessionList {
                     self.selected_index.1 = Some(0);
                 }
             },
-            SelectedIndex(Some(selected_session), Some(selected_tab), None) => {
+            SelectedIndex(Some(selected_session), Some(selected_tab), None)
+                if self.expansion_level == ExpansionLevel::TabsAndPanes =>
+            {
                 let selected_tab_has_panes = self
                     .get_session(selected_session)
                     .and_then(|s| s.tabs.get(selected_tab))
@@ -333,19 +870,43 @@ impl SessionList {
             },
             _ => {},
         }
+        self.selected_index != previously_selected_index
     }
-    pub fn result_shrink(&mut self) {
+    pub fn result_shrink(&mut self) -> bool {
+        // returns false if there was nothing to shrink
         // Only allow shrinking if expanded content is being shown
+        let previously_selected_index = self.selected_index.clone();
         if self.show_expanded_content {
             self.selected_index.result_shrink();
         }
+        self.selected_index != previously_selected_index
+    }
+    pub fn search_results_page(&self) -> (usize, usize) {
+        // start and end indices of the search results page with the selected result
+        match self.max_results.filter(|m| *m > 0) {
+            Some(max_results) => {
+                let page_start =
+                    self.selected_search_index.unwrap_or(0) / max_results * max_results;
+                let page_end = std::cmp::min(page_start + max_results, self.search_results.len());
+                (page_start, page_end)
+            },
+            None => (0, self.search_results.len()),
+        }
     }
     pub fn update_rows(&mut self, rows: usize) {
+        let (page_start, page_end) = self.search_results_page();
+        // the same row SessionList::render keeps for the "more" line
+        let rows = if page_end < self.search_results.len() {
+            rows.saturating_sub(1)
+        } else {
+            rows
+        };
         if let Some(search_result_rows_until_selected) = self.selected_search_index.map(|i| {
             self.search_results
                 .iter()
                 .enumerate()
                 .take(i + 1)
+                .skip(page_start)
                 .fold(0, |acc, s| acc + s.1.lines_to_render())
         }) {
             if search_result_rows_until_selected > rows
@@ -355,28 +916,150 @@ impl SessionList {
             }
         }
     }
+    pub fn toggle_show_hidden(&mut self) -> usize {
+        // returns how many sessions are hidden
+        self.show_hidden = !self.show_hidden;
+        let all_session_ui_infos = self.all_session_ui_infos.clone();
+        let forbidden_sessions = self.forbidden_sessions.clone();
+        self.relist_sessions(all_session_ui_infos, forbidden_sessions);
+        self.all_session_ui_infos
+            .iter()
+            .filter(|s| s.is_hidden)
+            .count()
+    }
+    pub fn move_item_up(&mut self) -> bool {
+        match self.movable_session_index() {
+            Some(session_index) if session_index > 0 && self.is_movable(session_index - 1) => {
+                self.swap_items(session_index - 1, session_index);
+                self.selected_index.0 = Some(session_index - 1);
+                true
+            },
+            _ => false,
+        }
+    }
+    pub fn move_item_down(&mut self) -> bool {
+        match self.movable_session_index() {
+            Some(session_index) if self.is_movable(session_index + 1) => {
+                self.swap_items(session_index, session_index + 1);
+                self.selected_index.0 = Some(session_index + 1);
+                true
+            },
+            _ => false,
+        }
+    }
+    fn movable_session_index(&self) -> Option<usize> {
+        // whole sessions move, within the unfiltered list
+        if self.is_searching || self.selected_index.1.is_some() {
+            return None;
+        }
+        self.selected_index.0.filter(|i| self.is_movable(*i))
+    }
+    fn is_movable(&self, session_index: usize) -> bool {
+        // the current session is always first and remote sessions always come after the local
+        // ones, so only the rest of the local sessions can be reordered
+        self.session_ui_infos
+            .get(session_index)
+            .map(|s| !s.is_current_session && !s.is_remote)
+            .unwrap_or(false)
+    }
+    fn swap_items(&mut self, first_index: usize, second_index: usize) {
+        self.session_ui_infos.swap(first_index, second_index);
+        // the current session is listed first here but has its place in the other sessions'
+        // lists, so it's part of the order too
+        let ordered_session_names = self
+            .session_ui_infos
+            .iter()
+            .filter(|s| !s.is_remote)
+            .map(|s| s.name.clone())
+            .collect();
+        let all_session_ui_infos = &self.all_session_ui_infos;
+        let resurrectable_session_names = &self.resurrectable_session_names;
+        self.session_order.set_order(ordered_session_names, |session_name| {
+            all_session_ui_infos.iter().any(|s| s.name == session_name)
+                || resurrectable_session_names.iter().any(|s| s == session_name)
+        });
+    }
     pub fn reset_selected_index(&mut self) {
         self.selected_index.reset();
+        self.selection_anchor = None;
     }
     pub fn has_session(&self, session_name: &str) -> bool {
-        self.session_ui_infos.iter().any(|s| s.name == session_name)
+        self.session_ui_infos
+            .iter()
+            .any(|s| s.name == session_name && !s.is_remote)
+    }
+    pub fn set_resurrectable_session_names(&mut self, resurrectable_session_names: Vec<String>) {
+        self.resurrectable_session_names = resurrectable_session_names;
+    }
+    pub fn set_remote_sessions(&mut self, remote_session_names: Vec<String>) {
+        self.remote_session_names = remote_session_names;
+        // from all the local sessions, the listed ones might be missing the hidden ones
+        let all_session_ui_infos = self.all_session_ui_infos.clone();
+        let forbidden_sessions = std::mem::take(&mut self.forbidden_sessions);
+        self.relist_sessions(all_session_ui_infos, forbidden_sessions);
+    }
+    pub fn current_session_allows_web_clients(&self) -> Option<bool> {
+        self.session_ui_infos
+            .iter()
+            .find(|s| s.is_current_session)
+            .map(|s| s.web_clients_allowed)
+    }
+    pub fn filtered_session_names(&self) -> Vec<String> {
+        // the local sessions matching the current search, in the order they are shown
+        let mut session_names: Vec<String> = vec![];
+        if self.is_searching {
+            for search_result in &self.search_results {
+                if !session_names.contains(&search_result.session_name)
+                    && self.has_session(&search_result.session_name)
+                {
+                    session_names.push(search_result.session_name.clone());
+                }
+            }
+        }
+        session_names
+    }
+    pub fn selected_is_remote_session(&self) -> bool {
+        self.get_selected_session_name()
+            .map(|name| {
+                self.session_ui_infos
+                    .iter()
+                    .any(|s| s.name == name && s.is_remote)
+            })
+            .unwrap_or(false)
     }
     pub fn has_forbidden_session(&self, session_name: &str) -> bool {
         self.forbidden_sessions
             .iter()
             .any(|s| s.name == session_name)
     }
+    pub fn toggle_session_tag(&mut self, session_name: &str, tag: &str) -> bool {
+        // returns whether the tag was added
+        let was_added = self.session_tags.toggle(session_name, tag);
+        if let Some(session_ui_info) = self
+            .session_ui_infos
+            .iter_mut()
+            .find(|s| s.name == session_name)
+        {
+            session_ui_info.tags = self.session_tags.tags(session_name);
+        }
+        was_added
+    }
     pub fn update_session_name(&mut self, old_name: &str, new_name: &str) {
+        self.session_tags.rename(old_name, new_name);
+        self.session_order.rename(old_name, new_name);
         self.session_ui_infos
             .iter_mut()
             .find(|s| s.name == old_name)
             .map(|s| s.name = new_name.to_owned());
     }
     pub fn all_other_sessions(&self) -> Vec<String> {
+        // this is both what we show in the kill-all warning and what we kill, so it must only
+        // include sessions the user can see - sessions web clients are not allowed to attach to
+        // are kept aside in forbidden_sessions and should already be absent here
         self.session_ui_infos
             .iter()
             .filter_map(|s| {
-                if !s.is_current_session {
+                if !s.is_current_session && !s.is_remote && !self.has_forbidden_session(&s.name) {
                     Some(s.name.clone())
                 } else {
                     None
@@ -385,17 +1068,97 @@ impl SessionList {
             .collect()
     }
     pub fn toggle_expansion(&mut self) {
-        self.show_expanded_content = !self.show_expanded_content;
+        self.set_expansion(!self.show_expanded_content);
+    }
+    pub fn set_expansion(&mut self, show_expanded_content: bool) {
+        self.set_expansion_level(if show_expanded_content {
+            ExpansionLevel::TabsAndPanes
+        } else {
+            ExpansionLevel::Collapsed
+        });
+    }
+    pub fn set_expansion_level(&mut self, expansion_level: ExpansionLevel) {
+        // returns early so that expanding all when already expanded keeps the selection
+        if self.expansion_level == expansion_level {
+            return;
+        }
+        self.expansion_level = expansion_level;
+        if expansion_level != ExpansionLevel::TabsAndPanes {
+            self.selected_index.2 = None;
+        }
+        let show_expanded_content = expansion_level != ExpansionLevel::Collapsed;
+        if self.show_expanded_content == show_expanded_content {
+            // between tabs and panes, the selected tab stays selected
+            return;
+        }
+        self.show_expanded_content = show_expanded_content;
         // Reset selection when toggling expansion to avoid confusion
         self.selected_index.1 = None;
         self.selected_index.2 = None;
     }
+    pub fn forget_session_cwds(&mut self) {
+        self.session_cwds.clear();
+        self.requested_session_cwds.clear();
+        for session_ui_info in self.session_ui_infos.iter_mut() {
+            session_ui_info.cwd = None;
+        }
+    }
+    pub fn sessions_with_unknown_cwd(&mut self) -> Vec<String> {
+        // marks the sessions as requested, so that we only ask once per session
+        let mut sessions = vec![];
+        for session_ui_info in &self.session_ui_infos {
+            if !session_ui_info.is_remote
+                && !self.session_cwds.contains_key(&session_ui_info.name)
+                && self.requested_session_cwds.insert(session_ui_info.name.clone())
+            {
+                sessions.push(session_ui_info.name.clone());
+            }
+        }
+        sessions
+    }
+    pub fn set_session_cwd(&mut self, session_name: &str, cwd: String) {
+        if let Some(session_ui_info) = self
+            .session_ui_infos
+            .iter_mut()
+            .find(|s| s.name == session_name)
+        {
+            session_ui_info.cwd = Some(cwd.clone());
+        }
+        self.session_cwds.insert(session_name.to_owned(), cwd);
+    }
+    pub fn compact_entries(&self) -> (Vec<String>, Option<usize>) {
+        // (entry names, selected entry)
+        if self.is_searching {
+            let entries = self
+                .search_results
+                .iter()
+                .map(|s| s.list_item.name.clone())
+                .collect();
+            (entries, self.selected_search_index)
+        } else {
+            let entries = self
+                .session_ui_infos
+                .iter()
+                .map(|s| s.name.clone())
+                .collect();
+            (entries, self.selected_index.0)
+        }
+    }
+    pub fn result_position(&self) -> (Option<usize>, usize) {
+        // (selected result, result count)
+        if self.is_searching {
+            let count = self.search_results.len();
+            (self.selected_search_index.filter(|i| *i < count), count)
+        } else {
+            (self.selected_index.0, self.session_ui_infos.len())
+        }
+    }
     pub fn is_expanded(&self) -> bool {
         self.show_expanded_content
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct SelectedIndex(pub Option<usize>, pub Option<usize>, pub Option<usize>);
 
 impl SelectedIndex {
@@ -437,6 +1200,7 @@ pub struct SearchResult {
     tab_position: Option<usize>,
     pane_id: Option<(u32, bool)>,
     is_current_session: bool,
+    is_child_match: bool,
 }
 
 impl SearchResult {
@@ -457,6 +1221,7 @@ impl SearchResult {
             tab_position,
             pane_id,
             is_current_session,
+            is_child_match: false,
         }
     }
     pub fn lines_to_render(&self) -> usize {
@@ -466,3 +1231,171 @@ impl SearchResult {
         self.list_item.render(Some(self.indices.clone()), max_width)
     }
 }
+
+// the skim matcher already gives word boundary, camelCase and consecutive character bonuses
+// (like fzf), on top of those we want the start of a name to weigh more than its middle: typing
+// `api` should put `api-gateway` above `gateway-api`
+const PREFIX_BONUS: i64 = 64;
+const FIRST_CHAR_BONUS: i64 = 16;
+
+fn score_match(
+    matcher: &SkimMatcherV2,
+    candidate: &str,
+    search_term: &str,
+) -> Option<(i64, Vec<usize>)> {
+    let (mut score, indices) = matcher.fuzzy_indices(candidate, search_term)?;
+    if candidate
+        .to_lowercase()
+        .starts_with(&search_term.to_lowercase())
+    {
+        score += PREFIX_BONUS;
+    } else if indices.first() == Some(&0) {
+        score += FIRST_CHAR_BONUS;
+    }
+    Some((score, indices))
+}
+
+struct ChildMatch {
+    score: i64,
+    kind: &'static str,
+    name: String,
+    tab_position: Option<usize>,
+    pane_id: Option<(u32, bool)>,
+}
+
+fn best_child_match(
+    session: &SessionUiInfo,
+    search_term: &str,
+    matcher: &SkimMatcherV2,
+) -> Option<ChildMatch> {
+    let mut best_match: Option<ChildMatch> = None;
+    for tab in &session.tabs {
+        if let Some((score, _)) = score_match(matcher, &tab.name, search_term) {
+            if best_match.as_ref().map(|m| score > m.score).unwrap_or(true) {
+                best_match = Some(ChildMatch {
+                    score,
+                    kind: "tab",
+                    name: tab.name.clone(),
+                    tab_position: Some(tab.position),
+                    pane_id: None,
+                });
+            }
+        }
+        for pane in &tab.panes {
+            if let Some((score, _)) = score_match(matcher, &pane.name, search_term) {
+                if best_match.as_ref().map(|m| score > m.score).unwrap_or(true) {
+                    best_match = Some(ChildMatch {
+                        score,
+                        kind: "pane",
+                        name: pane.name.clone(),
+                        tab_position: Some(tab.position),
+                        pane_id: Some((pane.pane_id, pane.is_plugin)),
+                    });
+                }
+            }
+        }
+    }
+    best_match
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_session(name: &str) -> SessionUiInfo {
+        SessionUiInfo {
+            is_remote: false,
+            web_clients_allowed: true,
+            ..SessionUiInfo::remote(name)
+        }
+    }
+
+    fn session_list(
+        session_ui_infos: Vec<SessionUiInfo>,
+        forbidden_sessions: Vec<SessionUiInfo>,
+    ) -> SessionList {
+        let mut session_list = SessionList::default();
+        session_list.set_sessions(session_ui_infos, forbidden_sessions);
+        session_list
+    }
+
+    #[test]
+    fn all_other_sessions_leave_out_the_current_and_remote_sessions() {
+        let mut session_list = session_list(
+            vec![
+                SessionUiInfo {
+                    is_current_session: true,
+                    ..local_session("current")
+                },
+                local_session("api"),
+                local_session("gateway"),
+            ],
+            vec![],
+        );
+        session_list.set_remote_sessions(vec!["remote".to_owned()]);
+        assert_eq!(session_list.all_other_sessions(), vec!["api", "gateway"]);
+    }
+
+    #[test]
+    fn all_other_sessions_leave_out_forbidden_sessions() {
+        // a web client is not given the sessions that disallow web clients, but even if one is
+        // listed it must not be killed (or counted) from the web
+        let session_list = session_list(
+            vec![local_session("api"), local_session("secret")],
+            vec![SessionUiInfo {
+                web_clients_allowed: false,
+                ..local_session("secret")
+            }],
+        );
+        assert_eq!(session_list.all_other_sessions(), vec!["api"]);
+    }
+
+    #[test]
+    fn all_other_sessions_include_web_restricted_sessions_for_terminal_clients() {
+        // sessions that disallow web clients are only forbidden for web clients
+        let session_list = session_list(
+            vec![
+                local_session("api"),
+                SessionUiInfo {
+                    web_clients_allowed: false,
+                    ..local_session("terminal-only")
+                },
+            ],
+            vec![],
+        );
+        assert_eq!(session_list.all_other_sessions(), vec!["api", "terminal-only"]);
+    }
+
+    #[test]
+    fn all_other_sessions_leave_out_hidden_sessions_unless_shown() {
+        let hidden_session = SessionUiInfo {
+            is_hidden: true,
+            ..local_session("welcome")
+        };
+        let mut session_list = session_list(vec![local_session("api"), hidden_session], vec![]);
+        assert_eq!(session_list.all_other_sessions(), vec!["api"]);
+        session_list.toggle_show_hidden();
+        assert_eq!(session_list.all_other_sessions(), vec!["api", "welcome"]);
+    }
+
+    fn score(candidate: &str, search_term: &str) -> Option<i64> {
+        score_match(&SkimMatcherV2::default(), candidate, search_term).map(|(score, _)| score)
+    }
+
+    #[test]
+    fn a_search_term_at_the_start_of_a_name_ranks_it_first() {
+        assert!(score("api-gateway", "api") > score("gateway-api", "api"));
+        assert!(score("gateway-api", "api").is_some());
+        assert_eq!(score("gateway-test", "api"), None);
+        assert_eq!(
+            score_match(&SkimMatcherV2::default(), "api-gateway", "api").map(|(_, i)| i),
+            Some(vec![0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn a_match_on_the_first_character_ranks_above_one_in_the_middle() {
+        assert!(score("gateway-test", "gw") > score("api-gateway", "gw"));
+        assert!(score("api-gateway", "gw").is_some());
+    }
+}
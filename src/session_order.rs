@@ -0,0 +1,60 @@
+use std::fs;
+
+use crate::logging::{log, LogLevel};
+
+// shared between all instances of this plugin like the session history, so the curated order is
+// the same in every session
+const SESSION_ORDER_FILE: &str = "/cache/session_order";
+
+// the order of the session list with sort = manual, by name - it keeps the names of sessions
+// that are not running so that they get their place back when they are resurrected
+#[derive(Debug, Default)]
+pub struct SessionOrder {
+    session_names: Vec<String>,
+}
+
+impl SessionOrder {
+    pub fn position(&self, session_name: &str) -> Option<usize> {
+        self.session_names.iter().position(|s| s == session_name)
+    }
+    pub fn set_order(
+        &mut self,
+        ordered_session_names: Vec<String>,
+        is_known_session: impl Fn(&str) -> bool,
+    ) {
+        // the given (listed) sessions first, then the ones we know of that are not listed - the
+        // names of sessions that are neither running nor resurrectable anymore are dropped
+        self.reload();
+        let unlisted_session_names: Vec<String> = self
+            .session_names
+            .drain(..)
+            .filter(|s| !ordered_session_names.contains(s) && is_known_session(s))
+            .collect();
+        self.session_names = ordered_session_names;
+        self.session_names.extend(unlisted_session_names);
+        self.save();
+    }
+    pub fn rename(&mut self, old_session_name: &str, new_session_name: &str) {
+        self.reload();
+        if let Some(position) = self.position(old_session_name) {
+            self.session_names[position] = new_session_name.to_owned();
+            self.save();
+        }
+    }
+    pub fn reload(&mut self) {
+        // persistence is best effort, if the cache folder is not available we keep using
+        // whatever we have in memory
+        if let Ok(contents) = fs::read_to_string(SESSION_ORDER_FILE) {
+            self.session_names = contents
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(|l| l.to_owned())
+                .collect();
+        }
+    }
+    fn save(&self) {
+        if let Err(e) = fs::write(SESSION_ORDER_FILE, self.session_names.join("\n")) {
+            log!(LogLevel::Error, "Failed to save session order: {}", e);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use crate::logging::{log, LogLevel};
+
+// shared between all instances of this plugin like the session history, so a tag added in one
+// session shows up in the session manager of the others
+const SESSION_TAGS_FILE: &str = "/cache/session_tags";
+
+// freeform tags by session name, filtered on with #tag in the search
+#[derive(Debug, Default)]
+pub struct SessionTags {
+    tags: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SessionTags {
+    pub fn tags(&self, session_name: &str) -> Vec<String> {
+        self.tags
+            .get(session_name)
+            .map(|t| t.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+    pub fn has_tags(&self, session_name: &str, tags: &[String]) -> bool {
+        tags.iter().all(|tag| {
+            self.tags
+                .get(session_name)
+                .map(|t| t.contains(tag))
+                .unwrap_or(false)
+        })
+    }
+    pub fn toggle(&mut self, session_name: &str, tag: &str) -> bool {
+        // returns whether the tag was added
+        self.reload();
+        let tags = self.tags.entry(session_name.to_owned()).or_default();
+        let was_added = tags.insert(tag.to_owned());
+        if !was_added {
+            tags.remove(tag);
+        }
+        if tags.is_empty() {
+            self.tags.remove(session_name);
+        }
+        self.save();
+        was_added
+    }
+    pub fn rename(&mut self, old_session_name: &str, new_session_name: &str) {
+        self.reload();
+        if let Some(tags) = self.tags.remove(old_session_name) {
+            self.tags.insert(new_session_name.to_owned(), tags);
+            self.save();
+        }
+    }
+    pub fn reload(&mut self) {
+        // persistence is best effort, if the cache folder is not available we keep using
+        // whatever we have in memory
+        if let Ok(contents) = fs::read_to_string(SESSION_TAGS_FILE) {
+            self.tags = contents
+                .lines()
+                .filter_map(|l| l.split_once('\t'))
+                .map(|(session_name, tags)| {
+                    (
+                        session_name.to_owned(),
+                        tags.split_whitespace().map(|t| t.to_owned()).collect(),
+                    )
+                })
+                .collect();
+        }
+    }
+    fn save(&self) {
+        let contents: Vec<String> = self
+            .tags
+            .iter()
+            .map(|(session_name, tags)| {
+                let tags: Vec<&str> = tags.iter().map(|t| t.as_str()).collect();
+                format!("{}\t{}", session_name, tags.join(" "))
+            })
+            .collect();
+        if let Err(e) = fs::write(SESSION_TAGS_FILE, contents.join("\n")) {
+            log!(LogLevel::Error, "Failed to save session tags: {}", e);
+        }
+    }
+}
+
+pub fn is_tag_character(character: char) -> bool {
+    // tags are single words in the search, so they cannot hold whitespace or the # they start with
+    !character.is_whitespace() && character != '#'
+}
+
+pub fn parse_tag_filters(search_term: &str) -> (Vec<String>, String) {
+    // #tag words of the search term and the rest of it, which is still fuzzy matched
+    let (tag_words, text_words): (Vec<&str>, Vec<&str>) = search_term
+        .split(' ')
+        .partition(|w| w.len() > 1 && w.starts_with('#'));
+    if tag_words.is_empty() {
+        return (vec![], search_term.to_owned());
+    }
+    let tag_filters = tag_words.iter().map(|w| w[1..].to_owned()).collect();
+    let text_words: Vec<&str> = text_words.into_iter().filter(|w| !w.is_empty()).collect();
+    (tag_filters, text_words.join(" "))
+}
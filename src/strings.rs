@@ -0,0 +1,716 @@
+use std::ops::Range;
+
+// user facing messages by id, in english unless the `lang` config picks one of the bundled
+// translations - the screen labels (which the messages refer to by name), the session list lines
+// and details (eg. <CURRENT SESSION>) and the banner stay in english
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    AlreadyAttached,
+    AreYouSure,
+    AttachToTab,
+    AttachWithLayoutTitle,
+    AutoAttachWarning,
+    BatchRenameFailed,
+    BatchRenamed,
+    BuiltIn,
+    Clear,
+    CommandNeedsSessionName,
+    Copied,
+    CreatedAgo,
+    CreatedSession,
+    CreatingSession,
+    CurrentLayoutUnavailable,
+    DefaultLayout,
+    DeleteResurrectableSessionsWarning,
+    DeletingDisabled,
+    DiscardNewSessionWarning,
+    Disconnect,
+    DisconnectOthersWarning,
+    DisconnectedOtherClients,
+    EnvVariablesInstruction,
+    EnvVariablesOnlyForNewSessions,
+    EnvVariablesPrompt,
+    EnvVariablesWithCurrentLayout,
+    Error,
+    ExpandToSaveLayout,
+    FailedToCopy,
+    FailedToCreateSession,
+    FailedToExpandDefaultCwd,
+    FailedToResurrectSession,
+    FailedToSaveLayout,
+    FilterSessionsToRenameFirst,
+    FolderPrompt,
+    FolderPromptShort,
+    ForbiddenSession,
+    ForceKillSessionWarning,
+    ForceKillUnsupported,
+    GoBack,
+    Help,
+    HelpNewSession,
+    Hide,
+    HidingHiddenSessions,
+    HintCollapseTabsPanes,
+    HintCommand,
+    HintDelete,
+    HintDeleteAll,
+    HintDisconnectOthers,
+    HintFolderDisplay,
+    HintForceKill,
+    HintGitRoot,
+    HintKill,
+    HintKillAll,
+    HintNavigate,
+    HintNewFromCurrentLayout,
+    HintOpenInNewWindow,
+    HintPickTab,
+    HintPreviousSession,
+    HintRecentlyKilled,
+    HintRename,
+    HintReorder,
+    HintResurrect,
+    HintSelectRange,
+    HintShowHidden,
+    HintTagSession,
+    HintWithLayout,
+    InitialCommandEmpty,
+    InitialCommandFailed,
+    InitialCommandInstruction,
+    InitialCommandOnlyForNewSessions,
+    InitialCommandPrompt,
+    InitialCommandWithCurrentLayout,
+    KillSessionWarning,
+    KillSessionsWarning,
+    KilledSessions,
+    KillingSessions,
+    LayoutClassic,
+    LayoutCompact,
+    LayoutDefault,
+    LayoutDisableStatusBar,
+    LayoutOnlyForLocalSessions,
+    LayoutPrompt,
+    LayoutSearchInstruction,
+    LayoutStrider,
+    LayoutTabsAndPanes,
+    LayoutWelcome,
+    MalformedEnvVariable,
+    MoreItems,
+    MustSelectSessionToAttach,
+    MustSelectSessionToCopy,
+    MustSelectSessionToKill,
+    MustSelectSessionToOpen,
+    MustSelectSessionToPickTab,
+    MustSelectSessionToRename,
+    MustSelectSessionToSaveLayout,
+    MustSelectSessionToTag,
+    NeedsConfig,
+    NewNameEmpty,
+    NewNameFor,
+    NewNameForCurrentSession,
+    NewScreenDisabled,
+    NewSessionInProgress,
+    NewSessionLayoutPrompt,
+    NewSessionNameInstruction,
+    NewSessionNamePrompt,
+    NewWindowNeedsRemoteUrl,
+    NoHiddenSessions,
+    NoKilledSessions,
+    NoLayoutMatches,
+    NoOtherSessionsInSelection,
+    NoOtherSessionsToKill,
+    NoPreviousSession,
+    NoSessionNamed,
+    NoSessionsMatchFilter,
+    NotInGitRepo,
+    OpenInNewWindow,
+    OpenRemoteSession,
+    OverwriteLayoutFileWarning,
+    PastedNameStripped,
+    PermissionsDenied,
+    PermissionsDeniedDisabled,
+    PermissionsDeniedHowToGrant,
+    PostCreateCommandFailed,
+    PostCreateCommandFinished,
+    RandomSessionName,
+    RecentlyKilledTitle,
+    RecreateSession,
+    Refreshed,
+    RemoteLayoutUnknown,
+    RenameFailedHasSlash,
+    RenameFailedNameEmpty,
+    RenameFailedNameTaken,
+    RenameFailedNameTooLong,
+    RenameMatchingSessions,
+    RenamedSession,
+    RenamingSession,
+    ResurrectSession,
+    ResurrectWithAnotherLayoutTitle,
+    ResurrectWithLayoutDisabled,
+    ResurrectWithLayoutWarning,
+    ResurrectableSessionNameTaken,
+    RunInitialCommandHint,
+    SavedLayout,
+    Search,
+    SearchWithQualifiers,
+    SessionAlreadyRunning,
+    SessionHasNoTabs,
+    SessionNameBlank,
+    SessionNameHasSlash,
+    SessionNameTaken,
+    SessionNameTooLong,
+    SetEnvVariablesHint,
+    ShowingHiddenSessions,
+    Sort,
+    SortAlphabetical,
+    SortMostRecentFirst,
+    StartedWebSharing,
+    StoppedWebSharing,
+    TabCount,
+    TabsOf,
+    TabsOnlyForLocalSessions,
+    TagAdded,
+    TagRemoved,
+    TagSessionTitle,
+    ToChange,
+    ToCorrect,
+    ToReset,
+    ToSet,
+    UnknownCommand,
+    WebSharingNotPermitted,
+    WebSharingOnlyCurrentSession,
+    WhenDone,
+}
+
+impl Language {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "en" => Some(Language::English),
+            "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+    pub fn text(&self, message: Message) -> &'static str {
+        match self {
+            Language::English => english(message),
+            Language::German => german(message),
+        }
+    }
+    pub fn format(&self, message: Message, args: &[&str]) -> String {
+        self.format_with_ranges(message, args).0
+    }
+    pub fn format_with_ranges(
+        &self,
+        message: Message,
+        args: &[&str],
+    ) -> (String, Vec<Range<usize>>) {
+        // fills the {} placeholders in order, returning where each argument ended up (in
+        // characters) so that it can be highlighted whatever the word order of the translation
+        let mut text = String::new();
+        let mut arg_ranges = vec![];
+        for (i, part) in self.text(message).split("{}").enumerate() {
+            if i > 0 {
+                let arg = args.get(i - 1).copied().unwrap_or_default();
+                let arg_start = text.chars().count();
+                text.push_str(arg);
+                arg_ranges.push(arg_start..arg_start + arg.chars().count());
+            }
+            text.push_str(part);
+        }
+        (text, arg_ranges)
+    }
+}
+
+pub fn confirmation_key_indices(confirmation_text: &str) -> Vec<usize> {
+    // the keys stay y/n in every language, so translations keep the "(y/n)"
+    match confirmation_text.find("(y/n)") {
+        Some(byte_index) => {
+            let start = confirmation_text[..byte_index].chars().count();
+            vec![start + 1, start + 3]
+        },
+        None => vec![],
+    }
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::AlreadyAttached => "Already attached to {}.",
+        Message::AreYouSure => "Are you sure? (y/n)",
+        Message::AttachToTab => "Attach to tab",
+        Message::AttachWithLayoutTitle => "Attach to {} with a layout",
+        Message::AutoAttachWarning => {
+            "This will attach to {} right away, like any search that only matches one session"
+        },
+        Message::BatchRenameFailed => "Renamed {} of {} sessions, failed: {}",
+        Message::BatchRenamed => "Renamed {} of {} sessions.",
+        Message::BuiltIn => "built-in",
+        Message::Clear => "Clear",
+        Message::Copied => "Copied: {}",
+        Message::CreatedAgo => "Created {} ago",
+        Message::CreatedSession => "Created {}.",
+        Message::CreatingSession => "Creating {}...",
+        Message::CommandNeedsSessionName => ":{} needs a session name.",
+        Message::CurrentLayoutUnavailable => "The current session layout is not available.",
+        Message::DefaultLayout => "Default layout",
+        Message::DeleteResurrectableSessionsWarning => "This will delete {} resurrectable sessions",
+        Message::DeletingDisabled => "Deleting resurrectable sessions is disabled.",
+        Message::DiscardNewSessionWarning => "This will hide the new session you filled in",
+        Message::Disconnect => "disconnect",
+        Message::DisconnectOthersWarning => "This will {} all other clients from this session",
+        Message::DisconnectedOtherClients => "Disconnected all other clients from this session.",
+        Message::EnvVariablesInstruction => "(KEY=VALUE, space separated)",
+        Message::EnvVariablesOnlyForNewSessions => {
+            "Environment variables only apply to new sessions."
+        },
+        Message::EnvVariablesPrompt => "Environment:",
+        Message::EnvVariablesWithCurrentLayout => {
+            "Environment variables cannot be used with the current session layout."
+        },
+        Message::Error => "Error: {}",
+        Message::ExpandToSaveLayout => {
+            "Expand the session list (Ctrl t) to save a session's layout."
+        },
+        Message::FailedToCopy => "Failed to copy: {}",
+        Message::FailedToCreateSession => "Failed to create session: {}",
+        Message::FailedToExpandDefaultCwd => "Failed to expand default_new_session_cwd: {}",
+        Message::FailedToResurrectSession => "Failed to resurrect session: {}",
+        Message::FailedToSaveLayout => "Failed to save layout: {}",
+        Message::FilterSessionsToRenameFirst => "Filter the sessions to rename first.",
+        Message::FolderPrompt => "New session folder:",
+        Message::FolderPromptShort => "Folder:",
+        Message::ForbiddenSession => {
+            "This session disallows web clients; open it from a terminal."
+        },
+        Message::ForceKillSessionWarning => "This will force kill session {}",
+        Message::ForceKillUnsupported => {
+            "Force killing is not supported by the host, {} was killed normally."
+        },
+        Message::GoBack => "({} to go back)",
+        Message::Help => "Help:",
+        Message::HelpNewSession => "Help: Fill in the form to start a new session",
+        Message::Hide => "<Esc> - Hide",
+        Message::HidingHiddenSessions => "Hiding the sessions that are normally hidden.",
+        Message::HintCollapseTabsPanes => "Collapse/tabs/panes",
+        Message::HintCommand => "Command",
+        Message::HintDelete => "Delete",
+        Message::HintDeleteAll => "Delete all",
+        Message::HintDisconnectOthers => "Disconnect others",
+        Message::HintFolderDisplay => "Folder display",
+        Message::HintForceKill => "Force kill",
+        Message::HintGitRoot => "Git repo root",
+        Message::HintKill => "Kill",
+        Message::HintKillAll => "Kill all",
+        Message::HintNavigate => "Navigate",
+        Message::HintNewFromCurrentLayout => "New from current layout",
+        Message::HintOpenInNewWindow => "Open in new window",
+        Message::HintPickTab => "Pick tab",
+        Message::HintPreviousSession => "Previous session",
+        Message::HintRecentlyKilled => "Recently killed",
+        Message::HintRename => "Rename",
+        Message::HintReorder => "Reorder",
+        Message::HintResurrect => "Resurrect",
+        Message::HintSelectRange => "Select range",
+        Message::HintShowHidden => "Show hidden",
+        Message::HintTagSession => "Tag session",
+        Message::HintWithLayout => "With layout",
+        Message::InitialCommandEmpty => "The initial command cannot be empty.",
+        Message::InitialCommandFailed => {
+            "Created session {} but failed to run the initial command: {}"
+        },
+        Message::InitialCommandInstruction => "(runs in a new pane)",
+        Message::InitialCommandOnlyForNewSessions => {
+            "The initial command only applies to new sessions."
+        },
+        Message::InitialCommandPrompt => "Initial command:",
+        Message::InitialCommandWithCurrentLayout => {
+            "An initial command cannot be used with the current session layout."
+        },
+        Message::KillSessionWarning => "This will kill session {}",
+        Message::KillSessionsWarning => "This will kill {} active sessions",
+        Message::KilledSessions => "Killed {} sessions.",
+        Message::KillingSessions => "Killing {} sessions...",
+        Message::LayoutClassic => "Tab bar, a terminal pane and the classic status bar",
+        Message::LayoutCompact => "A terminal pane and a single-line compact bar",
+        Message::LayoutDefault => "Tab bar, a terminal pane and the status bar",
+        Message::LayoutDisableStatusBar => "Tab bar and a terminal pane, no status bar",
+        Message::LayoutOnlyForLocalSessions => "Layouts can only be applied to local sessions.",
+        Message::LayoutPrompt => "Layout",
+        Message::LayoutSearchInstruction => "(Search and select from list, {} when done)",
+        Message::LayoutStrider => "A file explorer beside a terminal pane",
+        Message::LayoutTabsAndPanes => "{} tab(s), {} pane(s)",
+        Message::LayoutWelcome => "The session manager welcome screen",
+        Message::MalformedEnvVariable => "Malformed environment variable: {}",
+        Message::MoreItems => "+ {} more",
+        Message::MustSelectSessionToAttach => {
+            "Must select session before attaching to it with a layout."
+        },
+        Message::MustSelectSessionToCopy => {
+            "Must select session before copying its attach command."
+        },
+        Message::MustSelectSessionToKill => "Must select session before killing it.",
+        Message::MustSelectSessionToOpen => "Must select session before opening it.",
+        Message::MustSelectSessionToPickTab => "Must select session before picking its tab.",
+        Message::MustSelectSessionToRename => "Must select session before renaming it.",
+        Message::MustSelectSessionToSaveLayout => "Must select session before saving its layout.",
+        Message::MustSelectSessionToTag => "Must select session before tagging it.",
+        Message::NeedsConfig => "This needs {} = true in the plugin config.",
+        Message::NewNameEmpty => "New name must not be empty.",
+        Message::NewNameFor => "New name for {}",
+        Message::NewNameForCurrentSession => "New name for current session",
+        Message::NewScreenDisabled => "The New screen is disabled.",
+        Message::NewSessionInProgress => {
+            "A new session is being filled in on the New screen, finish or clear it first."
+        },
+        Message::NewSessionLayoutPrompt => "New session layout",
+        Message::NewSessionNameInstruction => "when done, blank for random",
+        Message::NewSessionNamePrompt => "New session name:",
+        Message::NewWindowNeedsRemoteUrl => {
+            "Set remote_url to open sessions in a new browser window."
+        },
+        Message::NoHiddenSessions => "There are no hidden sessions.",
+        Message::NoKilledSessions => "No sessions were killed from here yet.",
+        Message::NotInGitRepo => "The folder is not inside a git repository.",
+        Message::NoLayoutMatches => "No layout matches the search.",
+        Message::NoOtherSessionsInSelection => {
+            "No other sessions in the selection. Quit to kill the current one."
+        },
+        Message::NoOtherSessionsToKill => {
+            "No other sessions to kill. Quit to kill the current one."
+        },
+        Message::NoPreviousSession => "There is no previous session to swap with yet.",
+        Message::NoSessionNamed => "There is no session named {}.",
+        Message::NoSessionsMatchFilter => "No sessions match the filter.",
+        Message::OpenInNewWindow => "Open {} in a new browser tab to work side by side.",
+        Message::OpenRemoteSession => "Open {} to attach to this remote session.",
+        Message::OverwriteLayoutFileWarning => "This will overwrite the existing layout file {}",
+        Message::PastedNameStripped => {
+            "Left out the line breaks and slashes a session name cannot have."
+        },
+        Message::PermissionsDenied => {
+            "The session manager was not granted the permissions it needs."
+        },
+        Message::PermissionsDeniedDisabled => {
+            "Listing, creating, attaching to, renaming and killing sessions are disabled."
+        },
+        Message::PermissionsDeniedHowToGrant => {
+            "To grant them, remove this plugin from zellij's permissions.kdl and reload it."
+        },
+        Message::PostCreateCommandFailed => "Post create command failed for {}: {}",
+        Message::PostCreateCommandFinished => "Post create command finished for {}.",
+        Message::RandomSessionName => "<RANDOM>",
+        Message::RecentlyKilledTitle => "Recently killed",
+        Message::RecreateSession => "Re-create Session",
+        Message::Refreshed => "Refreshed.",
+        Message::RemoteLayoutUnknown => "The layout of remote sessions is not known.",
+        Message::RenameFailedHasSlash => "name contains '/'",
+        Message::RenameFailedNameEmpty => "empty name",
+        Message::RenameFailedNameTaken => "name taken",
+        Message::RenameFailedNameTooLong => "name too long",
+        Message::RenameMatchingSessions => "Rename {} matching sessions ({} is the current name)",
+        Message::RenamedSession => "Renamed to {}.",
+        Message::RenamingSession => "Renaming to {}...",
+        Message::ResurrectSession => "Resurrect Session",
+        Message::ResurrectWithAnotherLayoutTitle => "Resurrect {} with another layout",
+        Message::ResurrectWithLayoutDisabled => {
+            "Resurrecting with another layout is disabled along with deleting."
+        },
+        Message::ResurrectWithLayoutWarning => {
+            "This will delete the stored layout of {} and create it anew with the picked one"
+        },
+        Message::ResurrectableSessionNameTaken => {
+            "A resurrectable session by this name already exists."
+        },
+        Message::RunInitialCommandHint => "{} to run a command in a new pane",
+        Message::SavedLayout => "Saved the layout to {}",
+        Message::Search => "Search:",
+        Message::SearchWithQualifiers => "Search ({}):",
+        Message::SessionAlreadyRunning => "A session named {} is already running.",
+        Message::SessionHasNoTabs => "The session has no tabs to pick from.",
+        Message::SessionNameBlank => "Session name cannot be blank",
+        Message::SessionNameHasSlash => "Session names cannot contain '/'",
+        Message::SessionNameTaken => "A session by this name already exists.",
+        Message::SessionNameTooLong => "Session name must be shorter than 108 bytes",
+        Message::SetEnvVariablesHint => "{} to set environment variables",
+        Message::ShowingHiddenSessions => "Showing {} hidden sessions.",
+        Message::Sort => "Sort: {}",
+        Message::SortAlphabetical => "alphabetical",
+        Message::SortMostRecentFirst => "most recent",
+        Message::StartedWebSharing => "Sharing this session with web clients.",
+        Message::StoppedWebSharing => "Stopped sharing this session with web clients.",
+        Message::TabCount => "{} tabs",
+        Message::TabsOf => "Tabs of {}",
+        Message::TabsOnlyForLocalSessions => "Tabs can only be picked for local sessions.",
+        Message::TagAdded => "Tagged {} with #{}.",
+        Message::TagRemoved => "Removed #{} from {}.",
+        Message::TagSessionTitle => "Tag to add to or remove from {}",
+        Message::ToChange => "to change",
+        Message::ToCorrect => "to correct",
+        Message::ToReset => "to reset",
+        Message::ToSet => "to set",
+        Message::UnknownCommand => "Unknown command :{}, try kill, new or rename.",
+        Message::WebSharingNotPermitted => "The host did not permit changing web sharing.",
+        Message::WebSharingOnlyCurrentSession => {
+            "Web sharing can only be changed for the current session."
+        },
+        Message::WhenDone => "({} when done)",
+    }
+}
+
+fn german(message: Message) -> &'static str {
+    match message {
+        Message::AlreadyAttached => "Bereits mit {} verbunden.",
+        Message::AreYouSure => "Bist du sicher? (y/n)",
+        Message::AttachToTab => "Mit Tab verbinden",
+        Message::AttachWithLayoutTitle => "Mit {} über ein Layout verbinden",
+        Message::AutoAttachWarning => {
+            "Dies verbindet sofort mit {}, wie jede Suche, die nur eine Sitzung findet"
+        },
+        Message::BatchRenameFailed => "{} von {} Sitzungen umbenannt, fehlgeschlagen: {}",
+        Message::BatchRenamed => "{} von {} Sitzungen umbenannt.",
+        Message::BuiltIn => "eingebaut",
+        Message::Clear => "Leeren",
+        Message::Copied => "Kopiert: {}",
+        Message::CreatedAgo => "Vor {} erstellt",
+        Message::CreatedSession => "{} erstellt.",
+        Message::CreatingSession => "Erstelle {}...",
+        Message::CommandNeedsSessionName => ":{} braucht einen Sitzungsnamen.",
+        Message::CurrentLayoutUnavailable => {
+            "Das Layout der aktuellen Sitzung ist nicht verfügbar."
+        },
+        Message::DefaultLayout => "Standard-Layout",
+        Message::DeleteResurrectableSessionsWarning => {
+            "Dies löscht {} wiederherstellbare Sitzungen"
+        },
+        Message::DeletingDisabled => "Das Löschen wiederherstellbarer Sitzungen ist deaktiviert.",
+        Message::DiscardNewSessionWarning => "Dies blendet die ausgefüllte neue Sitzung aus",
+        Message::Disconnect => "trennen",
+        Message::DisconnectOthersWarning => "Dies wird alle anderen Clients von dieser Sitzung {}",
+        Message::DisconnectedOtherClients => {
+            "Alle anderen Clients wurden von dieser Sitzung getrennt."
+        },
+        Message::EnvVariablesInstruction => "(KEY=VALUE, durch Leerzeichen getrennt)",
+        Message::EnvVariablesOnlyForNewSessions => {
+            "Umgebungsvariablen gelten nur für neue Sitzungen."
+        },
+        Message::EnvVariablesPrompt => "Umgebung:",
+        Message::EnvVariablesWithCurrentLayout => {
+            "Umgebungsvariablen können nicht mit dem Layout der aktuellen Sitzung verwendet werden."
+        },
+        Message::Error => "Fehler: {}",
+        Message::ExpandToSaveLayout => {
+            "Klappe die Sitzungsliste aus (Ctrl t), um das Layout einer Sitzung zu speichern."
+        },
+        Message::FailedToCopy => "Kopieren fehlgeschlagen: {}",
+        Message::FailedToCreateSession => "Sitzung konnte nicht erstellt werden: {}",
+        Message::FailedToExpandDefaultCwd => {
+            "default_new_session_cwd konnte nicht aufgelöst werden: {}"
+        },
+        Message::FailedToResurrectSession => "Sitzung konnte nicht wiederhergestellt werden: {}",
+        Message::FailedToSaveLayout => "Layout konnte nicht gespeichert werden: {}",
+        Message::FilterSessionsToRenameFirst => {
+            "Filtere zuerst die Sitzungen, die umbenannt werden sollen."
+        },
+        Message::FolderPrompt => "Ordner der neuen Sitzung:",
+        Message::FolderPromptShort => "Ordner:",
+        Message::ForbiddenSession => {
+            "Diese Sitzung lässt keine Web-Clients zu; öffne sie in einem Terminal."
+        },
+        Message::ForceKillSessionWarning => "Dies beendet die Sitzung {} erzwungen",
+        Message::ForceKillUnsupported => {
+            "Der Host unterstützt kein erzwungenes Beenden, {} wurde normal beendet."
+        },
+        Message::GoBack => "({} für zurück)",
+        Message::Help => "Hilfe:",
+        Message::HelpNewSession => "Hilfe: Fülle das Formular aus, um eine neue Sitzung zu starten",
+        Message::Hide => "<Esc> - Ausblenden",
+        Message::HidingHiddenSessions => "Normalerweise versteckte Sitzungen werden ausgeblendet.",
+        Message::HintCollapseTabsPanes => "Einklappen/Tabs/Bereiche",
+        Message::HintCommand => "Befehl",
+        Message::HintDelete => "Löschen",
+        Message::HintDeleteAll => "Alle löschen",
+        Message::HintDisconnectOthers => "Andere trennen",
+        Message::HintFolderDisplay => "Ordneranzeige",
+        Message::HintForceKill => "Erzwungen beenden",
+        Message::HintGitRoot => "Git-Wurzel",
+        Message::HintKill => "Beenden",
+        Message::HintKillAll => "Alle beenden",
+        Message::HintNavigate => "Navigieren",
+        Message::HintNewFromCurrentLayout => "Neu mit aktuellem Layout",
+        Message::HintOpenInNewWindow => "In neuem Fenster öffnen",
+        Message::HintPickTab => "Tab wählen",
+        Message::HintPreviousSession => "Vorherige Sitzung",
+        Message::HintRecentlyKilled => "Zuletzt beendet",
+        Message::HintRename => "Umbenennen",
+        Message::HintReorder => "Umordnen",
+        Message::HintResurrect => "Wiederherstellen",
+        Message::HintSelectRange => "Bereich auswählen",
+        Message::HintShowHidden => "Versteckte zeigen",
+        Message::HintTagSession => "Sitzung markieren",
+        Message::HintWithLayout => "Mit Layout",
+        Message::InitialCommandEmpty => "Der Startbefehl darf nicht leer sein.",
+        Message::InitialCommandFailed => {
+            "Sitzung {} erstellt, aber der Startbefehl ist fehlgeschlagen: {}"
+        },
+        Message::InitialCommandInstruction => "(läuft in einem neuen Bereich)",
+        Message::InitialCommandOnlyForNewSessions => {
+            "Der Startbefehl gilt nur für neue Sitzungen."
+        },
+        Message::InitialCommandPrompt => "Startbefehl:",
+        Message::InitialCommandWithCurrentLayout => {
+            "Ein Startbefehl kann nicht mit dem Layout der aktuellen Sitzung verwendet werden."
+        },
+        Message::KillSessionWarning => "Dies beendet die Sitzung {}",
+        Message::KillSessionsWarning => "Dies beendet {} aktive Sitzungen",
+        Message::KilledSessions => "{} Sitzungen beendet.",
+        Message::KillingSessions => "Beende {} Sitzungen...",
+        Message::LayoutClassic => {
+            "Tab-Leiste, ein Terminal-Bereich und die klassische Statusleiste"
+        },
+        Message::LayoutCompact => "Ein Terminal-Bereich und eine einzeilige kompakte Leiste",
+        Message::LayoutDefault => "Tab-Leiste, ein Terminal-Bereich und die Statusleiste",
+        Message::LayoutDisableStatusBar => {
+            "Tab-Leiste und ein Terminal-Bereich, keine Statusleiste"
+        },
+        Message::LayoutOnlyForLocalSessions => {
+            "Layouts können nur auf lokale Sitzungen angewendet werden."
+        },
+        Message::LayoutPrompt => "Layout",
+        Message::LayoutSearchInstruction => "(Suchen und aus der Liste wählen, {} wenn fertig)",
+        Message::LayoutStrider => "Ein Dateibrowser neben einem Terminal-Bereich",
+        Message::LayoutTabsAndPanes => "{} Tab(s), {} Bereich(e)",
+        Message::LayoutWelcome => "Der Willkommensbildschirm des Sitzungsmanagers",
+        Message::MalformedEnvVariable => "Ungültige Umgebungsvariable: {}",
+        Message::MoreItems => "+ {} weitere",
+        Message::MustSelectSessionToAttach => {
+            "Wähle eine Sitzung aus, bevor du dich mit einem Layout verbindest."
+        },
+        Message::MustSelectSessionToCopy => {
+            "Wähle eine Sitzung aus, bevor du ihren Attach-Befehl kopierst."
+        },
+        Message::MustSelectSessionToKill => "Wähle eine Sitzung aus, bevor du sie beendest.",
+        Message::MustSelectSessionToOpen => "Wähle eine Sitzung aus, bevor du sie öffnest.",
+        Message::MustSelectSessionToPickTab => {
+            "Wähle eine Sitzung aus, bevor du einen ihrer Tabs auswählst."
+        },
+        Message::MustSelectSessionToRename => "Wähle eine Sitzung aus, bevor du sie umbenennst.",
+        Message::MustSelectSessionToSaveLayout => {
+            "Wähle eine Sitzung aus, bevor du ihr Layout speicherst."
+        },
+        Message::MustSelectSessionToTag => "Wähle eine Sitzung aus, bevor du sie markierst.",
+        Message::NeedsConfig => "Dafür muss {} = true in der Plugin-Konfiguration stehen.",
+        Message::NewNameEmpty => "Der neue Name darf nicht leer sein.",
+        Message::NewNameFor => "Neuer Name für {}",
+        Message::NewNameForCurrentSession => "Neuer Name für die aktuelle Sitzung",
+        Message::NewScreenDisabled => "Der Bildschirm New ist deaktiviert.",
+        Message::NewSessionInProgress => {
+            "Auf dem Bildschirm New wird eine Sitzung ausgefüllt, erst abschließen oder leeren."
+        },
+        Message::NewSessionLayoutPrompt => "Layout der neuen Sitzung",
+        Message::NewSessionNameInstruction => "wenn fertig, leer für zufällig",
+        Message::NewSessionNamePrompt => "Name der neuen Sitzung:",
+        Message::NewWindowNeedsRemoteUrl => {
+            "Setze remote_url, um Sitzungen in einem neuen Browserfenster zu öffnen."
+        },
+        Message::NoHiddenSessions => "Es gibt keine versteckten Sitzungen.",
+        Message::NoKilledSessions => "Hier wurden noch keine Sitzungen beendet.",
+        Message::NotInGitRepo => "Der Ordner liegt in keinem Git-Repository.",
+        Message::NoLayoutMatches => "Kein Layout passt zur Suche.",
+        Message::NoOtherSessionsInSelection => {
+            "Keine anderen Sitzungen in der Auswahl. Die aktuelle endet beim Verlassen."
+        },
+        Message::NoOtherSessionsToKill => {
+            "Keine anderen Sitzungen zum Beenden. Die aktuelle endet beim Verlassen."
+        },
+        Message::NoPreviousSession => "Es gibt noch keine vorherige Sitzung zum Wechseln.",
+        Message::NoSessionNamed => "Es gibt keine Sitzung namens {}.",
+        Message::NoSessionsMatchFilter => "Keine Sitzung passt zum Filter.",
+        Message::OpenInNewWindow => {
+            "Öffne {} in einem neuen Browser-Tab, um nebeneinander zu arbeiten."
+        },
+        Message::OpenRemoteSession => {
+            "Öffne {}, um dich mit dieser entfernten Sitzung zu verbinden."
+        },
+        Message::OverwriteLayoutFileWarning => "Dies überschreibt die vorhandene Layout-Datei {}",
+        Message::PastedNameStripped => {
+            "Zeilenumbrüche und Schrägstriche wurden aus dem Sitzungsnamen entfernt."
+        },
+        Message::PermissionsDenied => {
+            "Dem Sitzungsmanager wurden die nötigen Berechtigungen nicht erteilt."
+        },
+        Message::PermissionsDeniedDisabled => {
+            "Auflisten, Erstellen, Verbinden, Umbenennen und Beenden von Sitzungen ist deaktiviert."
+        },
+        Message::PermissionsDeniedHowToGrant => {
+            "Zum Erteilen das Plugin aus zellijs permissions.kdl entfernen und neu laden."
+        },
+        Message::PostCreateCommandFailed => "Post-Create-Befehl für {} fehlgeschlagen: {}",
+        Message::PostCreateCommandFinished => "Post-Create-Befehl für {} abgeschlossen.",
+        Message::RandomSessionName => "<ZUFALL>",
+        Message::RecentlyKilledTitle => "Zuletzt beendet",
+        Message::RecreateSession => "Sitzung neu erstellen",
+        Message::Refreshed => "Aktualisiert.",
+        Message::RemoteLayoutUnknown => "Das Layout entfernter Sitzungen ist nicht bekannt.",
+        Message::RenameFailedHasSlash => "Name enthält '/'",
+        Message::RenameFailedNameEmpty => "leerer Name",
+        Message::RenameFailedNameTaken => "Name vergeben",
+        Message::RenameFailedNameTooLong => "Name zu lang",
+        Message::RenameMatchingSessions => {
+            "{} passende Sitzungen umbenennen ({} ist der aktuelle Name)"
+        },
+        Message::RenamedSession => "Umbenannt in {}.",
+        Message::RenamingSession => "Benenne um in {}...",
+        Message::ResurrectSession => "Sitzung wiederherstellen",
+        Message::ResurrectWithAnotherLayoutTitle => "{} mit einem anderen Layout wiederherstellen",
+        Message::ResurrectWithLayoutDisabled => {
+            "Wiederherstellen mit einem anderen Layout ist wie das Löschen deaktiviert."
+        },
+        Message::ResurrectWithLayoutWarning => {
+            "Dies löscht das gespeicherte Layout von {} und erstellt sie mit dem gewählten neu"
+        },
+        Message::ResurrectableSessionNameTaken => {
+            "Eine wiederherstellbare Sitzung mit diesem Namen existiert bereits."
+        },
+        Message::RunInitialCommandHint => "{} führt einen Befehl in einem neuen Bereich aus",
+        Message::SavedLayout => "Layout in {} gespeichert",
+        Message::Search => "Suche:",
+        Message::SearchWithQualifiers => "Suche ({}):",
+        Message::SessionAlreadyRunning => "Eine Sitzung namens {} läuft bereits.",
+        Message::SessionHasNoTabs => "Die Sitzung hat keine Tabs zur Auswahl.",
+        Message::SessionNameBlank => "Sitzungsnamen dürfen nicht nur aus Leerzeichen bestehen.",
+        Message::SessionNameHasSlash => "Sitzungsnamen dürfen kein '/' enthalten.",
+        Message::SessionNameTaken => "Eine Sitzung mit diesem Namen existiert bereits.",
+        Message::SessionNameTooLong => "Sitzungsnamen müssen kürzer als 108 Bytes sein.",
+        Message::SetEnvVariablesHint => "{} setzt Umgebungsvariablen",
+        Message::ShowingHiddenSessions => "{} versteckte Sitzungen werden angezeigt.",
+        Message::Sort => "Sortierung: {}",
+        Message::SortAlphabetical => "alphabetisch",
+        Message::SortMostRecentFirst => "neueste zuerst",
+        Message::StartedWebSharing => "Diese Sitzung wird mit Web-Clients geteilt.",
+        Message::StoppedWebSharing => "Diese Sitzung wird nicht mehr mit Web-Clients geteilt.",
+        Message::TabCount => "{} Tabs",
+        Message::TabsOf => "Tabs von {}",
+        Message::TabsOnlyForLocalSessions => {
+            "Tabs können nur für lokale Sitzungen ausgewählt werden."
+        },
+        Message::TagAdded => "{} wurde mit #{} markiert.",
+        Message::TagRemoved => "#{} wurde von {} entfernt.",
+        Message::TagSessionTitle => "Markierung, die {} hinzugefügt oder entfernt wird",
+        Message::ToChange => "zum Ändern",
+        Message::ToCorrect => "zum Korrigieren",
+        Message::ToReset => "zum Zurücksetzen",
+        Message::ToSet => "zum Festlegen",
+        Message::UnknownCommand => "Unbekannter Befehl :{}, versuche kill, new oder rename.",
+        Message::WebSharingNotPermitted => {
+            "Der Host hat das Ändern der Web-Freigabe nicht erlaubt."
+        },
+        Message::WebSharingOnlyCurrentSession => {
+            "Die Web-Freigabe kann nur für die aktuelle Sitzung geändert werden."
+        },
+        Message::WhenDone => "({} wenn fertig)",
+    }
+}
@@ -0,0 +1,114 @@
+use zellij_tile::prelude::*;
+
+use crate::strings::{Language, Message};
+use crate::ui::TabUiInfo;
+
+// the tabs of the selected session, to attach straight to one of them without expanding the
+// session in the list - a snapshot taken when it's opened
+#[derive(Debug)]
+pub struct TabPicker {
+    pub session_name: String,
+    tabs: Vec<(usize, String)>, // position and name
+    selected_index: usize,
+}
+
+impl TabPicker {
+    pub fn new(session_name: String, tabs: &[TabUiInfo]) -> Self {
+        TabPicker {
+            session_name,
+            tabs: tabs.iter().map(|t| (t.position, t.name.clone())).collect(),
+            selected_index: 0,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+    pub fn move_selection_down(&mut self) {
+        if self.selected_index + 1 >= self.tabs.len() {
+            self.selected_index = 0;
+        } else {
+            self.selected_index += 1;
+        }
+    }
+    pub fn move_selection_up(&mut self) {
+        if self.selected_index == 0 {
+            self.selected_index = self.tabs.len().saturating_sub(1);
+        } else {
+            self.selected_index -= 1;
+        }
+    }
+    pub fn selected_tab_position(&self) -> Option<usize> {
+        self.tabs
+            .get(self.selected_index)
+            .map(|(position, _)| *position)
+    }
+    pub fn render(&self, language: Language, rows: usize, columns: usize, x: usize, y: usize) {
+        let (tabs_of, session_name_ranges) =
+            language.format_with_ranges(Message::TabsOf, &[&self.session_name]);
+        let tabs_of_len = tabs_of.chars().count();
+        let (go_back, shortcut_ranges) = language.format_with_ranges(Message::GoBack, &["<Esc>"]);
+        let mut title = Text::new(format!("{} {}", tabs_of, go_back)).color_range(2, ..tabs_of_len);
+        for range in session_name_ranges {
+            title = title.color_range(0, range);
+        }
+        for range in shortcut_ranges {
+            title = title.color_range(
+                3,
+                range.start + tabs_of_len + 1..range.end + tabs_of_len + 1,
+            );
+        }
+        print_text_with_coordinates(title, x.saturating_sub(1), y + 2, None, None);
+        let table_rows = rows.saturating_sub(5); // title row, controls row and some padding
+        let (first_row_index_to_render, last_row_index_to_render) =
+            self.range_to_render(table_rows);
+        let mut table = Table::new().add_row(vec![" ", " ", " "]); // skip the title row
+        for i in first_row_index_to_render..last_row_index_to_render {
+            let (position, name) = &self.tabs[i];
+            let is_selected = i == self.selected_index;
+            let mut table_cells = vec![
+                Text::new(format!("{}", position + 1)).color_range(3, ..),
+                Text::new(name).color_range(0, ..),
+                if is_selected {
+                    Text::new(format!("<ENTER> - {}", language.text(Message::AttachToTab)))
+                        .color_range(3, 0..7)
+                } else if i == first_row_index_to_render && i > 0 {
+                    Text::new(language.format(
+                        Message::MoreItems,
+                        &[&first_row_index_to_render.to_string()],
+                    ))
+                    .color_range(1, ..)
+                } else if i + 1 == last_row_index_to_render
+                    && last_row_index_to_render < self.tabs.len()
+                {
+                    Text::new(language.format(
+                        Message::MoreItems,
+                        &[&(self.tabs.len() - last_row_index_to_render).to_string()],
+                    ))
+                    .color_range(1, ..)
+                } else {
+                    Text::new(" ")
+                },
+            ];
+            if is_selected {
+                table_cells = table_cells.drain(..).map(|t| t.selected()).collect();
+            }
+            table = table.add_styled_row(table_cells);
+        }
+        print_table_with_coordinates(table, x, y + 3, Some(columns), Some(table_rows));
+    }
+    fn range_to_render(&self, table_rows: usize) -> (usize, usize) {
+        // like the resurrectable sessions, a window around the selected tab once they don't fit
+        let row_count_to_render = table_rows.saturating_sub(1); // 1 for the title
+        if row_count_to_render >= self.tabs.len() {
+            return (0, self.tabs.len());
+        }
+        let first_row_index_to_render = self
+            .selected_index
+            .saturating_sub(row_count_to_render / 2)
+            .min(self.tabs.len() - row_count_to_render);
+        (
+            first_row_index_to_render,
+            first_row_index_to_render + row_count_to_render,
+        )
+    }
+}
@@ -1,9 +1,14 @@
+use std::ops::Range;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use unicode_width::UnicodeWidthChar;
 use unicode_width::UnicodeWidthStr;
 use zellij_tile::prelude::*;
 
-use crate::ui::{PaneUiInfo, SessionUiInfo, TabUiInfo};
+use crate::session_list::ACTIVITY_SPARKLINE_LEN;
+use crate::ui::{PaneUiInfo, SessionOrigin, SessionUiInfo, TabUiInfo};
+use crate::strings::{confirmation_key_indices, Language, Message};
+use crate::new_session_info::FolderDisplay;
 use crate::{ActiveScreen, NewSessionInfo};
 
 #[derive(Debug)]
@@ -60,6 +65,53 @@ impl ListItem {
             colors,
         }
     }
+    pub fn from_pane_path(
+        session_ui_info: &SessionUiInfo,
+        tab_ui_info: &TabUiInfo,
+        pane_ui_info: &PaneUiInfo,
+        colors: Colors,
+    ) -> Self {
+        // a single "session › tab › pane" line, the pane name is the only truncatable span so
+        // that search indices apply to it
+        let path = format!("{} › {} › ", session_ui_info.name, tab_ui_info.name);
+        let short_path = format!("{} › ", session_ui_info.name);
+        let pane_path_line = vec![
+            UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![StringAndLength::new(
+                "   ".to_owned(),
+                3,
+            )])),
+            UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
+                StringAndLength::new(colors.session_and_folder_entry(&path), path.width()),
+                StringAndLength::new(
+                    colors.session_and_folder_entry(&short_path),
+                    short_path.width(),
+                ),
+            ])),
+            UiSpan::TruncatableUiSpan(TruncatableUiSpan::new(
+                pane_ui_info.name.clone(),
+                SpanStyle::Bold,
+            )),
+        ];
+        ListItem {
+            name: pane_ui_info.name.clone(),
+            session_name: Some(pane_path_line),
+            tab_name: None,
+            pane_name: None,
+            colors,
+        }
+    }
+    pub fn with_match_hint(mut self, kind: &str, matched_name: &str) -> Self {
+        let long_hint = format!(" (matches {}: {})", kind, matched_name);
+        let short_hint = format!(" ({}: {})", kind, matched_name);
+        let hint_span = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
+            StringAndLength::new(self.colors.match_hint(&long_hint), long_hint.width()),
+            StringAndLength::new(self.colors.match_hint(&short_hint), short_hint.width()),
+        ]));
+        if let Some(session_name) = self.session_name.as_mut() {
+            session_name.push(hint_span);
+        }
+        self
+    }
     pub fn line_count(&self) -> usize {
         let mut line_count = 0;
         if self.session_name.is_some() {
@@ -85,14 +137,7 @@ impl ListItem {
             let mut remaining_cols = max_cols;
             for span in session_name {
                 span.render(
-                    indices.clone().map(|i| {
-                        (
-                            SpanStyle::ForegroundBold(
-                                self.colors.palette.text_unselected.emphasis_3,
-                            ),
-                            i,
-                        )
-                    }),
+                    indices.clone().map(|i| (self.colors.search_match(), i)),
                     &mut line_to_render,
                     &mut remaining_cols,
                 );
@@ -109,14 +154,7 @@ impl ListItem {
             let mut remaining_cols = max_cols;
             for span in tab_name {
                 span.render(
-                    indices.clone().map(|i| {
-                        (
-                            SpanStyle::ForegroundBold(
-                                self.colors.palette.text_unselected.emphasis_3,
-                            ),
-                            i,
-                        )
-                    }),
+                    indices.clone().map(|i| (self.colors.search_match(), i)),
                     &mut line_to_render,
                     &mut remaining_cols,
                 );
@@ -128,14 +166,7 @@ impl ListItem {
             let mut remaining_cols = max_cols;
             for span in pane_name {
                 span.render(
-                    indices.clone().map(|i| {
-                        (
-                            SpanStyle::ForegroundBold(
-                                self.colors.palette.text_unselected.emphasis_3,
-                            ),
-                            i,
-                        )
-                    }),
+                    indices.clone().map(|i| (self.colors.search_match(), i)),
                     &mut line_to_render,
                     &mut remaining_cols,
                 );
@@ -177,6 +208,8 @@ pub enum SpanStyle {
     Bold,
     Foreground(PaletteColor),
     ForegroundBold(PaletteColor),
+    ForegroundBackgroundBold(PaletteColor, PaletteColor),
+    Dim,
 }
 
 impl SpanStyle {
@@ -184,6 +217,7 @@ impl SpanStyle {
         match self {
             SpanStyle::None => to_style.to_owned(),
             SpanStyle::Bold => format!("\u{1b}[1m{}\u{1b}[22m", to_style),
+            SpanStyle::Dim => format!("\u{1b}[2m{}\u{1b}[22m", to_style),
             SpanStyle::Foreground(color) => match color {
                 PaletteColor::EightBit(byte) => {
                     format!("\u{1b}[38;5;{byte}m{}\u{1b}[39m", to_style)
@@ -200,6 +234,19 @@ impl SpanStyle {
                     format!("\u{1b}[38;2;{};{};{};1m{}\u{1b}[39;22m", r, g, b, to_style)
                 },
             },
+            SpanStyle::ForegroundBackgroundBold(foreground, background) => {
+                let background = match background {
+                    PaletteColor::EightBit(byte) => format!("\u{1b}[48;5;{byte}m"),
+                    PaletteColor::Rgb((r, g, b)) => format!("\u{1b}[48;2;{};{};{}m", r, g, b),
+                };
+                // selected lines re-apply their own background in place of the reset (see
+                // LineToRender::make_selected)
+                format!(
+                    "{}{}\u{1b}[49m",
+                    background,
+                    SpanStyle::ForegroundBold(*foreground).style_string(to_style)
+                )
+            },
         }
     }
 }
@@ -227,8 +274,11 @@ impl TruncatableUiSpan {
         remaining_cols: &mut usize,
     ) {
         let mut rendered = String::new();
-        let truncated = if *remaining_cols >= self.text.width() {
-            self.text.clone()
+        let (truncated, indices) = if *remaining_cols >= self.text.width() {
+            (self.text.clone(), indices)
+        } else if let Some((index_style, indices)) = indices {
+            let (truncated, indices) = window_around_matches(&self.text, &indices, *remaining_cols);
+            (truncated, Some((index_style, indices)))
         } else {
             let mut truncated = String::new();
             for character in self.text.chars() {
@@ -238,7 +288,7 @@ impl TruncatableUiSpan {
                     break;
                 }
             }
-            truncated
+            (truncated, None)
         };
         match indices {
             Some((index_style, indices)) => {
@@ -257,10 +307,71 @@ impl TruncatableUiSpan {
             },
         }
         *remaining_cols = remaining_cols.saturating_sub(truncated.width());
+        line_to_render.width += truncated.width();
         line_to_render.append(&rendered);
     }
 }
 
+fn window_around_matches(text: &str, indices: &[usize], max_width: usize) -> (String, Vec<usize>) {
+    // the part of a too long text that shows its matched characters, with an ellipsis wherever
+    // it was cut - returns the window and the indices shifted into it
+    let characters: Vec<char> = text.chars().collect();
+    let width_of = |range: std::ops::Range<usize>| -> usize {
+        characters[range]
+            .iter()
+            .map(|c| c.width().unwrap_or(0))
+            .sum()
+    };
+    let last_match = indices
+        .iter()
+        .copied()
+        .filter(|i| *i < characters.len())
+        .max()
+        .unwrap_or(0);
+    let fits = |start: usize, end: usize| -> bool {
+        let leading_ellipsis = if start > 0 { 1 } else { 0 };
+        let trailing_ellipsis = if end < characters.len() { 1 } else { 0 };
+        leading_ellipsis + width_of(start..end) + trailing_ellipsis <= max_width
+    };
+    // start as early as possible while still showing the last match, then show as much of what
+    // comes after it as we can
+    let mut start = 0;
+    while start < last_match && !fits(start, last_match + 1) {
+        start += 1;
+    }
+    let mut end = last_match + 1;
+    while end < characters.len() && fits(start, end + 1) {
+        end += 1;
+    }
+    if !fits(start, end) {
+        // too narrow for even a single match, cut from the start like unmatched text
+        let mut truncated = String::new();
+        for character in characters {
+            if truncated.width() + character.width().unwrap_or(0) <= max_width {
+                truncated.push(character);
+            } else {
+                break;
+            }
+        }
+        return (truncated, indices.to_vec());
+    }
+    let leading_ellipsis = if start > 0 { 1 } else { 0 };
+    let mut window = String::new();
+    if start > 0 {
+        window.push('…');
+    }
+    window.extend(&characters[start..end]);
+    if end < characters.len() {
+        window.push('…');
+    }
+    let shifted_indices = indices
+        .iter()
+        .filter(|i| **i >= start && **i < end)
+        .map(|i| i - start + leading_ellipsis)
+        .collect();
+    (window, shifted_indices)
+}
+
 #[derive(Debug, Default)]
 pub struct UiSpanTelescope(Vec<StringAndLength>);
 
@@ -272,6 +383,7 @@ impl UiSpanTelescope {
         for string_and_length in &self.0 {
             if string_and_length.length < *remaining_cols {
                 line_to_render.append(&string_and_length.string);
+                line_to_render.width += string_and_length.length;
                 *remaining_cols -= string_and_length.length;
                 break;
             }
@@ -295,7 +407,13 @@ impl StringAndLength {
 pub struct LineToRender {
     line: String,
     is_selected: bool,
+    is_in_selection_range: bool, // highlighted like the selected line, without the arrows
     truncated_result_count: usize,
+    relative_line_number: Option<usize>,   // shown in the gutter of unselected lines
+    width: usize,                          // of the rendered spans, without the gutter
+    attach_target: Option<String>,         // where Enter lands, shown after the selected line
+    is_grid_row: bool,                     // already rendered cells of the multi column list
+    session_origin: Option<SessionOrigin>, // only for session lines
     colors: Colors,
 }
 
@@ -304,7 +422,13 @@ impl LineToRender {
         LineToRender {
             line: String::default(),
             is_selected: false,
+            is_in_selection_range: false,
             truncated_result_count: 0,
+            relative_line_number: None,
+            width: 0,
+            attach_target: None,
+            is_grid_row: false,
+            session_origin: None,
             colors,
         }
     }
@@ -313,6 +437,7 @@ impl LineToRender {
     }
     pub fn make_selected_as_search(&mut self, add_arrows: bool) {
         self.is_selected = true;
+        self.restore_selected_background();
         let arrows = if add_arrows {
             self.colors.shortcuts(" <↓↑> ")
         } else {
@@ -335,6 +460,7 @@ impl LineToRender {
     }
     pub fn make_selected(&mut self, add_arrows: bool) {
         self.is_selected = true;
+        self.restore_selected_background();
         let arrows = if add_arrows {
             self.colors.shortcuts("<←↓↑→>")
         } else {
@@ -355,7 +481,70 @@ impl LineToRender {
             },
         }
     }
+    pub fn make_in_selection_range(&mut self) {
+        self.is_in_selection_range = true;
+        self.restore_selected_background();
+        let background = self.selected_background();
+        self.line = format!("{background}\u{1b}[K{background}      {}", self.line);
+    }
+    pub fn from_grid_cells(cells: Vec<String>, colors: Colors) -> Self {
+        let mut line_to_render = LineToRender::new(colors);
+        line_to_render.line = cells.concat();
+        line_to_render.is_grid_row = true;
+        line_to_render
+    }
+    pub fn render_as_grid_cell(
+        mut self,
+        is_selected: bool,
+        is_in_selection_range: bool,
+        cell_width: usize,
+    ) -> String {
+        // unlike a selected line, a selected cell must not paint the rest of the row
+        let padding = " ".repeat(cell_width.saturating_sub(self.width + 6));
+        if is_selected {
+            self.restore_selected_background();
+            format!(
+                "{}{}{}\u{1b}[49m{}",
+                self.selected_background(),
+                self.colors.shortcuts("<←↓↑→>"),
+                self.line,
+                padding
+            )
+        } else if is_in_selection_range {
+            self.restore_selected_background();
+            format!(
+                "{}      {}\u{1b}[49m{}",
+                self.selected_background(),
+                self.line,
+                padding
+            )
+        } else {
+            format!("\u{1b}[49m      {}{}", self.line, padding)
+        }
+    }
+    fn selected_background(&self) -> String {
+        match self.colors.palette.list_selected.background {
+            PaletteColor::EightBit(byte) => format!("\u{1b}[48;5;{byte}m"),
+            PaletteColor::Rgb((r, g, b)) => format!("\u{1b}[48;2;{};{};{}m", r, g, b),
+        }
+    }
+    fn restore_selected_background(&mut self) {
+        let background = self.selected_background();
+        self.line = self.line.replace("\u{1b}[49m", &background);
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn set_session_origin(&mut self, session_origin: SessionOrigin) {
+        self.session_origin = Some(session_origin);
+    }
+    pub fn session_origin(&self) -> Option<SessionOrigin> {
+        self.session_origin
+    }
     pub fn render(&self) -> String {
+        if self.is_grid_row {
+            return self.line.clone();
+        }
         let mut line = self.line.clone();
 
         let more = if self.truncated_result_count > 0 {
@@ -367,11 +556,36 @@ impl LineToRender {
 
         line.push_str(&more);
         if self.is_selected {
-            self.line.clone()
+            match &self.attach_target {
+                Some(attach_target) => {
+                    format!("{}  \u{1b}[2m{}\u{1b}[22m", self.line, attach_target)
+                },
+                None => self.line.clone(),
+            }
+        } else if self.is_in_selection_range {
+            line
+        } else if let Some(relative_line_number) = self.relative_line_number {
+            format!(
+                "\u{1b}[49m\u{1b}[2m{:>4}\u{1b}[22m  {}",
+                relative_line_number, line
+            )
         } else {
             format!("\u{1b}[49m      {}", line)
         }
     }
+    pub fn is_selected(&self) -> bool {
+        self.is_selected
+    }
+    pub fn set_relative_line_number(&mut self, relative_line_number: usize) {
+        // the gutter is the indentation of unselected lines, so it must fit in 4 cells
+        self.relative_line_number = Some(relative_line_number.min(9999));
+    }
+    pub fn set_attach_target(&mut self, attach_target: &str, max_cols: usize) {
+        // a hint, so we'd rather drop it than truncate the line
+        if self.width + attach_target.width() + 2 <= max_cols {
+            self.attach_target = Some(attach_target.to_owned());
+        }
+    }
     pub fn add_truncated_results(&mut self, result_count: usize) {
         self.truncated_result_count += result_count;
     }
@@ -402,10 +616,15 @@ pub fn build_session_ui_line(session_ui_info: &SessionUiInfo, colors: Colors, is
             3,
         )]))
     };
-    let session_name_span = UiSpan::TruncatableUiSpan(TruncatableUiSpan::new(
-        session_name.clone(),
-        SpanStyle::ForegroundBold(colors.palette.text_unselected.emphasis_0),
-    ));
+    let session_name_style = match colors.current_session_style {
+        CurrentSessionStyle::Dim if session_ui_info.is_current_session => SpanStyle::Dim,
+        CurrentSessionStyle::Highlight if session_ui_info.is_current_session => {
+            SpanStyle::ForegroundBold(colors.palette.text_unselected.emphasis_2)
+        },
+        _ => SpanStyle::ForegroundBold(colors.palette.text_unselected.emphasis_0),
+    };
+    let session_name_span =
+        UiSpan::TruncatableUiSpan(TruncatableUiSpan::new(session_name.clone(), session_name_style));
     let tab_and_pane_count = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
         StringAndLength::new(
             format!(" ({tab_count_styled} tabs, {total_pane_count_styled} panes)"),
@@ -430,6 +649,19 @@ pub fn build_session_ui_line(session_ui_info: &SessionUiInfo, colors: Colors, is
     ui_spans.push(session_name_span);
     ui_spans.push(tab_and_pane_count);
     ui_spans.push(connected_users_count);
+    if !session_ui_info.tags.is_empty() {
+        let tags: Vec<String> = session_ui_info.tags.iter().map(|t| format!("#{}", t)).collect();
+        let tags = tags.join(" ");
+        let tag_count = format!("#{}", session_ui_info.tags.len());
+        let tags_indication = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
+            StringAndLength::new(format!(" {}", colors.match_hint(&tags)), 1 + tags.width()),
+            StringAndLength::new(
+                format!(" {}", colors.match_hint(&tag_count)),
+                1 + tag_count.width(),
+            ),
+        ]));
+        ui_spans.push(tags_indication);
+    }
     if session_ui_info.is_current_session {
         let current_session_indication = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
             StringAndLength::new(
@@ -441,9 +673,94 @@ pub fn build_session_ui_line(session_ui_info: &SessionUiInfo, colors: Colors, is
         ]));
         ui_spans.push(current_session_indication);
     }
+    if session_ui_info.web_clients_allowed {
+        let web_sharing_indication = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
+            StringAndLength::new(colors.connected_users(" <SHARED>"), 9),
+            StringAndLength::new(colors.connected_users(" <S>"), 4),
+        ]));
+        ui_spans.push(web_sharing_indication);
+    }
+    if session_ui_info.is_hidden {
+        let hidden_session_indication = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
+            StringAndLength::new(colors.shortcuts(" <HIDDEN>"), 9),
+            StringAndLength::new(colors.shortcuts(" <H>"), 4),
+        ]));
+        ui_spans.push(hidden_session_indication);
+    }
+    if session_ui_info.is_remote {
+        let remote_session_indication = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
+            StringAndLength::new(colors.shortcuts(" <REMOTE>"), 9),
+            StringAndLength::new(colors.shortcuts(" <R>"), 4),
+        ]));
+        ui_spans.push(remote_session_indication);
+    }
+    if let Some(activity) = &session_ui_info.activity {
+        let sparkline = activity_sparkline(activity, ACTIVITY_SPARKLINE_LEN);
+        let last_active = activity_sparkline(activity, 1);
+        let activity_indication = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
+            StringAndLength::new(
+                format!(" {}", colors.pane_count(&sparkline)),
+                1 + sparkline.width(),
+            ),
+            StringAndLength::new(
+                format!(" {}", colors.pane_count(&last_active)),
+                1 + last_active.width(),
+            ),
+        ]));
+        ui_spans.push(activity_indication);
+    }
+    if let Some(cwd) = session_ui_info.cwd.as_ref().filter(|_| is_expanded) {
+        let cwd = shorten_home_folder(cwd);
+        let short_cwd = format!("...{}", last_path_component(&cwd));
+        let cwd_indication = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
+            StringAndLength::new(
+                format!(" {}", colors.session_and_folder_entry(&cwd)),
+                1 + cwd.width(),
+            ),
+            StringAndLength::new(
+                format!(" {}", colors.session_and_folder_entry(&short_cwd)),
+                1 + short_cwd.width(),
+            ),
+        ]));
+        ui_spans.push(cwd_indication);
+    }
     ui_spans
 }
 
+fn activity_sparkline(activity: &[usize], width: usize) -> String {
+    // quiet updates are the lowest block, padded on the left so that sessions we only saw for a
+    // few updates line up with the others
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let mut sparkline = " ".repeat(width.saturating_sub(activity.len()));
+    for level in &activity[activity.len().saturating_sub(width)..] {
+        sparkline.push(BLOCKS[(*level).min(BLOCKS.len() - 1)]);
+    }
+    sparkline
+}
+
+// the plugin does not see the host's environment, so the home folder is asked from the shell
+// once (see HOME_FOLDER_REQUEST) - global like the log level so that every path shown can use it,
+// until it's known paths are shown as they are
+static HOME_FOLDER: OnceLock<String> = OnceLock::new();
+
+pub fn set_home_folder(home_folder: &str) {
+    let home_folder = home_folder.trim_end_matches('/');
+    if !home_folder.is_empty() {
+        let _ = HOME_FOLDER.set(home_folder.to_owned());
+    }
+}
+
+pub fn shorten_home_folder(path: &str) -> String {
+    match HOME_FOLDER.get().and_then(|home_folder| path.strip_prefix(home_folder.as_str())) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("~{}", rest),
+        _ => path.to_owned(),
+    }
+}
+
+fn last_path_component(path: &str) -> &str {
+    path.trim_end_matches('/').rsplit('/').next().unwrap_or(path)
+}
+
 pub fn build_tab_ui_line(tab_ui_info: &TabUiInfo, colors: Colors) -> Vec<UiSpan> {
     let mut ui_spans = vec![];
     let tab_name = &tab_ui_info.name;
@@ -530,21 +847,36 @@ pub fn minimize_lines(
     (start_index, anchor_index, end_index, line_count_to_remove)
 }
 
-pub fn render_prompt(search_term: &str, cursor_pos: usize, is_expanded: bool, colors: Colors, x: usize, y: usize) {
-    // Show different prompt based on expansion state
-    let prompt_text = if is_expanded {
-        "Search (expanded):"
-    } else {
-        "Search:"
-    };
+pub fn render_prompt(
+    search_term: &str,
+    cursor_pos: usize,
+    prompt_text: &str,
+    colors: Colors,
+    x: usize,
+    y: usize,
+    max_cols: usize,
+) {
     let prompt = colors.session_and_folder_entry(prompt_text);
     
     // Build search term with cursor
     let mut display_text = String::new();
     let chars: Vec<char> = search_term.chars().collect();
+
+    // if the search term does not fit, only show the part around the cursor
+    let room_for_search_term = max_cols.saturating_sub(prompt_text.width() + 2); // space and cursor
+    let (first_visible_char, last_visible_char) = if chars.len() > room_for_search_term {
+        let first_visible_char = (cursor_pos + 1).saturating_sub(room_for_search_term);
+        (first_visible_char, first_visible_char + room_for_search_term)
+    } else {
+        (0, chars.len())
+    };
     
     for (i, &ch) in chars.iter().enumerate() {
-        if i == cursor_pos {
+        if i < first_visible_char || i >= last_visible_char {
+            continue;
+        } else if i == first_visible_char && first_visible_char > 0 {
+            display_text.push('…');
+        } else if i == cursor_pos {
             // Show cursor as inverted character or block
             display_text.push_str(&colors.bold(&format!("\u{1b}[7m{}\u{1b}[27m", ch)));
         } else {
@@ -566,40 +898,181 @@ pub fn render_prompt(search_term: &str, cursor_pos: usize, is_expanded: bool, co
     );
 }
 
+pub fn result_position_width(position: Option<usize>, count: usize) -> usize {
+    format!("{}/{}", position.map(|p| p + 1).unwrap_or(0), count).width()
+}
+
+pub fn render_result_position(
+    position: Option<usize>,
+    count: usize,
+    colors: Colors,
+    x: usize,
+    y: usize,
+    max_cols: usize,
+) {
+    // right aligned on the prompt line, eg. 3/12
+    let result_position = format!("{}/{}", position.map(|p| p + 1).unwrap_or(0), count);
+    let styled_result_position = if count == 0 {
+        colors.exit_code_error(&result_position)
+    } else {
+        colors.session_and_folder_entry(&result_position)
+    };
+    print!(
+        "\u{1b}[{};{}H\u{1b}[m{}",
+        y + 1,
+        x + max_cols.saturating_sub(result_position.width()),
+        styled_result_position
+    );
+}
+
+pub fn render_scrollbar(
+    first_visible_line: usize,
+    visible_line_count: usize,
+    total_line_count: usize,
+    colors: Colors,
+    x: usize,
+    y: usize,
+    rows: usize,
+) {
+    // the thumb is proportional to the part of the list that is visible
+    if total_line_count == 0 || rows == 0 {
+        return;
+    }
+    let thumb_size = std::cmp::max(1, rows * visible_line_count / total_line_count).min(rows);
+    let thumb_start =
+        std::cmp::min(rows * first_visible_line / total_line_count, rows - thumb_size);
+    for row in 0..rows {
+        let cell = if row >= thumb_start && row < thumb_start + thumb_size {
+            colors.shortcuts("┃")
+        } else {
+            "\u{1b}[2m│\u{1b}[22m".to_owned()
+        };
+        print!("\u{1b}[{};{}H\u{1b}[m{}", y + row, x, cell);
+    }
+}
+
+pub fn prompt_width(search_term: &str, prompt_text: &str) -> usize {
+    // prompt, space, search term and the trailing cursor
+    prompt_text.width() + 1 + search_term.width() + 1
+}
+
+pub fn render_clear_search_hint(
+    language: Language,
+    search_term: &str,
+    prompt_text: &str,
+    colors: Colors,
+    x: usize,
+    y: usize,
+    max_cols: usize,
+) {
+    if search_term.is_empty() {
+        return;
+    }
+    let hint_x = prompt_width(search_term, prompt_text) + 1;
+    let clear = language.text(Message::Clear);
+    if hint_x + clear.width() + 10 > max_cols {
+        return;
+    }
+    print!(
+        "\u{1b}[{};{}H\u{1b}[m{} - {}",
+        y + 1,
+        x + hint_x,
+        colors.shortcuts("<Esc>"),
+        colors.bold(clear)
+    );
+}
+
+pub fn render_compact_matches(
+    entries: &[String],
+    selected: Option<usize>,
+    colors: Colors,
+    x: usize,
+    y: usize,
+    max_cols: usize,
+) {
+    let more_indication_width = 2;
+    let available_cols = max_cols.saturating_sub(more_indication_width * 2);
+    if entries.is_empty() || available_cols == 0 {
+        return;
+    }
+    let entry_widths: Vec<usize> = entries.iter().map(|e| e.width() + 2).collect(); // padding
+    let selected_index = selected.unwrap_or(0).min(entries.len().saturating_sub(1));
+    // scroll horizontally so that the selected entry is always visible
+    let mut first_visible = 0;
+    while first_visible < selected_index
+        && entry_widths[first_visible..=selected_index].iter().sum::<usize>() > available_cols
+    {
+        first_visible += 1;
+    }
+    let mut line = String::new();
+    let mut used_cols = 0;
+    let mut last_visible = first_visible;
+    for (i, entry) in entries.iter().enumerate().skip(first_visible) {
+        let mut entry = entry.clone();
+        if used_cols + entry_widths[i] > available_cols {
+            if i != first_visible {
+                break;
+            }
+            entry = truncate_to_width(&entry, available_cols.saturating_sub(2));
+        }
+        if selected == Some(i) {
+            line.push_str(&colors.bold(&format!("\u{1b}[7m {} \u{1b}[27m", entry)));
+        } else {
+            line.push_str(&format!(" {} ", entry));
+        }
+        used_cols += entry.width() + 2;
+        last_visible = i;
+    }
+    let more_before = if first_visible > 0 {
+        colors.shortcuts("< ")
+    } else {
+        "  ".to_owned()
+    };
+    let more_after = if last_visible + 1 < entries.len() {
+        colors.shortcuts(" >")
+    } else {
+        String::new()
+    };
+    print!(
+        "\u{1b}[{};{}H\u{1b}[m{}{}{}",
+        y + 1,
+        x + 1,
+        more_before,
+        line,
+        more_after
+    );
+}
+
+fn truncate_to_width(text: &str, max_width: usize) -> String {
+    let mut truncated = String::new();
+    for character in text.chars() {
+        if truncated.width() + character.width().unwrap_or(0) <= max_width {
+            truncated.push(character);
+        } else {
+            break;
+        }
+    }
+    truncated
+}
+
 pub fn render_screen_toggle(
     active_screen: ActiveScreen,
+    screens: &[ActiveScreen],
+    screen_labels: &ScreenLabels,
     x: usize,
     y: usize,
     max_cols: usize,
     background: &PaletteColor,
 ) {
     let key_indication_text = "<TAB>";
-    let (new_session_text, running_sessions_text, exited_sessions_text) = if max_cols > 66 {
-        ("New Session", "Attach to Session", "Resurrect Session")
-    } else {
-        ("New", "Attach", "Resurrect")
-    };
     let key_indication_len = key_indication_text.chars().count() + 1;
-    let first_ribbon_length = new_session_text.chars().count() + 4;
-    let second_ribbon_length = running_sessions_text.chars().count() + 4;
+    // the long labels if all the ribbons (each padded by 4) fit next to the key indication
+    let wide_width = screens.iter().fold(key_indication_len, |acc, screen| {
+        acc + screen_labels.label(*screen, true).width() + 4
+    });
+    let is_wide = wide_width <= max_cols;
     let key_indication_x = x;
     let first_ribbon_x = key_indication_x + key_indication_len;
-    let second_ribbon_x = first_ribbon_x + first_ribbon_length;
-    let third_ribbon_x = second_ribbon_x + second_ribbon_length;
-    let mut new_session_text = Text::new(new_session_text);
-    let mut running_sessions_text = Text::new(running_sessions_text);
-    let mut exited_sessions_text = Text::new(exited_sessions_text);
-    match active_screen {
-        ActiveScreen::New => {
-            new_session_text = new_session_text.selected();
-        },
-        ActiveScreen::Attach => {
-            running_sessions_text = running_sessions_text.selected();
-        },
-        ActiveScreen::Resurrect => {
-            exited_sessions_text = exited_sessions_text.selected();
-        },
-    }
     let bg_color = match background {
         PaletteColor::Rgb((r, g, b)) => format!("\u{1b}[48;2;{};{};{}m\u{1b}[0K", r, g, b),
         PaletteColor::EightBit(color) => format!("\u{1b}[48;5;{}m\u{1b}[0K", color),
@@ -612,12 +1085,49 @@ pub fn render_screen_toggle(
         None,
     );
     println!("\u{1b}[{};{}H{}", y + 1, first_ribbon_x, bg_color);
-    print_ribbon_with_coordinates(new_session_text, first_ribbon_x, y, None, None);
-    print_ribbon_with_coordinates(running_sessions_text, second_ribbon_x, y, None, None);
-    print_ribbon_with_coordinates(exited_sessions_text, third_ribbon_x, y, None, None);
+    let mut ribbon_x = first_ribbon_x;
+    for screen in screens {
+        let label = screen_labels.label(*screen, is_wide);
+        let mut ribbon_text = Text::new(label);
+        if *screen == active_screen {
+            ribbon_text = ribbon_text.selected();
+        }
+        print_ribbon_with_coordinates(ribbon_text, ribbon_x, y, None, None);
+        ribbon_x += label.width() + 4;
+    }
+}
+
+// labels (or icons) from the config replace both the long and the short built in label
+#[derive(Debug, Default, Clone)]
+pub struct ScreenLabels {
+    pub new: Option<String>,
+    pub attach: Option<String>,
+    pub resurrect: Option<String>,
+}
+
+impl ScreenLabels {
+    pub fn label(&self, screen: ActiveScreen, is_wide: bool) -> &str {
+        let configured_label = match screen {
+            ActiveScreen::New => &self.new,
+            ActiveScreen::Attach => &self.attach,
+            ActiveScreen::Resurrect => &self.resurrect,
+        };
+        if let Some(configured_label) = configured_label {
+            return configured_label;
+        }
+        match (screen, is_wide) {
+            (ActiveScreen::New, true) => "New Session",
+            (ActiveScreen::New, false) => "New",
+            (ActiveScreen::Attach, true) => "Attach to Session",
+            (ActiveScreen::Attach, false) => "Attach",
+            (ActiveScreen::Resurrect, true) => "Resurrect Session",
+            (ActiveScreen::Resurrect, false) => "Resurrect",
+        }
+    }
 }
 
 fn render_new_session_folder_prompt(
+    language: Language,
     new_session_info: &NewSessionInfo,
     colors: Colors,
     x: usize,
@@ -626,16 +1136,25 @@ fn render_new_session_folder_prompt(
 ) {
     match new_session_info.new_session_folder.as_ref() {
         Some(new_session_folder) => {
-            let folder_prompt = "New session folder:";
-            let short_folder_prompt = "Folder:";
-            let new_session_path = new_session_folder.clone();
-            let new_session_folder = new_session_folder.display().to_string();
+            let folder_prompt = language.text(Message::FolderPrompt);
+            let short_folder_prompt = language.text(Message::FolderPromptShort);
+            let new_session_folder = match new_session_info.folder_display() {
+                FolderDisplay::Absolute => new_session_folder.display().to_string(),
+                FolderDisplay::HomeRelative => {
+                    shorten_home_folder(&new_session_folder.display().to_string())
+                },
+                FolderDisplay::Basename => new_session_folder
+                    .file_name()
+                    .map(|f| f.to_string_lossy().to_string())
+                    .unwrap_or_else(|| new_session_folder.display().to_string()),
+            };
+            let new_session_path = PathBuf::from(&new_session_folder);
             let change_folder_shortcut_text = "<Ctrl f>";
             let change_folder_shortcut = colors.shortcuts(&change_folder_shortcut_text);
-            let to_change = "to change";
+            let to_change = language.text(Message::ToChange);
             let reset_folder_shortcut_text = "<Ctrl c>";
             let reset_folder_shortcut = colors.shortcuts(reset_folder_shortcut_text);
-            let to_reset = "to reset";
+            let to_reset = language.text(Message::ToReset);
             if max_cols
                 >= folder_prompt.width()
                     + new_session_folder.width()
@@ -710,11 +1229,11 @@ fn render_new_session_folder_prompt(
             }
         },
         None => {
-            let folder_prompt = "New session folder:";
-            let short_folder_prompt = "Folder:";
+            let folder_prompt = language.text(Message::FolderPrompt);
+            let short_folder_prompt = language.text(Message::FolderPromptShort);
             let change_folder_shortcut_text = "<Ctrl f>";
             let change_folder_shortcut = colors.shortcuts(change_folder_shortcut_text);
-            let to_set = "to set";
+            let to_set = language.text(Message::ToSet);
 
             if max_cols
                 >= folder_prompt.width() + change_folder_shortcut_text.width() + to_set.width() + 4
@@ -752,6 +1271,7 @@ fn render_new_session_folder_prompt(
 }
 
 pub fn render_new_session_block(
+    language: Language,
     new_session_info: &NewSessionInfo,
     colors: Colors,
     max_rows_of_new_session_block: usize,
@@ -761,8 +1281,8 @@ pub fn render_new_session_block(
 ) {
     let enter = colors.shortcuts("<ENTER>");
     if new_session_info.entering_new_session_name() {
-        let prompt = "New session name:";
-        let long_instruction = "when done, blank for random";
+        let prompt = language.text(Message::NewSessionNamePrompt);
+        let long_instruction = language.text(Message::NewSessionNameInstruction);
         let new_session_name = new_session_info.name();
         if max_cols_of_new_session_block
             > prompt.width() + long_instruction.width() + new_session_name.width() + 15
@@ -803,41 +1323,62 @@ pub fn render_new_session_block(
         }
     } else if new_session_info.entering_layout_search_term() {
         let new_session_name = if new_session_info.name().is_empty() {
-            "<RANDOM>"
+            language.text(Message::RandomSessionName)
         } else {
             new_session_info.name()
         };
-        let prompt = "New session name:";
-        let long_instruction = "to correct";
+        let prompt = language.text(Message::NewSessionNamePrompt);
+        let long_instruction = language.text(Message::ToCorrect);
         let esc = colors.shortcuts("<ESC>");
         if max_cols_of_new_session_block
             > prompt.width() + long_instruction.width() + new_session_name.width() + 15
         {
             println!(
-                "\u{1b}[m{}{}: {} ({} to correct)",
+                "\u{1b}[m{}{} {} ({} {})",
                 format!("\u{1b}[{};{}H", y + 1, x + 1),
                 colors.session_name_prompt(prompt),
                 colors.session_and_folder_entry(new_session_name),
                 esc,
+                long_instruction,
             );
         } else {
             println!(
-                "\u{1b}[m{}{}: {} {}",
+                "\u{1b}[m{}{} {} {}",
                 format!("\u{1b}[{};{}H", y + 1, x + 1),
-                colors.session_name_prompt("New session name"),
+                colors.session_name_prompt(prompt),
                 colors.session_and_folder_entry(new_session_name),
                 esc,
             );
         }
         render_layout_selection_list(
+            language,
             new_session_info,
-            max_rows_of_new_session_block.saturating_sub(8),
+            language.text(Message::NewSessionLayoutPrompt),
+            // leave room for the env variables and the initial command
+            max_rows_of_new_session_block.saturating_sub(10),
             max_cols_of_new_session_block,
             x,
             y + 1,
         );
     }
+    render_new_session_initial_command_prompt(
+        language,
+        new_session_info,
+        colors,
+        x,
+        (y + max_rows_of_new_session_block).saturating_sub(5),
+        max_cols_of_new_session_block,
+    );
+    render_new_session_env_variables_prompt(
+        language,
+        new_session_info,
+        colors,
+        x,
+        (y + max_rows_of_new_session_block).saturating_sub(4),
+        max_cols_of_new_session_block,
+    );
     render_new_session_folder_prompt(
+        language,
         new_session_info,
         colors,
         x,
@@ -846,8 +1387,138 @@ pub fn render_new_session_block(
     );
 }
 
+fn render_new_session_env_variables_prompt(
+    language: Language,
+    new_session_info: &NewSessionInfo,
+    colors: Colors,
+    x: usize,
+    y: usize,
+    max_cols: usize,
+) {
+    let env_variables = new_session_info.env_variables_text();
+    let is_editing = new_session_info.entering_env_variables();
+    if env_variables.is_empty() && !is_editing {
+        let env_hint = language.format(Message::SetEnvVariablesHint, &["<Ctrl e>"]);
+        if max_cols > env_hint.width() {
+            print!(
+                "\u{1b}[m\u{1b}[{};{}H{}",
+                y + 1,
+                x + 1,
+                language.format(
+                    Message::SetEnvVariablesHint,
+                    &[&colors.shortcuts("<Ctrl e>")]
+                ),
+            );
+        }
+        return;
+    }
+    let prompt = language.text(Message::EnvVariablesPrompt);
+    let cursor = if is_editing { "_" } else { "" };
+    let instruction = if is_editing {
+        language.text(Message::EnvVariablesInstruction)
+    } else {
+        ""
+    };
+    let space_for_env_variables =
+        max_cols.saturating_sub(prompt.width() + instruction.width() + 4);
+    let env_variables = if env_variables.width() > space_for_env_variables {
+        // keep the end in view, that's where the user is typing
+        let mut truncated = String::new();
+        for character in env_variables.chars().rev() {
+            if truncated.width() + character.width().unwrap_or(0) + 3 <= space_for_env_variables {
+                truncated.push(character);
+            } else {
+                break;
+            }
+        }
+        format!("...{}", truncated.chars().rev().collect::<String>())
+    } else {
+        env_variables.to_owned()
+    };
+    let is_valid = new_session_info.env_variables().is_ok();
+    print!(
+        "\u{1b}[m\u{1b}[{};{}H{} {}{} {}",
+        y + 1,
+        x + 1,
+        colors.session_name_prompt(prompt),
+        if is_valid {
+            colors.session_and_folder_entry(&env_variables)
+        } else {
+            colors.exit_code_error(&env_variables)
+        },
+        colors.bold(cursor),
+        instruction,
+    );
+}
+
+fn render_new_session_initial_command_prompt(
+    language: Language,
+    new_session_info: &NewSessionInfo,
+    colors: Colors,
+    x: usize,
+    y: usize,
+    max_cols: usize,
+) {
+    let initial_command = new_session_info.initial_command_text();
+    let is_editing = new_session_info.entering_initial_command();
+    if initial_command.is_empty() && !is_editing {
+        let command_hint = language.format(Message::RunInitialCommandHint, &["<Ctrl r>"]);
+        if max_cols > command_hint.width() {
+            print!(
+                "\u{1b}[m\u{1b}[{};{}H{}",
+                y + 1,
+                x + 1,
+                language.format(
+                    Message::RunInitialCommandHint,
+                    &[&colors.shortcuts("<Ctrl r>")]
+                ),
+            );
+        }
+        return;
+    }
+    let prompt = language.text(Message::InitialCommandPrompt);
+    let cursor = if is_editing { "_" } else { "" };
+    let instruction = if is_editing {
+        language.text(Message::InitialCommandInstruction)
+    } else {
+        ""
+    };
+    let space_for_initial_command =
+        max_cols.saturating_sub(prompt.width() + instruction.width() + 4);
+    let initial_command = if initial_command.width() > space_for_initial_command {
+        let mut truncated = String::new();
+        for character in initial_command.chars().rev() {
+            if truncated.width() + character.width().unwrap_or(0) + 3 <= space_for_initial_command
+            {
+                truncated.push(character);
+            } else {
+                break;
+            }
+        }
+        format!("...{}", truncated.chars().rev().collect::<String>())
+    } else {
+        initial_command.to_owned()
+    };
+    let is_valid = new_session_info.initial_command().is_ok();
+    print!(
+        "\u{1b}[m\u{1b}[{};{}H{} {}{} {}",
+        y + 1,
+        x + 1,
+        colors.session_name_prompt(prompt),
+        if is_valid {
+            colors.session_and_folder_entry(&initial_command)
+        } else {
+            colors.exit_code_error(&initial_command)
+        },
+        colors.bold(cursor),
+        instruction,
+    );
+}
+
 pub fn render_layout_selection_list(
+    language: Language,
     new_session_info: &NewSessionInfo,
+    prompt: &str,
     max_rows_of_new_session_block: usize,
     max_cols_of_new_session_block: usize,
     x: usize,
@@ -855,39 +1526,57 @@ pub fn render_layout_selection_list(
 ) {
     let layout_search_term = new_session_info.layout_search_term();
     let search_term_len = layout_search_term.width();
-    let layout_indication_line = if max_cols_of_new_session_block > 73 + search_term_len {
-        Text::new(format!(
-            "New session layout: {}_ (Search and select from list, <ENTER> when done)",
-            layout_search_term
-        ))
-        .color_range(2, ..20 + search_term_len)
-        .color_range(3, 20..20 + search_term_len)
-        .color_range(3, 52 + search_term_len..59 + search_term_len)
-    } else {
-        Text::new(format!(
-            "New session layout: {}_ <ENTER>",
-            layout_search_term
-        ))
-        .color_range(2, ..20 + search_term_len)
-        .color_range(3, 20..20 + search_term_len)
-        .color_range(3, 22 + search_term_len..)
-    };
+    let prompt_len = prompt.width() + 2; // and the ": " after it
+    let (instruction, enter_ranges) =
+        language.format_with_ranges(Message::LayoutSearchInstruction, &["<ENTER>"]);
+    let instruction_x = prompt_len + search_term_len + 2; // after the cursor and a space
+    let layout_indication_line =
+        if max_cols_of_new_session_block > instruction_x + instruction.width() + 2 {
+            let mut text = Text::new(format!(
+                "{}: {}_ {}",
+                prompt, layout_search_term, instruction
+            ))
+            .color_range(2, ..prompt_len + search_term_len)
+            .color_range(3, prompt_len..prompt_len + search_term_len);
+            for range in enter_ranges {
+                text = text.color_range(3, range.start + instruction_x..range.end + instruction_x);
+            }
+            text
+        } else {
+            Text::new(format!("{}: {}_ <ENTER>", prompt, layout_search_term))
+                .color_range(2, ..prompt_len + search_term_len)
+                .color_range(3, prompt_len..prompt_len + search_term_len)
+                .color_range(3, prompt_len + 2 + search_term_len..)
+        };
     print_text_with_coordinates(layout_indication_line, x, y + 1, None, None);
     println!();
     let mut table = Table::new();
+    let mut widest_layout_cell = 0;
     for (i, (layout_info, indices, is_selected)) in new_session_info
         .layouts_to_render(max_rows_of_new_session_block)
         .into_iter()
         .enumerate()
     {
-        let layout_name = layout_info.name();
+        let layout_name = new_session_info.layout_name(&layout_info);
         let layout_name_len = layout_name.width();
-        let is_builtin = layout_info.is_builtin();
+        // built-in, or the kind of project in the new session folder it fits
+        let annotation = if layout_info.is_builtin() {
+            Some(language.text(Message::BuiltIn))
+        } else {
+            new_session_info.layout_project_kind(&layout_info)
+        };
+        widest_layout_cell = std::cmp::max(
+            widest_layout_cell,
+            match annotation {
+                Some(annotation) => layout_name_len + annotation.width() + 3,
+                None => layout_name_len,
+            },
+        );
         if i > max_rows_of_new_session_block.saturating_sub(1) {
             break;
         } else {
-            let mut layout_cell = if is_builtin {
-                Text::new(format!("{} (built-in)", layout_name))
+            let mut layout_cell = if let Some(annotation) = annotation {
+                Text::new(format!("{} ({})", layout_name, annotation))
                     .color_range(1, 0..layout_name_len)
                     .color_range(0, layout_name_len + 1..)
                     .color_indices(3, indices)
@@ -909,11 +1598,195 @@ pub fn render_layout_selection_list(
     }
     let table_y = y + 3;
     print_table_with_coordinates(table, x, table_y, None, None);
+    render_layout_description(
+        new_session_info,
+        max_cols_of_new_session_block,
+        x + widest_layout_cell + 8, // arrow column and padding
+        table_y,
+    );
+}
+
+fn render_layout_description(
+    new_session_info: &NewSessionInfo,
+    max_cols: usize,
+    x: usize,
+    y: usize,
+) {
+    let description = match new_session_info.selected_layout_description() {
+        Some(description) => description,
+        None => return,
+    };
+    let available_width = max_cols.saturating_sub(x);
+    if available_width < 20 {
+        return;
+    }
+    let description = truncate_to_width(&description, available_width);
+    print_text_with_coordinates(Text::new(description).color_range(0, ..), x, y, None, None);
+}
+
+pub fn render_frame(colors: Colors, rows: usize, cols: usize) {
+    // around the whole plugin pane, drawn last because some lines clear to their end
+    if rows < 2 || cols < 2 {
+        return;
+    }
+    let horizontal_line = "─".repeat(cols - 2);
+    print!(
+        "\u{1b}[m\u{1b}[1;1H{}",
+        colors.frame(&format!("┌{}┐", horizontal_line))
+    );
+    for row in 2..rows {
+        print!("\u{1b}[m\u{1b}[{};1H{}", row, colors.frame("│"));
+        print!("\u{1b}[m\u{1b}[{};{}H{}", row, cols, colors.frame("│"));
+    }
+    print!(
+        "\u{1b}[m\u{1b}[{};1H{}",
+        rows,
+        colors.frame(&format!("└{}┘", horizontal_line))
+    );
+}
+
+pub fn render_flash_line(max_cols: usize, x: usize, y: usize) {
+    print!("\u{1b}[m\u{1b}[{y};{x}H\u{1b}[7m{}\u{1b}[m", " ".repeat(max_cols));
+}
+
+pub const SESSION_DETAILS_ROWS: usize = 2;
+
+pub fn render_session_details(
+    session_ui_info: Option<&SessionUiInfo>,
+    colors: Colors,
+    x: usize,
+    y: usize,
+    max_cols: usize,
+) {
+    // SessionInfo has no creation time, so unlike resurrectable sessions we cannot show an age
+    let session_ui_info = match session_ui_info {
+        Some(session_ui_info) => session_ui_info,
+        None => return,
+    };
+    let pane_count = session_ui_info
+        .tabs
+        .iter()
+        .fold(0, |acc, tab| acc + tab.panes.len());
+    let cwd = session_ui_info
+        .cwd
+        .as_deref()
+        .unwrap_or(if session_ui_info.is_remote { "remote" } else { "…" });
+    let name = truncate_to_width(&session_ui_info.name, max_cols);
+    let counts = format!(
+        "{} tabs, {} panes, {} clients",
+        session_ui_info.tabs.len(),
+        pane_count,
+        session_ui_info.connected_users
+    );
+    let cwd = truncate_to_width(cwd, max_cols.saturating_sub(counts.width() + 3));
+    print!(
+        "\u{1b}[m\u{1b}[{};{}H{}",
+        y + 1,
+        x + 1,
+        colors.session_name_prompt(&name)
+    );
+    print!(
+        "\u{1b}[m\u{1b}[{};{}H{}{}",
+        y + 2,
+        x + 1,
+        colors.session_and_folder_entry(&cwd),
+        truncate_to_width(&format!(" | {}", counts), max_cols.saturating_sub(cwd.width())),
+    );
+}
+
+pub fn render_action_log(entries: &[String], colors: Colors, x: usize, y: usize, width: usize) {
+    // an overlay, so every line is padded to the full width to hide what's underneath
+    let title = truncate_to_width(" Recent actions (<Alt l> to hide)", width);
+    print!(
+        "\u{1b}[m\u{1b}[{};{}H\u{1b}[7m{}{}\u{1b}[m",
+        y + 1,
+        x + 1,
+        colors.bold(&title),
+        " ".repeat(width.saturating_sub(title.width()))
+    );
+    for (i, entry) in entries.iter().enumerate() {
+        let entry = truncate_to_width(&format!(" {}", entry), width);
+        print!(
+            "\u{1b}[m\u{1b}[{};{}H{}{}",
+            y + i + 2,
+            x + 1,
+            colors.session_and_folder_entry(&entry),
+            " ".repeat(width.saturating_sub(entry.width()))
+        );
+    }
+}
+
+pub fn render_error(
+    language: Language,
+    error_text: &str,
+    rows: usize,
+    columns: usize,
+    x: usize,
+    y: usize,
+) {
+    let error_text = language.format(Message::Error, &[error_text]);
+    let mut lines = wrap_to_width(&error_text, columns);
+    if lines.len() <= 1 {
+        print_text_with_coordinates(
+            Text::new(error_text).color_range(3, ..),
+            x,
+            y + rows,
+            Some(columns),
+            None,
+        );
+        return;
+    }
+    // too long for the footer line, so it's shown as a block in the middle of what we have (rows
+    // being the footer line, 0 based), padded so that it hides whatever is drawn below it
+    lines.truncate(rows + 1);
+    let first_line_y = y + (rows + 1 - lines.len()) / 2;
+    for (i, line) in lines.iter().enumerate() {
+        let padding = " ".repeat(columns.saturating_sub(line.width()));
+        print_text_with_coordinates(
+            Text::new(format!("{}{}", line, padding)).color_range(3, ..),
+            x,
+            first_line_y + i,
+            Some(columns),
+            None,
+        );
+    }
 }
 
-pub fn render_error(error_text: &str, rows: usize, columns: usize, x: usize, y: usize) {
+fn wrap_to_width(text: &str, max_width: usize) -> Vec<String> {
+    // breaks between words where it can, words that are wider than a line (eg. long paths) are
+    // broken wherever the line ends
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let separator = if line.is_empty() { "" } else { " " };
+        if line.width() + separator.len() + word.width() <= max_width {
+            line.push_str(separator);
+            line.push_str(word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        let mut rest = word;
+        while rest.width() > max_width && max_width > 0 {
+            let part = truncate_to_width(rest, max_width);
+            if part.is_empty() {
+                break; // a single character wider than the line
+            }
+            rest = &rest[part.len()..];
+            lines.push(part);
+        }
+        line.push_str(rest);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+pub fn render_notice(notice_text: &str, rows: usize, columns: usize, x: usize, y: usize) {
     print_text_with_coordinates(
-        Text::new(format!("Error: {}", error_text)).color_range(3, ..),
+        Text::new(notice_text).color_range(2, ..),
         x,
         y + rows,
         Some(columns),
@@ -921,7 +1794,84 @@ pub fn render_error(error_text: &str, rows: usize, columns: usize, x: usize, y:
     );
 }
 
+pub fn render_permissions_denied(language: Language, can_hide: bool, rows: usize, columns: usize) {
+    // the host only tells us that the request was denied, not which of the permissions
+    let mut lines = vec![
+        Text::new(language.text(Message::PermissionsDenied)).color_range(3, ..),
+        Text::new(language.text(Message::PermissionsDeniedDisabled)),
+        Text::new(language.text(Message::PermissionsDeniedHowToGrant)),
+    ];
+    if can_hide {
+        lines.push(Text::new(language.text(Message::Hide)).color_range(3, ..5));
+    }
+    let first_line_y = (rows / 2).saturating_sub(lines.len() / 2);
+    for (i, line) in lines.into_iter().enumerate() {
+        print_text_with_coordinates(line, 0, first_line_y + i * 2, Some(columns), None);
+    }
+}
+
+pub fn render_confirmation(
+    title: &str,
+    highlighted_ranges: Vec<Range<usize>>, // in characters, of the parts of the title to emphasize
+    detail: &str,                          // the question, with the (y/n) keys in it
+    rows: usize,
+    columns: usize,
+    x: usize,
+    y: usize,
+) {
+    if rows == 0 || columns == 0 {
+        return;
+    }
+    let title_y_location = y + (rows / 2).saturating_sub(1);
+    let detail_y_location = y + (rows / 2) + 1;
+    let title_x_location = x + columns.saturating_sub(title.chars().count()) / 2;
+    let detail_x_location = x + columns.saturating_sub(detail.chars().count()) / 2;
+    print_text_with_coordinates(
+        highlighted_ranges
+            .into_iter()
+            .fold(Text::new(title), |text, range| text.color_range(0, range)),
+        title_x_location,
+        title_y_location,
+        None,
+        None,
+    );
+    print_text_with_coordinates(
+        Text::new(detail).color_indices(2, confirmation_key_indices(detail)),
+        detail_x_location,
+        detail_y_location,
+        None,
+        None,
+    );
+}
+
+pub fn render_command_line(command_line: &str, columns: usize, x: usize, y: usize) {
+    // like the command line of vim, so it's told apart from the search prompt by the leading :
+    let command_line_text = format!(":{}_", command_line);
+    print_text_with_coordinates(
+        Text::new(command_line_text).color_range(3, ..1),
+        x,
+        y,
+        Some(columns),
+        None,
+    );
+    let help_text = "kill <session>, new <name>, rename <name> (<ENTER> to run, <Esc> to cancel)";
+    print_text_with_coordinates(
+        Text::new(help_text)
+            .color_range(2, ..4)
+            .color_range(2, 16..19)
+            .color_range(2, 28..34)
+            .color_range(3, 43..50)
+            .color_range(3, 59..64),
+        x,
+        y + 2,
+        Some(columns),
+        None,
+    );
+}
+
 pub fn render_renaming_session_screen(
+    language: Language,
+    prompt: &str,
     new_session_name: &str,
     rows: usize,
     columns: usize,
@@ -931,18 +1881,21 @@ pub fn render_renaming_session_screen(
     if rows == 0 || columns == 0 {
         return;
     }
-    let text = Text::new(format!(
-        "New name for current session: {}_ (<ENTER> when done)",
-        new_session_name
-    ))
-    .color_range(2, ..29)
-    .color_range(
-        3,
-        33 + new_session_name.width()..40 + new_session_name.width(),
-    );
+    let prompt_len = prompt.width();
+    let (instruction, enter_ranges) = language.format_with_ranges(Message::WhenDone, &["<ENTER>"]);
+    // after the ": ", the cursor and a space
+    let instruction_x = prompt_len + new_session_name.width() + 4;
+    let mut text = Text::new(format!("{}: {}_ {}", prompt, new_session_name, instruction))
+        .color_range(2, ..prompt_len + 1);
+    for range in enter_ranges {
+        text = text.color_range(3, range.start + instruction_x..range.end + instruction_x);
+    }
     print_text_with_coordinates(text, x, y, None, None);
     if new_session_name.contains('/') {
-        let error_text = "Error: session name cannot contain '/'";
+        let error_text = language.format(
+            Message::Error,
+            &[language.text(Message::SessionNameHasSlash)],
+        );
         print_text_with_coordinates(
             Text::new(error_text).color_range(3, ..),
             x,
@@ -953,58 +1906,181 @@ pub fn render_renaming_session_screen(
     }
 }
 
-pub fn render_controls_line(
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ControlsVerbosity {
+    #[default]
+    Full, // eg. <Ctrl r> - Rename, falls back to minimal when it doesn't fit
+    Minimal, // eg. <Ctrl r>/<Del>
+    None,
+}
+
+impl ControlsVerbosity {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "full" => Some(ControlsVerbosity::Full),
+            "minimal" => Some(ControlsVerbosity::Minimal),
+            "none" => Some(ControlsVerbosity::None),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ControlsConfig {
+    pub verbosity: ControlsVerbosity,
+    pub hints: Option<Vec<String>>, // hint ids in the order to show them, all of them if None
+}
+
+impl ControlsConfig {
+    fn chosen_hints<'a>(
+        &self,
+        available_hints: Vec<(&'a str, &'a str, Message)>,
+    ) -> Vec<(&'a str, &'a str, Message)> {
+        match &self.hints {
+            Some(hint_ids) => hint_ids
+                .iter()
+                .filter_map(|id| available_hints.iter().find(|h| h.0 == id).copied())
+                .collect(),
+            None => available_hints,
+        }
+    }
+}
+
+// whose hints the controls line shows, and which of them
+pub struct ScreenControls<'a> {
+    pub active_screen: ActiveScreen,
+    pub allow_delete_resurrectable: bool,
+    pub controls: &'a ControlsConfig,
+}
+
+pub fn screen_hints(
     active_screen: ActiveScreen,
+    allow_delete_resurrectable: bool,
+) -> Vec<(&'static str, &'static str, Message)> {
+    // id (as used in the controls_hints config), shortcut, description
+    match active_screen {
+        ActiveScreen::New => vec![
+            ("current_layout", "<Ctrl l>", Message::HintNewFromCurrentLayout),
+            ("folder_display", "<Alt f>", Message::HintFolderDisplay),
+            ("git_root", "<Alt g>", Message::HintGitRoot),
+        ],
+        ActiveScreen::Attach => vec![
+            ("rename", "<Ctrl r>", Message::HintRename),
+            ("disconnect", "<Ctrl x>", Message::HintDisconnectOthers),
+            ("kill", "<Del>", Message::HintKill),
+            ("kill_all", "<Ctrl d>", Message::HintKillAll),
+            ("force_kill", "<Shift Del>", Message::HintForceKill),
+            ("select_range", "<Shift ↓↑>", Message::HintSelectRange),
+            ("reorder", "<Alt ↓↑>", Message::HintReorder),
+            ("tag", "<Alt t>", Message::HintTagSession),
+            ("command", "<:>", Message::HintCommand),
+            ("attach_with_layout", "<Alt w>", Message::HintWithLayout),
+            ("pick_tab", "<Alt p>", Message::HintPickTab),
+            ("swap", "<Ctrl ^>", Message::HintPreviousSession),
+            ("show_hidden", "<Alt h>", Message::HintShowHidden),
+            ("open_in_new_window", "<Alt o>", Message::HintOpenInNewWindow),
+            ("expansion_level", "<Ctrl 0/1/2>", Message::HintCollapseTabsPanes),
+        ],
+        ActiveScreen::Resurrect => {
+            let mut hints = vec![
+                ("navigate", "<↓↑>", Message::HintNavigate),
+                ("resurrect", "<ENTER>", Message::HintResurrect),
+                ("rename", "<Ctrl r>", Message::HintRename),
+                ("resurrect_with_layout", "<Ctrl l>", Message::HintWithLayout),
+                ("recently_killed", "<Ctrl f>", Message::HintRecentlyKilled),
+            ];
+            if allow_delete_resurrectable {
+                hints.push(("delete", "<DEL>", Message::HintDelete));
+                hints.push(("delete_all", "<Ctrl d>", Message::HintDeleteAll));
+            }
+            hints
+        },
+    }
+}
+
+pub fn render_controls_line(
+    language: Language,
+    screen_controls: ScreenControls,
     max_cols: usize,
     colors: Colors,
     x: usize,
     y: usize,
 ) {
-    match active_screen {
-        ActiveScreen::New => {
-            if max_cols >= 50 {
-                print!(
-                    "\u{1b}[m\u{1b}[{y};{x}H\u{1b}[1mHelp: Fill in the form to start a new session."
-                );
-            }
-        },
-        ActiveScreen::Attach => {
-            let rename = colors.shortcuts("<Ctrl r>");
-            let rename_text = colors.bold("Rename");
-            let disconnect = colors.shortcuts("<Ctrl x>");
-            let disconnect_text = colors.bold("Disconnect others");
-            let kill = colors.shortcuts("<Del>");
-            let kill_text = colors.bold("Kill");
-            let kill_all = colors.shortcuts("<Ctrl d>");
-            let kill_all_text = colors.bold("Kill all");
-
-            if max_cols > 90 {
-                print!(
-                    "\u{1b}[m\u{1b}[{y};{x}HHelp: {rename} - {rename_text}, {disconnect} - {disconnect_text}, {kill} - {kill_text}, {kill_all} - {kill_all_text}"
-                );
-            } else if max_cols >= 28 {
-                print!("\u{1b}[m\u{1b}[{y};{x}H{rename}/{disconnect}/{kill}/{kill_all}");
+    let ScreenControls {
+        active_screen,
+        allow_delete_resurrectable,
+        controls,
+    } = screen_controls;
+    let available_hints = screen_hints(active_screen, allow_delete_resurrectable);
+    let hints = controls.chosen_hints(available_hints);
+    let help_prefix = match active_screen {
+        ActiveScreen::New => language.text(Message::HelpNewSession),
+        _ => language.text(Message::Help),
+    };
+    let full_line = |with_descriptions: bool| -> Vec<(String, usize)> {
+        // styled piece, width
+        let mut pieces = vec![];
+        if with_descriptions {
+            let styled_prefix = if active_screen == ActiveScreen::New {
+                colors.bold(help_prefix)
+            } else {
+                help_prefix.to_owned()
+            };
+            pieces.push((styled_prefix, help_prefix.chars().count()));
+        }
+        for (i, (_, shortcut, description)) in hints.iter().enumerate() {
+            let description = language.text(*description);
+            let separator = match (with_descriptions, i) {
+                (true, 0) if active_screen == ActiveScreen::New => ", ",
+                (true, 0) => " ",
+                (true, _) => ", ",
+                (false, 0) => "",
+                (false, _) => "/",
+            };
+            if with_descriptions {
+                pieces.push((
+                    format!(
+                        "{}{} - {}",
+                        separator,
+                        colors.shortcuts(shortcut),
+                        colors.bold(description)
+                    ),
+                    separator.chars().count()
+                        + shortcut.chars().count()
+                        + 3
+                        + description.chars().count(),
+                ));
+            } else {
+                pieces.push((
+                    format!("{}{}", separator, colors.shortcuts(shortcut)),
+                    separator.chars().count() + shortcut.chars().count(),
+                ));
             }
-        },
-        ActiveScreen::Resurrect => {
-            let arrows = colors.shortcuts("<↓↑>");
-            let navigate = colors.bold("Navigate");
-            let enter = colors.shortcuts("<ENTER>");
-            let select = colors.bold("Resurrect");
-            let del = colors.shortcuts("<DEL>");
-            let del_text = colors.bold("Delete");
-            let del_all = colors.shortcuts("<Ctrl d>");
-            let del_all_text = colors.bold("Delete all");
-
-            if max_cols > 83 {
-                print!(
-                    "\u{1b}[m\u{1b}[{y};{x}HHelp: {arrows} - {navigate}, {enter} - {select}, {del} - {del_text}, {del_all} - {del_all_text}"
-                );
-            } else if max_cols >= 28 {
-                print!("\u{1b}[m\u{1b}[{y};{x}H{arrows}/{enter}/{del}/{del_all}");
+        }
+        pieces
+    };
+    let line_width = |pieces: &[(String, usize)]| pieces.iter().map(|p| p.1).sum::<usize>();
+    let pieces = match controls.verbosity {
+        ControlsVerbosity::None => return,
+        ControlsVerbosity::Full if line_width(&full_line(true)) <= max_cols => full_line(true),
+        ControlsVerbosity::Full | ControlsVerbosity::Minimal => full_line(false),
+    };
+    let mut line = String::new();
+    let mut width = 0;
+    let piece_count = pieces.len();
+    for (i, (piece, piece_width)) in pieces.into_iter().enumerate() {
+        let is_last = i + 1 == piece_count;
+        let room_needed = if is_last { piece_width } else { piece_width + 1 }; // 1 for the ellipsis
+        if width + room_needed > max_cols {
+            if width < max_cols {
+                line.push('…');
             }
-        },
+            break;
+        }
+        line.push_str(&piece);
+        width += piece_width;
     }
+    print!("\u{1b}[m\u{1b}[{y};{x}H{line}");
 }
 
 // Maps the various prompts and UI elements to the colors to present them with
@@ -1016,10 +2092,63 @@ pub fn render_controls_line(
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Colors {
     pub palette: Styling,
+    pub match_fg: Option<PaletteColor>,
+    pub match_bg: Option<PaletteColor>,
+    pub current_session_style: CurrentSessionStyle,
+}
+
+// how the current session's name is shown in the list, it is always marked as <CURRENT SESSION>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CurrentSessionStyle {
+    #[default]
+    Marker,
+    Dim,
+    Highlight,
+}
+
+impl CurrentSessionStyle {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "marker" => Some(CurrentSessionStyle::Marker),
+            "dim" => Some(CurrentSessionStyle::Dim),
+            "highlight" => Some(CurrentSessionStyle::Highlight),
+            _ => None,
+        }
+    }
 }
 impl Colors {
     pub fn new(palette: Styling) -> Self {
-        Colors { palette }
+        Colors {
+            palette,
+            match_fg: None,
+            match_bg: None,
+            current_session_style: CurrentSessionStyle::default(),
+        }
+    }
+    pub fn with_current_session_style(
+        mut self,
+        current_session_style: CurrentSessionStyle,
+    ) -> Self {
+        self.current_session_style = current_session_style;
+        self
+    }
+    pub fn with_match_colors(
+        mut self,
+        match_fg: Option<PaletteColor>,
+        match_bg: Option<PaletteColor>,
+    ) -> Self {
+        self.match_fg = match_fg;
+        self.match_bg = match_bg;
+        self
+    }
+    pub fn search_match(&self) -> SpanStyle {
+        let foreground = self
+            .match_fg
+            .unwrap_or(self.palette.text_unselected.emphasis_3);
+        match self.match_bg {
+            Some(background) => SpanStyle::ForegroundBackgroundBold(foreground, background),
+            None => SpanStyle::ForegroundBold(foreground),
+        }
     }
     pub fn bold(&self, text: &str) -> String {
         format!("\u{1b}[1m{}\u{1b}[22m", text)
@@ -1048,6 +2177,14 @@ impl Colors {
         self.color(&self.palette.text_unselected.emphasis_0, text)
     }
 
+    pub fn frame(&self, text: &str) -> String {
+        let frame_color = self
+            .palette
+            .frame_unselected
+            .map(|f| f.base)
+            .unwrap_or(self.palette.text_unselected.base);
+        self.color(&frame_color, text)
+    }
     pub fn current_session_marker(&self, text: &str) -> String {
         self.color(&self.palette.text_unselected.emphasis_0, text)
     }
@@ -1064,11 +2201,49 @@ impl Colors {
         self.color(&self.palette.text_unselected.emphasis_1, text)
     }
 
+    pub fn match_hint(&self, text: &str) -> String {
+        self.color(&self.palette.text_unselected.emphasis_1, text)
+    }
+
     pub fn shortcuts(&self, text: &str) -> String {
         self.color(&self.palette.text_unselected.emphasis_3, text)
     }
 }
 
+// accepts either a color name (eg. "red", "bright-blue"), an 8-bit color index (eg. "208") or an
+// rgb hex value (eg. "#ff8800")
+pub fn parse_color(color: &str) -> Option<PaletteColor> {
+    let color = color.trim().to_lowercase();
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() == 6 && hex.is_ascii() {
+            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+            return Some(PaletteColor::Rgb((r, g, b)));
+        }
+        return None;
+    }
+    if let Ok(index) = color.parse::<u8>() {
+        return Some(PaletteColor::EightBit(index));
+    }
+    let (is_bright, name) = match color.strip_prefix("bright-") {
+        Some(name) => (true, name),
+        None => (false, color.as_str()),
+    };
+    let index = match name {
+        "black" => 0,
+        "red" => 1,
+        "green" => 2,
+        "yellow" => 3,
+        "blue" => 4,
+        "magenta" => 5,
+        "cyan" => 6,
+        "white" => 7,
+        _ => return None,
+    };
+    Some(PaletteColor::EightBit(if is_bright { index + 8 } else { index }))
+}
+
 fn truncate_path(path: PathBuf, mut char_count_to_remove: usize) -> String {
     let mut truncated = String::new();
     let component_count = path.iter().count();
@@ -1092,3 +2267,68 @@ fn truncate_path(path: PathBuf, mut char_count_to_remove: usize) -> String {
     }
     truncated
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_around_matches_leaves_a_text_that_fits() {
+        assert_eq!(window_around_matches("api", &[0], 10), ("api".to_owned(), vec![0]));
+    }
+
+    #[test]
+    fn window_around_matches_shows_matches_at_the_end_of_a_long_name() {
+        assert_eq!(
+            window_around_matches("a-very-long-session-name-api", &[25, 26, 27], 10),
+            ("…-name-api".to_owned(), vec![7, 8, 9])
+        );
+    }
+
+    #[test]
+    fn window_around_matches_keeps_the_last_match_when_they_are_spread_too_wide() {
+        assert_eq!(
+            window_around_matches("api-a-very-long-gateway", &[0, 16], 10),
+            ("…y-long-g…".to_owned(), vec![8])
+        );
+    }
+
+    #[test]
+    fn window_around_matches_counts_the_width_of_multibyte_names() {
+        assert_eq!(
+            window_around_matches("日本語のセッション", &[8], 8),
+            ("…ション".to_owned(), vec![3])
+        );
+        // too narrow for the match and an ellipsis, cut from the start instead
+        assert_eq!(window_around_matches("日本語", &[2], 2), ("日".to_owned(), vec![2]));
+    }
+
+    #[test]
+    fn wrap_to_width_breaks_between_words() {
+        assert_eq!(
+            wrap_to_width("kill the current session", 10),
+            vec!["kill the", "current", "session"]
+        );
+        assert_eq!(wrap_to_width("  kill   the  ", 10), vec!["kill the"]);
+    }
+
+    #[test]
+    fn wrap_to_width_breaks_a_path_wider_than_the_line() {
+        assert_eq!(
+            wrap_to_width("open /home/user/projects/api now", 10),
+            vec!["open", "/home/user", "/projects/", "api now"]
+        );
+    }
+
+    #[test]
+    fn wrap_to_width_puts_each_word_on_its_own_line_at_zero_width() {
+        assert_eq!(wrap_to_width("kill the session", 0), vec!["kill", "the", "session"]);
+        assert!(wrap_to_width("", 0).is_empty());
+    }
+
+    #[test]
+    fn wrap_to_width_keeps_a_character_wider_than_the_line() {
+        assert_eq!(wrap_to_width("日本 x", 1), vec!["日本", "x"]);
+        assert_eq!(wrap_to_width("日本語", 3), vec!["日", "本", "語"]);
+    }
+}
@@ -2,7 +2,7 @@ pub mod components;
 pub mod welcome_screen;
 use zellij_tile::prelude::*;
 
-use crate::session_list::{SelectedIndex, SessionList};
+use crate::session_list::{SelectedIndex, SessionList, GRID_CELL_GUTTER, GRID_COLUMN_GAP};
 use components::{
     build_pane_ui_line, build_session_ui_line, build_tab_ui_line, minimize_lines, Colors,
     LineToRender,
@@ -67,16 +67,89 @@ macro_rules! render_assets {
 impl SessionList {
     pub fn render(&self, max_rows: usize, max_cols: usize, colors: Colors) -> Vec<LineToRender> {
         if self.is_searching {
-            self.render_search_results(max_rows, max_cols)
+            self.render_search_results(max_rows, max_cols, colors)
+        } else if let Some(columns) = self.grid_columns() {
+            self.render_grid(columns, max_rows, max_cols, colors)
         } else {
             self.render_list(max_rows, max_cols, colors)
         }
     }
-    fn render_search_results(&self, max_rows: usize, max_cols: usize) -> Vec<LineToRender> {
+    pub fn scroll_position(&self, max_rows: usize) -> Option<(usize, usize, usize)> {
+        // first visible line, visible line count, total line count - only when the list overflows
+        if self.is_searching {
+            // search results are always rendered from the top of their page
+            let (page_start, page_end) = self.search_results_page();
+            let total_line_count = self
+                .search_results
+                .iter()
+                .fold(0, |acc, r| acc + r.lines_to_render());
+            if total_line_count <= max_rows {
+                return None;
+            }
+            let first_visible_line = self.search_results[..page_start]
+                .iter()
+                .fold(0, |acc, r| acc + r.lines_to_render());
+            let mut visible_line_count = 0;
+            for result in &self.search_results[page_start..page_end] {
+                if visible_line_count + result.lines_to_render() > max_rows {
+                    break;
+                }
+                visible_line_count += result.lines_to_render();
+            }
+            Some((first_visible_line, visible_line_count, total_line_count))
+        } else if let Some(columns) = self.grid_columns() {
+            let (first_visible_row, row_count) = self.grid_rows(columns, max_rows);
+            if row_count <= max_rows {
+                return None;
+            }
+            Some((first_visible_row, max_rows, row_count))
+        } else {
+            let max_rows = max_rows.saturating_sub(self.group_header_rows());
+            let total_line_count = self.total_lines_to_render();
+            if total_line_count <= max_rows {
+                return None;
+            }
+            // same window as render_sessions, every session above it takes one line
+            let (first_visible_session, _, _, _) = minimize_lines(
+                self.session_ui_infos.len(),
+                total_line_count - max_rows,
+                self.selected_index.0,
+            );
+            Some((first_visible_session, max_rows, total_line_count))
+        }
+    }
+    fn render_search_results(
+        &self,
+        max_rows: usize,
+        max_cols: usize,
+        colors: Colors,
+    ) -> Vec<LineToRender> {
         let mut lines_to_render = vec![];
-        for (i, result) in self.search_results.iter().enumerate() {
+        let (page_start, page_end) = self.search_results_page();
+        let results_after_page = self.search_results.len().saturating_sub(page_end);
+        // room for the "more" line
+        let max_rows = if results_after_page > 0 {
+            max_rows.saturating_sub(1)
+        } else {
+            max_rows
+        };
+        for (i, result) in self
+            .search_results
+            .iter()
+            .enumerate()
+            .take(page_end)
+            .skip(page_start)
+        {
             if lines_to_render.len() + result.lines_to_render() <= max_rows {
                 let mut result_lines = result.render(max_cols);
+                if self.show_relative_line_numbers {
+                    // movement is by result, so only the first line of each result is numbered
+                    if let (Some(selected_search_index), Some(first_line)) =
+                        (self.selected_search_index, result_lines.first_mut())
+                    {
+                        first_line.set_relative_line_number(i.abs_diff(selected_search_index));
+                    }
+                }
                 if Some(i) == self.selected_search_index {
                     let mut render_arrows = true;
                     for line_to_render in result_lines.iter_mut() {
@@ -89,17 +162,71 @@ impl SessionList {
                 break;
             }
         }
+        if results_after_page > 0 {
+            let mut more_line = LineToRender::new(colors);
+            more_line.append(&colors.match_hint(&format!(
+                "… {} more, refine search",
+                results_after_page
+            )));
+            lines_to_render.push(more_line);
+        }
+        lines_to_render
+    }
+    fn grid_rows(&self, columns: usize, max_rows: usize) -> (usize, usize) {
+        // first visible row, row count - the selected row is kept in the middle when scrolling
+        let row_count = self.session_ui_infos.len().div_ceil(columns);
+        let selected_row = self.selected_index.0.map(|i| i / columns).unwrap_or(0);
+        let first_visible_row = selected_row
+            .saturating_sub(max_rows / 2)
+            .min(row_count.saturating_sub(max_rows));
+        (first_visible_row, row_count)
+    }
+    fn render_grid(
+        &self,
+        columns: usize,
+        max_rows: usize,
+        max_cols: usize,
+        colors: Colors,
+    ) -> Vec<LineToRender> {
+        let cell_width = max_cols / columns;
+        let (first_visible_row, row_count) = self.grid_rows(columns, max_rows);
+        let mut lines_to_render = vec![];
+        for row in first_visible_row..row_count.min(first_visible_row + max_rows) {
+            let cells = self
+                .session_ui_infos
+                .iter()
+                .enumerate()
+                .skip(row * columns)
+                .take(columns)
+                .map(|(i, session_ui_info)| {
+                    session_ui_info
+                        .as_line_to_render(
+                            0,
+                            cell_width.saturating_sub(GRID_CELL_GUTTER + GRID_COLUMN_GAP),
+                            colors,
+                            false,
+                        )
+                        .render_as_grid_cell(
+                            self.selected_index.0 == Some(i),
+                            self.is_in_selection_range(i),
+                            cell_width,
+                        )
+                })
+                .collect();
+            lines_to_render.push(LineToRender::from_grid_cells(cells, colors));
+        }
         lines_to_render
     }
     fn render_list(&self, max_rows: usize, max_cols: usize, colors: Colors) -> Vec<LineToRender> {
+        let max_rows = max_rows.saturating_sub(self.group_header_rows());
         let mut lines_to_render_until_selected = vec![];
         let mut lines_to_render_after_selected = vec![];
         let total_lines_to_render = self.total_lines_to_render();
-        let line_count_to_remove = total_lines_to_render.saturating_sub(max_rows);
+        let total_line_count_to_remove = total_lines_to_render.saturating_sub(max_rows);
         let line_count_to_remove = self.render_sessions(
             &mut lines_to_render_until_selected,
             &mut lines_to_render_after_selected,
-            line_count_to_remove,
+            total_line_count_to_remove,
             max_cols,
             colors,
         );
@@ -119,8 +246,67 @@ impl SessionList {
         );
         let mut lines_to_render = lines_to_render_until_selected;
         lines_to_render.append(&mut lines_to_render_after_selected);
+        if self.selection_range().is_some() {
+            // a range only exists while no tab is selected, so every line is a session
+            let (first_visible_session, _, _, _) = minimize_lines(
+                self.session_ui_infos.len(),
+                total_line_count_to_remove,
+                self.selected_index.0,
+            );
+            for (i, line) in lines_to_render.iter_mut().enumerate() {
+                if !line.is_selected() && self.is_in_selection_range(first_visible_session + i) {
+                    line.make_in_selection_range();
+                }
+            }
+        }
+        if self.show_relative_line_numbers {
+            if let Some(selected_line) = lines_to_render.iter().position(|l| l.is_selected()) {
+                for (i, line) in lines_to_render.iter_mut().enumerate() {
+                    line.set_relative_line_number(i.abs_diff(selected_line));
+                }
+            }
+        }
+        if self.group_header_rows() > 0 {
+            lines_to_render = self.with_group_headers(lines_to_render, colors);
+        }
         lines_to_render
     }
+    fn group_header_rows(&self) -> usize {
+        // a single group (eg. only local sessions) is not worth a header
+        let mut origins = vec![];
+        for session_ui_info in &self.session_ui_infos {
+            if !origins.contains(&session_ui_info.origin()) {
+                origins.push(session_ui_info.origin());
+            }
+        }
+        if origins.len() > 1 {
+            origins.len()
+        } else {
+            0
+        }
+    }
+    fn with_group_headers(&self, lines: Vec<LineToRender>, colors: Colors) -> Vec<LineToRender> {
+        // sessions are already sorted by origin, so each group starts at its first session line
+        let mut lines_with_headers = vec![];
+        let mut current_origin = None;
+        for line in lines {
+            if let Some(origin) = line.session_origin().filter(|o| Some(*o) != current_origin) {
+                let label = match (origin, &self.remote_host) {
+                    (SessionOrigin::Local, _) => "Local".to_owned(),
+                    (SessionOrigin::Remote, Some(remote_host)) => {
+                        format!("Remote ({})", remote_host)
+                    },
+                    (SessionOrigin::Remote, None) => "Remote".to_owned(),
+                };
+                let mut header = LineToRender::new(colors);
+                header.append(&colors.bold(&label));
+                lines_with_headers.push(header);
+                current_origin = Some(origin);
+            }
+            lines_with_headers.push(line);
+        }
+        lines_with_headers
+    }
     fn render_sessions(
         &self,
         to_render_until_selected: &mut Vec<LineToRender>,
@@ -225,12 +411,25 @@ impl SessionList {
     }
 }
 
+// where a session runs, the list is grouped by it once there is more than one origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionOrigin {
+    Local,
+    Remote, // listed through the remote_sessions pipe, attached to through the remote url
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionUiInfo {
     pub name: String,
     pub tabs: Vec<TabUiInfo>,
     pub connected_users: usize,
     pub is_current_session: bool,
+    pub cwd: Option<String>,
+    pub is_remote: bool,
+    pub web_clients_allowed: bool,
+    pub activity: Option<Vec<usize>>, // changes per recent session update, when shown
+    pub tags: Vec<String>,
+    pub is_hidden: bool, // only listed with show_hidden, eg. the welcome screen's own session
 }
 
 impl SessionUiInfo {
@@ -244,8 +443,50 @@ impl SessionUiInfo {
                 .collect(),
             connected_users: session_info.connected_clients,
             is_current_session: session_info.is_current_session,
+            cwd: None, // not part of SessionInfo, fetched separately when expanded
+            is_remote: false,
+            web_clients_allowed: session_info.web_clients_allowed,
+            activity: None, // tracked across updates by the session list
+            tags: vec![],   // filled in by the session list, by name
+            is_hidden: false,
+        }
+    }
+    pub fn remote(name: &str) -> Self {
+        // we only know the names of remote sessions
+        SessionUiInfo {
+            name: name.to_owned(),
+            tabs: vec![],
+            connected_users: 0,
+            is_current_session: false,
+            cwd: None,
+            is_remote: true,
+            web_clients_allowed: false,
+            activity: None,
+            tags: vec![],
+            is_hidden: false,
         }
     }
+    pub fn origin(&self) -> SessionOrigin {
+        if self.is_remote {
+            SessionOrigin::Remote
+        } else {
+            SessionOrigin::Local
+        }
+    }
+    pub fn to_layout_kdl(&self) -> String {
+        // we only know the names of things, so commands and pane sizes are not part of the
+        // layout and plugin panes (whose location we don't know) are left out
+        let mut kdl = String::from("layout {\n");
+        for tab in &self.tabs {
+            kdl.push_str(&format!("    tab name=\"{}\" {{\n", kdl_escape(&tab.name)));
+            for pane in tab.panes.iter().filter(|p| !p.is_plugin) {
+                kdl.push_str(&format!("        pane name=\"{}\"\n", kdl_escape(&pane.name)));
+            }
+            kdl.push_str("    }\n");
+        }
+        kdl.push_str("}\n");
+        kdl
+    }
     pub fn line_count(&self, selected_index: &SelectedIndex) -> usize {
         let mut line_count = 1; // self
         if selected_index.tabs_are_visible() {
@@ -267,6 +508,10 @@ impl SessionUiInfo {
         }
         line_count
     }
+    pub fn line_width(&self, colors: Colors) -> usize {
+        // untruncated, as a collapsed list line
+        self.as_line_to_render(0, usize::MAX, colors, false).width()
+    }
     fn as_line_to_render(
         &self,
         _session_index: u8,
@@ -279,10 +524,15 @@ impl SessionUiInfo {
         for span in ui_spans {
             span.render(None, &mut line_to_render, &mut max_cols);
         }
+        line_to_render.set_session_origin(self.origin());
         line_to_render
     }
 }
 
+fn kdl_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 #[derive(Debug, Clone)]
 pub struct TabUiInfo {
     pub name: String,
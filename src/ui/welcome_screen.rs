@@ -1,3 +1,9 @@
+use zellij_tile::prelude::*;
+
+use crate::strings::Language;
+use crate::ui::components::{screen_hints, ScreenLabels};
+use crate::ActiveScreen;
+
 static BANNER: &str = "
 ██╗  ██╗██╗    ███████╗██████╗  ██████╗ ███╗   ███╗    ███████╗███████╗██╗     ██╗     ██╗     ██╗██╗
 ██║  ██║██║    ██╔════╝██╔══██╗██╔═══██╗████╗ ████║    ╚══███╔╝██╔════╝██║     ██║     ██║     ██║██║
@@ -54,7 +60,68 @@ pub fn render_banner(x: usize, y: usize, rows: usize, cols: usize) {
     }
 }
 
-pub fn render_welcome_boundaries(rows: usize, cols: usize) {
+const CHEATSHEET_ACTIONS_PER_SCREEN: usize = 4;
+
+// the first few controls of each screen, shown right of the main menu (welcome_cheatsheet config)
+pub struct CheatsheetSection {
+    title: String,
+    actions: Vec<(&'static str, &'static str)>, // shortcut, description
+}
+
+pub fn cheatsheet_sections(
+    language: Language,
+    screens: &[ActiveScreen],
+    allow_delete_resurrectable: bool,
+    screen_labels: &ScreenLabels,
+) -> Vec<CheatsheetSection> {
+    screens
+        .iter()
+        .map(|screen| CheatsheetSection {
+            title: screen_labels.label(*screen, true).to_owned(),
+            actions: screen_hints(*screen, allow_delete_resurrectable)
+                .into_iter()
+                .take(CHEATSHEET_ACTIONS_PER_SCREEN)
+                .map(|(_, shortcut, description)| (shortcut, language.text(description)))
+                .collect(),
+        })
+        .collect()
+}
+
+fn cheatsheet_lines(cheatsheet: &[CheatsheetSection]) -> Vec<Text> {
+    let mut lines = vec![];
+    for (i, section) in cheatsheet.iter().enumerate() {
+        if i > 0 {
+            lines.push(Text::new(" "));
+        }
+        lines.push(Text::new(&section.title).color_range(2, ..));
+        for (shortcut, description) in &section.actions {
+            lines.push(
+                Text::new(format!("{} {}", shortcut, description))
+                    .color_range(3, ..shortcut.chars().count()),
+            );
+        }
+    }
+    lines
+}
+
+fn cheatsheet_width(cheatsheet: &[CheatsheetSection]) -> usize {
+    cheatsheet
+        .iter()
+        .flat_map(|section| {
+            std::iter::once(section.title.chars().count()).chain(
+                section
+                    .actions
+                    .iter()
+                    .map(|(shortcut, description)| {
+                        shortcut.chars().count() + 1 + description.chars().count()
+                    }),
+            )
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn render_welcome_boundaries(rows: usize, cols: usize, cheatsheet: &[CheatsheetSection]) {
     let width_of_main_menu = std::cmp::min(cols, 101);
     let has_room_for_logos = cols.saturating_sub(width_of_main_menu) > 100;
     let left_boundary_x = (cols.saturating_sub(width_of_main_menu) as f64 / 2.0).floor() as usize;
@@ -119,6 +186,15 @@ pub fn render_welcome_boundaries(rows: usize, cols: usize) {
             }
         }
     }
+    // the cheatsheet goes right of the main menu, between its top and bottom boundaries and
+    // around the middle row (which might have the connectors to the logos), in place of the
+    // right logo - if it does not fit it is left out
+    let cheatsheet_x = right_boundary_x + 3;
+    let cheatsheet_rows = rows.saturating_sub(y_starting_point + 3); // boundaries, middle row
+    let cheatsheet_lines = cheatsheet_lines(cheatsheet);
+    let has_cheatsheet = !cheatsheet.is_empty()
+        && cheatsheet_lines.len() <= cheatsheet_rows
+        && cheatsheet_x + cheatsheet_width(cheatsheet) < cols;
     if rows.saturating_sub(y_starting_point) > 25 && has_room_for_logos {
         for (i, line) in LOGO.lines().enumerate() {
             print!(
@@ -128,13 +204,25 @@ pub fn render_welcome_boundaries(rows: usize, cols: usize) {
                 line
             );
         }
-        for (i, line) in LOGO.lines().enumerate() {
-            print!(
-                "\u{1b}[{};{}H{}",
-                middle_row.saturating_sub(12) + i,
-                cols.saturating_sub(47),
-                line
-            );
+        if !has_cheatsheet {
+            for (i, line) in LOGO.lines().enumerate() {
+                print!(
+                    "\u{1b}[{};{}H{}",
+                    middle_row.saturating_sub(12) + i,
+                    cols.saturating_sub(47),
+                    line
+                );
+            }
+        }
+    }
+    if has_cheatsheet {
+        let mut line_y = y_starting_point + 1;
+        for line in cheatsheet_lines {
+            if line_y == middle_row {
+                line_y += 1;
+            }
+            print_text_with_coordinates(line, cheatsheet_x, line_y, None, None);
+            line_y += 1;
         }
     }
 }